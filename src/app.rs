@@ -1,6 +1,7 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use crate::components::simulation::SimulationWorkerProvider;
 use crate::components::theme_toggle::ThemeToggle;
 use crate::router::{switch, Route};
 
@@ -10,7 +11,9 @@ pub fn app() -> Html {
       <BrowserRouter>
           <Navbar />
           <main class="p-4">
-            <Switch<Route> render={switch} />
+            <SimulationWorkerProvider>
+              <Switch<Route> render={switch} />
+            </SimulationWorkerProvider>
           </main>
       </BrowserRouter>
     }
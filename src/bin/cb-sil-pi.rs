@@ -0,0 +1,43 @@
+//! Stand-in for firmware in a software-in-the-loop simulation: a PI controller
+//! speaking the line protocol of `cb_simulator_yew::control::external`.
+//!
+//! ```text
+//! cb-sil-pi [<kp> [<ki>]]
+//! ```
+//!
+//! With `kp = 1` and `ki = 0` it returns the control error, which makes it an echo
+//! of the inputs for checking the protocol.
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).map(|a| a.parse::<f64>());
+    let (kp, ki) = match (args.next().unwrap_or(Ok(1.0)), args.next().unwrap_or(Ok(0.0))) {
+        (Ok(kp), Ok(ki)) => (kp, ki),
+        _ => {
+            eprintln!("usage: cb-sil-pi [<kp> [<ki>]]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut integral = 0.0;
+    let mut previous_time: Option<f64> = None;
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        let values: Vec<f64> = line.split_whitespace().filter_map(|v| v.parse::<f64>().ok()).collect();
+        let [setpoint, process_variable, time] = values[..] else {
+            eprintln!("cb-sil-pi: expected '<r> <y> <t>', got '{}'", line);
+            return ExitCode::FAILURE;
+        };
+        let error = setpoint - process_variable;
+        // the interval follows the sampling instants, including jitter
+        let dt = previous_time.map_or(0.0, |previous| time - previous);
+        previous_time = Some(time);
+        integral += ki * error * dt;
+        if writeln!(stdout, "{}", kp * error + integral).and_then(|_| stdout.flush()).is_err() {
+            break;
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,196 @@
+//! Headless simulator: runs the control loop of a project file and writes the signals,
+//! or checks a directory of scenarios against their golden results.
+//! A project starting an external controller program only runs with `--allow-external`.
+//!
+//! ```text
+//! cb-sim <project.json> [--format csv|json] [--output <file>] [--integral] [--allow-external]
+//! cb-sim --check <directory> [--bless] [--absolute <tolerance>] [--relative <tolerance>] [--allow-external]
+//! ```
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use cb_simulator_yew::simulation::{Golden, LoopResult, Project, Report, Tolerance};
+
+const USAGE: &str = "usage: cb-sim <project.json> [--format csv|json] [--output <file>] [--integral] [--allow-external]
+       cb-sim --check <directory> [--bless] [--absolute <tolerance>] [--relative <tolerance>] [--allow-external]";
+
+/// Suffix of the golden result next to the scenario `<name>.json`
+const GOLDEN_SUFFIX: &str = ".golden.csv";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(Debug)]
+struct Arguments {
+    project: Option<String>,
+    format: Format,
+    output: Option<String>,
+    integral: bool,
+    /// Directory of the scenarios to check
+    check: Option<String>,
+    /// Stores the results as new golden results instead of comparing
+    bless: bool,
+    tolerance: Tolerance,
+    /// Permits projects to start the external controller program they name
+    allow_external: bool,
+}
+
+fn number(args: &mut impl Iterator<Item = String>, option: &str) -> Result<f64, String> {
+    args.next()
+        .and_then(|v| v.parse::<f64>().ok())
+        .ok_or(format!("{} needs a number", option))
+}
+
+fn parse_arguments(mut args: impl Iterator<Item = String>) -> Result<Arguments, String> {
+    let mut arguments = Arguments {
+        project: None,
+        format: Format::Csv,
+        output: None,
+        integral: false,
+        check: None,
+        bless: false,
+        tolerance: Tolerance::default(),
+        allow_external: false,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                arguments.format = match args.next().as_deref() {
+                    Some("csv") => Format::Csv,
+                    Some("json") => Format::Json,
+                    other => return Err(format!("unknown format {:?}", other.unwrap_or_default())),
+                }
+            }
+            "--output" => arguments.output = Some(args.next().ok_or("--output needs a file name")?),
+            "--integral" => arguments.integral = true,
+            "--check" => arguments.check = Some(args.next().ok_or("--check needs a directory")?),
+            "--bless" => arguments.bless = true,
+            "--allow-external" => arguments.allow_external = true,
+            "--absolute" => arguments.tolerance = arguments.tolerance.set_absolute(number(&mut args, &arg)?),
+            "--relative" => arguments.tolerance = arguments.tolerance.set_relative(number(&mut args, &arg)?),
+            "--help" | "-h" => return Err(USAGE.to_owned()),
+            _ if arguments.project.is_none() && !arg.starts_with("--") => arguments.project = Some(arg),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+    if arguments.project.is_some() == arguments.check.is_some() {
+        return Err(USAGE.to_owned());
+    }
+    Ok(arguments)
+}
+
+fn simulate(project: &Path, integral: bool, allow_external: bool) -> Result<LoopResult, String> {
+    let text = fs::read_to_string(project).map_err(|e| format!("cannot read {}: {}", project.display(), e))?;
+    let description: Project =
+        serde_json::from_str(&text).map_err(|e| format!("invalid project {}: {}", project.display(), e))?;
+    if let Some(external) = description.external_controller.as_ref().filter(|_| !allow_external) {
+        return Err(format!(
+            "project {} starts the external program {}, pass --allow-external to run it",
+            project.display(),
+            external.program
+        ));
+    }
+    let setup = description
+        .loop_setup()
+        .map_err(|e| format!("invalid project {}: {}", project.display(), e))?;
+    let setup = setup.set_record_integral(integral || description.record_integral);
+    Ok(setup.simulate())
+}
+
+/// Column name to values
+fn to_json(result: &LoopResult) -> Result<String, serde_json::Error> {
+    let columns: serde_json::Map<String, serde_json::Value> = result
+        .columns()
+        .into_iter()
+        .map(|(name, values)| (name.to_owned(), values.iter().copied().collect::<Vec<f64>>().into()))
+        .collect();
+    serde_json::to_string_pretty(&columns)
+}
+
+fn run(project: &str, arguments: &Arguments) -> Result<(), String> {
+    let result = simulate(Path::new(project), arguments.integral, arguments.allow_external)?;
+    let output = match arguments.format {
+        Format::Csv => result.to_csv(),
+        Format::Json => to_json(&result).map_err(|e| e.to_string())?,
+    };
+    match &arguments.output {
+        Some(file) => fs::write(file, output).map_err(|e| format!("cannot write {}: {}", file, e)),
+        None => {
+            print!("{}", output);
+            Ok(())
+        }
+    }
+}
+
+/// Simulates each scenario of the directory and compares it with its golden result,
+/// returns if all passed; scenarios without golden result are skipped
+fn check(directory: &str, arguments: &Arguments) -> Result<bool, String> {
+    let mut scenarios: Vec<PathBuf> = fs::read_dir(directory)
+        .map_err(|e| format!("cannot read {}: {}", directory, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == "json"))
+        .collect();
+    scenarios.sort();
+
+    let mut passed = true;
+    for scenario in scenarios {
+        let name = scenario.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let golden_path = scenario.with_file_name(format!("{}{}", name, GOLDEN_SUFFIX));
+        let result = match simulate(&scenario, false, arguments.allow_external) {
+            Ok(result) => result,
+            Err(message) => {
+                println!("{}: FAILED\n  {}", name, message);
+                passed = false;
+                continue;
+            }
+        };
+        if arguments.bless {
+            fs::write(&golden_path, result.to_csv())
+                .map_err(|e| format!("cannot write {}: {}", golden_path.display(), e))?;
+            println!("{}: golden result written to {}", name, golden_path.display());
+            continue;
+        }
+        if !golden_path.exists() {
+            println!("{}: SKIPPED\n  no golden result {}, create it with --bless", name, golden_path.display());
+            continue;
+        }
+        let reference = match fs::read_to_string(&golden_path) {
+            Ok(text) => Golden::from_csv(&text).map_err(|e| format!("invalid golden result {}: {}", golden_path.display(), e)),
+            Err(e) => Err(format!("cannot read {}: {}", golden_path.display(), e)),
+        };
+        match reference {
+            Ok(reference) => {
+                let report = Report::compare(&name, &reference, &Golden::from_result(&result), arguments.tolerance);
+                passed &= report.passed();
+                print!("{}", report);
+            }
+            Err(message) => {
+                println!("{}: FAILED\n  {}", name, message);
+                passed = false;
+            }
+        }
+    }
+    Ok(passed)
+}
+
+fn main() -> ExitCode {
+    let result = parse_arguments(std::env::args().skip(1)).and_then(|arguments| {
+        match (&arguments.project, &arguments.check) {
+            (Some(project), _) => run(project, &arguments).map(|_| true),
+            (None, Some(directory)) => check(directory, &arguments),
+            (None, None) => Err(USAGE.to_owned()),
+        }
+    });
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
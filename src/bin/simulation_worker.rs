@@ -0,0 +1,11 @@
+//! Entry point of the Web Worker running the simulations, see
+//! `cb_simulator_yew::simulation::worker`.
+use gloo::worker::Registrable;
+
+use cb_simulator_yew::simulation::worker::SimulationWorker;
+
+fn main() {
+    console_error_panic_hook::set_once();
+    wasm_logger::init(wasm_logger::Config::default());
+    SimulationWorker::registrar().register();
+}
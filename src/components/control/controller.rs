@@ -3,19 +3,38 @@ use accordion_rs::Size;
 use log::{debug, info};
 use yew::prelude::*;
 
-use cb_controller::pid::{PidController, PidCoreBuilder, PidOutputLimit, PidSetpointRange};
+use cb_controller::pid::{PidCoreBuilder, PidOutputLimit, PidSetpointRange};
 
+use crate::control::gain_schedule::GainScheduleSetup;
+use crate::control::mpc::MpcSetup;
+use crate::control::pid_law::{AntiWindup, PidOptions};
+use crate::control::pid_setup::PidSetup;
+use crate::control::smith_predictor::SmithPredictorSetup;
+use crate::control::state_feedback::StateFeedbackSetup;
+use crate::plant::named_element::NamedElement;
+
+use super::gain_schedule::GainScheduleDialog;
+use super::mpc::MpcDialog;
 use super::pid_core::PidControllerDialog;
 use super::pid_output::PidControllerOutputDialog;
 use super::pid_input::PidControllerInputDialog;
 use super::pid_dead_band::PidControllerDeadBandDialog;
+use super::pid_design::PidDesignDialog;
+use super::pid_form::PidFormDialog;
+use super::pid_two_dof::PidTwoDofDialog;
+use super::smith_predictor::SmithPredictorDialog;
+use super::state_feedback::StateFeedbackDialog;
 
 #[derive(Properties, PartialEq)]
 pub struct AccordeonControllerProps {
-    // pub controller: PidController<f64>,
     pub sampling_interval: f64,
-    pub update: Callback<PidController<f64>>,
-
+    pub update: Callback<PidSetup>,
+    /// Distinguishes several controllers on one page, e.g. outer and inner loop of a cascade
+    #[prop_or(AttrValue::from("PID Controller"))]
+    pub name: AttrValue,
+    /// Candidates for controller internal models
+    #[prop_or_default]
+    pub elements: Vec<NamedElement<f64>>,
 }
 
 #[function_component(AccordeonController)]
@@ -27,92 +46,181 @@ pub fn accordeon_elements(props: &AccordeonControllerProps) -> Html {
     let output_config: UseStateHandle<Option<PidOutputLimit<f64>>>   = use_state ( || None);
     let input_config: UseStateHandle<Option<PidSetpointRange<f64>>>   = use_state ( || None);
     let dead_band_config: UseStateHandle<Option<f64>>   = use_state ( || None);
+    let smith_config: UseStateHandle<Option<SmithPredictorSetup>>   = use_state ( || None);
+    let options_config: UseStateHandle<PidOptions>   = use_state (PidOptions::default);
+    let schedule_config: UseStateHandle<Option<GainScheduleSetup>>   = use_state ( || None);
+    let state_feedback_config: UseStateHandle<Option<StateFeedbackSetup>>   = use_state ( || None);
+    let mpc_config: UseStateHandle<Option<MpcSetup>>   = use_state ( || None);
+
+    // Combines the recent state of all dialogs
+    let setup = PidSetup::default()
+        .set_core((*core_builder).clone())
+        .set_output_limit((*output_config).clone())
+        .set_setpoint_range((*input_config).clone())
+        .set_dead_band(*dead_band_config)
+        .set_options((*options_config).clone())
+        .set_smith_predictor((*smith_config).clone())
+        .set_gain_schedule((*schedule_config).clone())
+        .set_state_feedback((*state_feedback_config).clone())
+        .set_mpc((*mpc_config).clone());
 
     let on_core_update: Callback<PidCoreBuilder<f64>> = {
         let update = props.update.clone();
         let core_builder = core_builder.clone();
-        let output_config = output_config.clone();
+        let setup = setup.clone();
         Callback::from(move |builder: PidCoreBuilder<f64>| {
             info!("AccordeonController Core Builder Update to: {:?}", builder);
-
             core_builder.set(builder.clone());
-            let mut pid = builder.build();
+            let setup = setup.clone().set_core(builder);
+            debug!("Update on PID-Controller {:?}", setup);
+            update.emit(setup);
+        })
+    };
 
-            match (*output_config).clone() {
-                Some(c) => pid.set_output_limit(c),
-                None => pid.reset_output_limit(),
-            }
-            debug!("Update on PID-Controller {:?}", pid);
-            update.emit(pid);
+    // gains entered in another PID form or designed recreate the core dialog with the new values
+    let core_revision = use_state(|| 0_usize);
+    let on_form_update: Callback<PidCoreBuilder<f64>> = {
+        let on_core_update = on_core_update.clone();
+        let core_revision = core_revision.clone();
+        Callback::from(move |builder: PidCoreBuilder<f64>| {
+            core_revision.set(*core_revision + 1);
+            on_core_update.emit(builder);
         })
     };
 
-    if props.sampling_interval != (*core_builder).dt as f64 {
-        let mut cb = (*core_builder).clone();
-        cb.dt = props.sampling_interval as f32;
-        core_builder.clone().set(cb.clone());
-        let pid = cb.build();
-        debug!("Update on sampling interval of PID-Controller {:?}", props.sampling_interval);
-        props.update.emit(pid);
+    // follows the sampling interval of the time range after the render, not during it
+    {
+        let update = props.update.clone();
+        let core_builder = core_builder.clone();
+        let setup = setup.clone();
+        use_effect_with(props.sampling_interval, move |&sampling_interval| {
+            if sampling_interval != (*core_builder).dt as f64 {
+                let setup = setup.set_sampling_interval(sampling_interval);
+                core_builder.set(setup.core.clone());
+                debug!("Update on sampling interval of PID-Controller {:?}", sampling_interval);
+                update.emit(setup);
+            }
+        });
     }
 
     let on_output_update: Callback<Option<PidOutputLimit<f64>>> = {
         let update = props.update.clone();
-        let core_builder = core_builder.clone();
         let output_config = output_config.clone();
+        let setup = setup.clone();
         Callback::from(move |config: Option<PidOutputLimit<f64>>| {
             info!("AccordeonController Output Limit Update to: {:?}", config);
-            let mut pid = (*core_builder).clone().build();
-            match config.clone() {
-                Some(c) => pid.set_output_limit(c),
-                None => pid.reset_output_limit(),
-            }
-            output_config.set(config);
-            debug!("Update on PID-Controller {:?}", pid);
-            update.emit(pid);
+            output_config.set(config.clone());
+            let setup = setup.clone().set_output_limit(config);
+            debug!("Update on PID-Controller {:?}", setup);
+            update.emit(setup);
         })
     };
 
     let on_input_update: Callback<Option<PidSetpointRange<f64>>> = {
         let update = props.update.clone();
-        let core_builder = core_builder.clone();
         let input_config = input_config.clone();
+        let setup = setup.clone();
         Callback::from(move |config: Option<PidSetpointRange<f64>>| {
             info!("AccordeonController Input Range to: {:?}", config);
-            let mut pid = (*core_builder).clone().build();
-            match config.clone() {
-                Some(c) => pid.set_setpoint_range(c),
-                None => pid.reset_setpoint_range(),
-            }
-            input_config.set(config);
-            debug!("Update on PID-Controller {:?}", pid);
-            update.emit(pid);
+            input_config.set(config.clone());
+            let setup = setup.clone().set_setpoint_range(config);
+            debug!("Update on PID-Controller {:?}", setup);
+            update.emit(setup);
         })
     };
 
     let on_dead_band_update: Callback<Option<f64>> = {
         let update = props.update.clone();
-        let core_builder = core_builder.clone();
         let dead_band_config = dead_band_config.clone();
+        let setup = setup.clone();
         Callback::from(move |config: Option<f64>| {
             info!("AccordeonController Dead Band Tolerance to: {:?}", config);
-            let mut pid = (*core_builder).clone().build();
-            match config.clone() {
-                Some(c) => pid.set_dead_band_tolerance(c),
-                None => pid.reset_dead_band_tolerance(),
-            }
             dead_band_config.set(config);
-            debug!("Update on PID-Controller {:?}", pid);
-            update.emit(pid);
+            let setup = setup.clone().set_dead_band(config);
+            debug!("Update on PID-Controller {:?}", setup);
+            update.emit(setup);
+        })
+    };
+
+    let on_options_update: Callback<PidOptions> = {
+        let update = props.update.clone();
+        let options_config = options_config.clone();
+        let setup = setup.clone();
+        Callback::from(move |config: PidOptions| {
+            info!("AccordeonController PID Options to: {:?}", config);
+            options_config.set(config.clone());
+            let setup = setup.clone().set_options(config);
+            debug!("Update on PID-Controller {:?}", setup);
+            update.emit(setup);
+        })
+    };
+
+    let on_anti_windup_update: Callback<AntiWindup> = {
+        let on_options_update = on_options_update.clone();
+        let options = (*options_config).clone();
+        Callback::from(move |anti_windup: AntiWindup| {
+            on_options_update.emit(options.clone().set_anti_windup(anti_windup));
+        })
+    };
+
+    let on_smith_update: Callback<Option<SmithPredictorSetup>> = {
+        let update = props.update.clone();
+        let smith_config = smith_config.clone();
+        let setup = setup.clone();
+        Callback::from(move |config: Option<SmithPredictorSetup>| {
+            info!("AccordeonController Smith Predictor to: {:?}", config);
+            smith_config.set(config.clone());
+            let setup = setup.clone().set_smith_predictor(config);
+            debug!("Update on PID-Controller {:?}", setup);
+            update.emit(setup);
+        })
+    };
+
+    let on_schedule_update: Callback<Option<GainScheduleSetup>> = {
+        let update = props.update.clone();
+        let schedule_config = schedule_config.clone();
+        let setup = setup.clone();
+        Callback::from(move |config: Option<GainScheduleSetup>| {
+            info!("AccordeonController Gain Schedule to: {:?}", config);
+            schedule_config.set(config.clone());
+            let setup = setup.clone().set_gain_schedule(config);
+            debug!("Update on PID-Controller {:?}", setup);
+            update.emit(setup);
+        })
+    };
+
+    let on_state_feedback_update: Callback<Option<StateFeedbackSetup>> = {
+        let update = props.update.clone();
+        let state_feedback_config = state_feedback_config.clone();
+        let setup = setup.clone();
+        Callback::from(move |config: Option<StateFeedbackSetup>| {
+            info!("AccordeonController State Feedback to: {:?}", config);
+            state_feedback_config.set(config.clone());
+            let setup = setup.clone().set_state_feedback(config);
+            debug!("Update on PID-Controller {:?}", setup);
+            update.emit(setup);
+        })
+    };
+
+    let on_mpc_update: Callback<Option<MpcSetup>> = {
+        let update = props.update.clone();
+        let mpc_config = mpc_config.clone();
+        let setup = setup.clone();
+        Callback::from(move |config: Option<MpcSetup>| {
+            info!("AccordeonController MPC to: {:?}", config);
+            mpc_config.set(config.clone());
+            let setup = setup.clone().set_mpc(config);
+            debug!("Update on PID-Controller {:?}", setup);
+            update.emit(setup);
         })
     };
 
     html! {
         <Accordion
             expand={expand}
-            expanded={html! { "PID Controller Parameter" }}
+            expanded={html! { format!("{} Parameter", props.name) }}
             collapsed={html! {<>
-                 { "Set PID Controller Parameter" }
+                 { format!("Set {} Parameter", props.name) }
             </>}}
             size={Size::Custom("auto")}
             class=" p-4 rounded border border-gray-400 dark:border-gray-600"
@@ -125,8 +233,17 @@ pub fn accordeon_elements(props: &AccordeonControllerProps) -> Html {
                         <label class="block text-sm mb-2 form-fieldtext-gray-300 dark:text-gray-700" for="step_function_label"> { "Sampling Interval" } </label>
                         <div id="step_function_label" class="pt-2 text-lg"> { props.sampling_interval.to_string() } </div>
                     </div>
-                    <PidControllerDialog  builder={(*core_builder).clone()} on_update={on_core_update} />
-                    <PidControllerOutputDialog  config={(*output_config).clone()} on_update={on_output_update} />
+                    <PidControllerDialog key={core_revision.to_string()} builder={(*core_builder).clone()} on_update={on_core_update} />
+                    <PidFormDialog builder={(*core_builder).clone()} on_update={on_form_update.clone()} />
+                    <PidDesignDialog builder={(*core_builder).clone()} elements={props.elements.clone()} on_update={on_form_update} />
+                    <PidTwoDofDialog config={(*options_config).clone()} on_update={on_options_update} />
+                    <SmithPredictorDialog config={(*smith_config).clone()} elements={props.elements.clone()} on_update={on_smith_update} />
+                    <GainScheduleDialog config={(*schedule_config).clone()} builder={(*core_builder).clone()} on_update={on_schedule_update} />
+                    <StateFeedbackDialog config={(*state_feedback_config).clone()} sampling_interval={props.sampling_interval}
+                        elements={props.elements.clone()} on_update={on_state_feedback_update} />
+                    <MpcDialog config={(*mpc_config).clone()} elements={props.elements.clone()} on_update={on_mpc_update} />
+                    <PidControllerOutputDialog  config={(*output_config).clone()} on_update={on_output_update}
+                        anti_windup={options_config.anti_windup} on_anti_windup_update={on_anti_windup_update} />
                     <PidControllerInputDialog  config={(*input_config).clone()} on_update={on_input_update} />
                     <PidControllerDeadBandDialog config={(*dead_band_config).clone()} on_update={on_dead_band_update} />
                 </Item>
@@ -0,0 +1,220 @@
+use input_rs::yew::Input;
+use yew::prelude::*;
+use log::debug;
+
+use crate::components::hooks::use_emit_changed;
+use crate::control::feedforward::{FeedforwardSetup, LeadLag};
+
+
+#[derive(Properties, PartialEq)]
+pub struct FeedforwardDialogProps {
+    pub config: FeedforwardSetup,
+    pub on_update: Callback<FeedforwardSetup>,
+}
+
+#[function_component(FeedforwardDialog)]
+pub fn feedforward_dialog(props: &FeedforwardDialogProps) -> Html {
+
+    let on_setpoint_update = {
+        let emitter = props.on_update.clone();
+        let config = props.config.clone();
+        Callback::from(move |filter: Option<LeadLag>| {
+            debug!("Setpoint Feedforward Change {:?} propagate", filter );
+            emitter.emit(config.clone().set_setpoint(filter));
+        })
+    };
+
+    let on_disturbance_update = {
+        let emitter = props.on_update.clone();
+        let config = props.config.clone();
+        Callback::from(move |filter: Option<LeadLag>| {
+            debug!("Disturbance Feedforward Change {:?} propagate", filter );
+            emitter.emit(config.clone().set_disturbance(filter));
+        })
+    };
+
+    html! {
+        <>
+            <LeadLagOptionalDialog label="Setpoint Feedforward"
+                config={props.config.setpoint.clone()}
+                default={LeadLag::default()}
+                on_update={on_setpoint_update} />
+            // a gain of -1 compensates a disturbance at the plant input statically
+            <LeadLagOptionalDialog label="Disturbance Feedforward"
+                config={props.config.disturbance.clone()}
+                default={LeadLag::default().set_gain(-1.0)}
+                on_update={on_disturbance_update} />
+        </>
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct LeadLagOptionalDialogProps {
+    pub label: AttrValue,
+    pub config: Option<LeadLag>,
+    /// Used when the filter gets enabled
+    pub default: LeadLag,
+    pub on_update: Callback<Option<LeadLag>>,
+}
+
+#[function_component(LeadLagOptionalDialog)]
+pub fn lead_lag_optional_dialog(props: &LeadLagOptionalDialogProps) -> Html {
+
+    debug!("LeadLagOptionalDialog - Entry: {:?}", props.config);
+
+    let is_enabled = use_state( || props.config.is_some());
+
+    let enabled_change = {
+        let is_enabled = is_enabled.clone();
+        let emitter = props.on_update.clone();
+        let default = props.config.clone().unwrap_or(props.default.clone());
+        Callback::from(move |e: Event| {
+
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("Feedforward {} Checkbox {}", *is_enabled, input.checked(), );
+
+            is_enabled.set(input.checked());
+            if input.checked() {
+                emitter.emit(Some(default.clone()))
+            } else {
+                emitter.emit(None)
+            }
+        })
+    };
+
+    let filter_change = {
+        let emitter = props.on_update.clone();
+        Callback::from(move |config: LeadLag| {
+            debug!("Lead-Lag Change {:?} propagate", config );
+            emitter.emit(Some(config));
+        })
+    };
+
+    html! {
+        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+            <div class="flex flex-col w-48">
+                <label for="feedforward_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { props.label.clone() }
+                </label>
+                <div id="feedforward_label">
+                    <label class="relative inline-flex items-center cursor-pointer">
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                        <input type="checkbox" checked={*is_enabled} onchange={enabled_change} class="sr-only peer"/>
+                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                    </label>
+                </div>
+            </div>
+
+           if *is_enabled {
+                <LeadLagDialog config={
+                    match props.config.clone() {
+                        Some(c) => c,
+                        None => props.default.clone(),
+                    }
+                } on_update={ filter_change } />
+           }
+        </form>
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct LeadLagDialogProps {
+    pub config: LeadLag,
+    pub on_update: Callback<LeadLag>,
+}
+
+#[function_component(LeadLagDialog)]
+pub fn lead_lag_dialog(props: &LeadLagDialogProps) -> Html {
+
+    fn always_valid(_s: String) -> bool {
+        true
+    }
+
+    fn not_negative_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value >= 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    let filter = props.config.clone();
+    debug!("LeadLagDialog - Entry: {:?}", filter);
+
+    let gain_ref = use_node_ref();
+    let gain_handle =  use_state(|| filter.gain.to_string());
+    let gain_valid_handle = use_state(|| true);
+
+    let lead_time_ref = use_node_ref();
+    let lead_time_handle =  use_state(|| filter.lead_time.to_string());
+    let lead_time_valid_handle = use_state(|| true);
+
+    let lag_time_ref = use_node_ref();
+    let lag_time_handle =  use_state(|| filter.lag_time.to_string());
+    let lag_time_valid_handle = use_state(|| true);
+
+    let updated = filter.clone()
+        .set_gain((*gain_handle).parse::<f64>().unwrap_or_default())
+        .set_lead_time((*lead_time_handle).parse::<f64>().unwrap_or_default())
+        .set_lag_time((*lag_time_handle).parse::<f64>().unwrap_or_default());
+    use_emit_changed(updated, filter, props.on_update.clone());
+
+    html! {
+        <div class="flex flex-row">
+
+            <Input
+                r#type="number"
+                name="gain"
+                r#ref={gain_ref}
+                handle={gain_handle}
+                valid_handle={gain_valid_handle}
+                validate_function={always_valid}
+
+                label="Gain K"
+                required={true}
+                error_message="Must be a number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+
+            <Input
+                r#type="number"
+                name="lead_time"
+                r#ref={lead_time_ref}
+                handle={lead_time_handle}
+                valid_handle={lead_time_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Lead Time [ms]"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+
+            <Input
+                r#type="number"
+                name="lag_time"
+                r#ref={lag_time_ref}
+                handle={lag_time_handle}
+                valid_handle={lag_time_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Lag Time [ms]"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+
+        </div>
+    }
+}
@@ -0,0 +1,344 @@
+use input_rs::yew::Input;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use log::debug;
+
+use cb_controller::pid::PidCoreBuilder;
+
+use crate::components::hooks::use_emit_changed;
+use crate::control::gain_schedule::{GainScheduleRow, GainScheduleSetup, SchedulingVariable};
+
+
+#[derive(Properties, PartialEq)]
+pub struct GainScheduleOptionalDialogProps {
+    pub config: Option<GainScheduleSetup>,
+    /// The gains of the core are the initial rows of the table
+    pub builder: PidCoreBuilder<f64>,
+    pub on_update: Callback<Option<GainScheduleSetup>>,
+}
+
+
+#[function_component(GainScheduleDialog)]
+pub fn gain_schedule_dialog(props: &GainScheduleOptionalDialogProps) -> Html {
+
+    debug!("GainScheduleDialog - Entry: {:?}", props.config);
+
+    let is_scheduled = use_state( || props.config.is_some());
+
+    let scheduled_change = {
+        let is_scheduled = is_scheduled.clone();
+        let emitter = props.on_update.clone();
+        let initial = props.config.clone().unwrap_or_else(|| {
+            let (kp, ki, kd) = (props.builder.kp, props.builder.get_ki(), props.builder.get_kd());
+            GainScheduleSetup::default()
+                .set_interpolate(true)
+                .set_rows(vec![GainScheduleRow::new(0.0, kp, ki, kd), GainScheduleRow::new(1.0, kp, ki, kd)])
+        });
+        Callback::from(move |e: Event| {
+
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("Gain Schedule {} Checkbox {}", *is_scheduled, input.checked(), );
+
+            is_scheduled.set(input.checked());
+            if input.checked() {
+                emitter.emit(Some(initial.clone()))
+            } else {
+                emitter.emit(None)
+            }
+        })
+    };
+
+    let table_change = {
+        let emitter = props.on_update.clone();
+        Callback::from(move |config: GainScheduleSetup| {
+            debug!("Gain Schedule Change {:?} propagate", config );
+            emitter.emit(Some(config));
+        })
+    };
+
+    html! {
+        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+            <div class="flex flex-col w-40">
+                <label for="gain_schedule_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { "Gain Schedule" }
+                </label>
+                <div id="gain_schedule_label">
+                    <label class="relative inline-flex items-center cursor-pointer">
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                        <input type="checkbox" checked={*is_scheduled} onchange={scheduled_change} class="sr-only peer"/>
+                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                    </label>
+                </div>
+            </div>
+
+           if *is_scheduled {
+                <GainScheduleTableDialog config={
+                    match props.config.clone() {
+                        Some(c) => c,
+                        None => GainScheduleSetup::default(),
+                    }
+                } on_update={ table_change } />
+           }
+        </form>
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct GainScheduleTableDialogProps {
+    pub config: GainScheduleSetup,
+    pub on_update: Callback<GainScheduleSetup>,
+}
+
+#[function_component(GainScheduleTableDialog)]
+pub fn gain_schedule_table_dialog(props: &GainScheduleTableDialogProps) -> Html {
+
+    let schedule = props.config.clone();
+    debug!("GainScheduleTableDialog - Entry: {:?}", schedule);
+
+    let on_variable_change = {
+        let emitter = props.on_update.clone();
+        let schedule = schedule.clone();
+        Callback::from(move |event: Event| {
+            let target = event.target_dyn_into::<HtmlSelectElement>();
+            if let Some(select) = target {
+                let variable = select
+                    .value()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| SchedulingVariable::ALL.get(index).copied())
+                    .unwrap_or_default();
+                emitter.emit(schedule.clone().set_variable(variable));
+            }
+        })
+    };
+    let variable_names = SchedulingVariable::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, v)| {
+            html! {
+                <option value={index.to_string()} selected={*v == schedule.variable}>
+                    { v.to_string() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let interpolate_change = {
+        let emitter = props.on_update.clone();
+        let schedule = schedule.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("Interpolate gains {}", input.checked(), );
+            emitter.emit(schedule.clone().set_interpolate(input.checked()));
+        })
+    };
+
+    let on_add = {
+        let emitter = props.on_update.clone();
+        let schedule = schedule.clone();
+        Callback::from(move |_| {
+            let mut rows = schedule.rows.clone();
+            let new = rows
+                .iter()
+                .max_by(|a, b| a.at.total_cmp(&b.at))
+                .map(|last| GainScheduleRow { at: last.at + 1.0, ..*last })
+                .unwrap_or(GainScheduleRow::new(0.0, 1.0, 0.0, 0.0));
+            rows.push(new);
+            emitter.emit(schedule.clone().set_rows(rows));
+        })
+    };
+
+    // the row count is part of the key, adding or removing rows re-initializes their inputs
+    let rows = schedule
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let on_row_update = {
+                let emitter = props.on_update.clone();
+                let schedule = schedule.clone();
+                Callback::from(move |row: GainScheduleRow| {
+                    let mut rows = schedule.rows.clone();
+                    rows[index] = row;
+                    emitter.emit(schedule.clone().set_rows(rows));
+                })
+            };
+            let on_remove = {
+                let emitter = props.on_update.clone();
+                let schedule = schedule.clone();
+                Callback::from(move |_| {
+                    let mut rows = schedule.rows.clone();
+                    rows.remove(index);
+                    emitter.emit(schedule.clone().set_rows(rows));
+                })
+            };
+            html! {
+                <div class="flex flex-row items-end">
+                    <GainScheduleRowDialog key={format!("{}-{}", index, schedule.rows.len())} row={*row} on_update={on_row_update} />
+                    <button type="button" onclick={on_remove}
+                        class="btn-social bg-blue-600 hover:bg-blue-700 text-white w-12 h-12 rounded-lg text-xl leading-12 m-2"
+                        aria-label="Remove the row"
+                    >
+                        <span class="fa-solid fa-minus"></span>
+                    </button>
+                </div>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    html! {
+        <div class="flex flex-col">
+            <div class="flex flex-row">
+                <div class="flex flex-col w-48 pl-2 pr-2">
+                    <label for="scheduling_variable_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Scheduling Variable" } </label>
+                    <select name={"scheduling_variable"} onchange={on_variable_change}
+                        class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                    id="scheduling_variable_label">
+                        { variable_names }
+                    </select>
+                </div>
+                <div class="flex flex-col w-64 pl-2 pr-2">
+                    <label for="interpolate_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                        { "Between Rows" }
+                    </label>
+                    <div id="interpolate_label">
+                        <label class="relative inline-flex items-center cursor-pointer">
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Switch"}</span>
+                            <input type="checkbox" checked={schedule.interpolate} onchange={interpolate_change} class="sr-only peer"/>
+                            <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Interpolate"}</span>
+                        </label>
+                    </div>
+                </div>
+            </div>
+            { rows }
+            <div class="flex flex-row p-2">
+                <button type="button" onclick={on_add}
+                    class="btn-social bg-blue-600 hover:bg-blue-700 text-white w-12 h-12 rounded-lg text-xl leading-12"
+                    aria-label="Add a row"
+                >
+                    <span class="fa-solid fa-plus"></span>
+                </button>
+            </div>
+        </div>
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct GainScheduleRowDialogProps {
+    pub row: GainScheduleRow,
+    pub on_update: Callback<GainScheduleRow>,
+}
+
+#[function_component(GainScheduleRowDialog)]
+pub fn gain_schedule_row_dialog(props: &GainScheduleRowDialogProps) -> Html {
+
+    fn always_valid(_s: String) -> bool {
+        true
+    }
+
+    fn not_negative_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value >= 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    let row = props.row;
+
+    let at_ref = use_node_ref();
+    let at_handle =  use_state(|| row.at.to_string());
+    let at_valid_handle = use_state(|| true);
+
+    let kp_ref = use_node_ref();
+    let kp_handle =  use_state(|| row.kp.to_string());
+    let kp_valid_handle = use_state(|| true);
+
+    let ki_ref = use_node_ref();
+    let ki_handle =  use_state(|| row.ki.to_string());
+    let ki_valid_handle = use_state(|| true);
+
+    let kd_ref = use_node_ref();
+    let kd_handle =  use_state(|| row.kd.to_string());
+    let kd_valid_handle = use_state(|| true);
+
+    let updated = GainScheduleRow::new(
+        (*at_handle).parse::<f64>().unwrap_or_default(),
+        (*kp_handle).parse::<f64>().unwrap_or_default(),
+        (*ki_handle).parse::<f64>().unwrap_or_default(),
+        (*kd_handle).parse::<f64>().unwrap_or_default(),
+    );
+    use_emit_changed(updated, row.clone(), props.on_update.clone());
+
+    html! {
+        <div class="flex flex-row">
+            <Input
+                r#type="number"
+                name="schedule_at"
+                r#ref={at_ref}
+                handle={at_handle}
+                valid_handle={at_valid_handle}
+                validate_function={always_valid}
+
+                label="At"
+                required={true}
+                error_message="Must be a number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="schedule_kp"
+                r#ref={kp_ref}
+                handle={kp_handle}
+                valid_handle={kp_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Kp"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="schedule_ki"
+                r#ref={ki_ref}
+                handle={ki_handle}
+                valid_handle={ki_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Ki"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="schedule_kd"
+                r#ref={kd_ref}
+                handle={kd_handle}
+                valid_handle={kd_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Kd"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+        </div>
+    }
+}
@@ -0,0 +1,405 @@
+use input_rs::yew::Input;
+use yew::prelude::*;
+use log::debug;
+
+use crate::components::hooks::use_emit_changed;
+use crate::control::instrument::{ActuatorSetup, SensorSetup};
+
+
+fn always_valid(_s: String) -> bool {
+    true
+}
+
+fn not_negative_valid(s: String) -> bool {
+    match s.parse::<f64>() {
+        Ok(value) => value >= 0.0 ,
+        Err(_) => false,
+    }
+}
+
+fn bits_valid(s: String) -> bool {
+    match s.parse::<u32>() {
+        Ok(value) => value <= 32 ,
+        Err(_) => false,
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct ActuatorOptionalDialogProps {
+    pub config: Option<ActuatorSetup>,
+    pub on_update: Callback<Option<ActuatorSetup>>,
+}
+
+#[function_component(ActuatorDialog)]
+pub fn actuator_dialog(props: &ActuatorOptionalDialogProps) -> Html {
+
+    debug!("ActuatorDialog - Entry: {:?}", props.config);
+
+    let is_enabled = use_state( || props.config.is_some());
+
+    let enabled_change = {
+        let is_enabled = is_enabled.clone();
+        let emitter = props.on_update.clone();
+        let default = props.config.clone().unwrap_or_default();
+        Callback::from(move |e: Event| {
+
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("Actuator {} Checkbox {}", *is_enabled, input.checked(), );
+
+            is_enabled.set(input.checked());
+            if input.checked() {
+                emitter.emit(Some(default.clone()))
+            } else {
+                emitter.emit(None)
+            }
+        })
+    };
+
+    let actuator_change = {
+        let emitter = props.on_update.clone();
+        Callback::from(move |config: ActuatorSetup| {
+            debug!("Actuator Change {:?} propagate", config );
+            emitter.emit(Some(config));
+        })
+    };
+
+    html! {
+        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+            <div class="flex flex-col w-40">
+                <label for="actuator_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { "Actuator Model" }
+                </label>
+                <div id="actuator_label">
+                    <label class="relative inline-flex items-center cursor-pointer">
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                        <input type="checkbox" checked={*is_enabled} onchange={enabled_change} class="sr-only peer"/>
+                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                    </label>
+                </div>
+            </div>
+
+           if *is_enabled {
+                <ActuatorSetupDialog config={props.config.clone().unwrap_or_default()} on_update={ actuator_change } />
+           }
+        </form>
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct ActuatorSetupDialogProps {
+    pub config: ActuatorSetup,
+    pub on_update: Callback<ActuatorSetup>,
+}
+
+#[function_component(ActuatorSetupDialog)]
+pub fn actuator_setup_dialog(props: &ActuatorSetupDialogProps) -> Html {
+
+    let actuator = props.config.clone();
+    debug!("ActuatorSetupDialog - Entry: {:?}", actuator);
+
+    let minimum_ref = use_node_ref();
+    let minimum_handle = use_state(|| actuator.minimum.to_string());
+    let minimum_valid_handle = use_state(|| true);
+
+    let maximum_ref = use_node_ref();
+    let maximum_handle = use_state(|| actuator.maximum.to_string());
+    let maximum_valid_handle = use_state(|| true);
+
+    let rate_limit_ref = use_node_ref();
+    let rate_limit_handle = use_state(|| actuator.rate_limit.to_string());
+    let rate_limit_valid_handle = use_state(|| true);
+
+    let bits_ref = use_node_ref();
+    let bits_handle = use_state(|| actuator.bits.to_string());
+    let bits_valid_handle = use_state(|| true);
+
+    let updated = actuator.clone()
+        .set_minimum((*minimum_handle).parse::<f64>().unwrap_or(actuator.minimum))
+        .set_maximum((*maximum_handle).parse::<f64>().unwrap_or(actuator.maximum))
+        .set_rate_limit((*rate_limit_handle).parse::<f64>().unwrap_or_default())
+        .set_bits((*bits_handle).parse::<u32>().unwrap_or_default());
+    use_emit_changed(updated, actuator, props.on_update.clone());
+
+    html! {
+        <div class="flex flex-row">
+            <Input
+                r#type="number"
+                name="actuator_minimum"
+                r#ref={minimum_ref}
+                handle={minimum_handle}
+                valid_handle={minimum_valid_handle}
+                validate_function={always_valid}
+
+                label="Saturation Minimum"
+                required={true}
+                error_message="Must be a number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="actuator_maximum"
+                r#ref={maximum_ref}
+                handle={maximum_handle}
+                valid_handle={maximum_valid_handle}
+                validate_function={always_valid}
+
+                label="Saturation Maximum"
+                required={true}
+                error_message="Must be a number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="actuator_rate_limit"
+                r#ref={rate_limit_ref}
+                handle={rate_limit_handle}
+                valid_handle={rate_limit_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Rate Limit [1/ms] (0 = off)"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="actuator_bits"
+                r#ref={bits_ref}
+                handle={bits_handle}
+                valid_handle={bits_valid_handle}
+                validate_function={bits_valid}
+
+                label="DAC Bits (0 = off)"
+                required={true}
+                error_message="Must be an integer up to 32"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+        </div>
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct SensorOptionalDialogProps {
+    pub config: Option<SensorSetup>,
+    pub on_update: Callback<Option<SensorSetup>>,
+}
+
+#[function_component(SensorDialog)]
+pub fn sensor_dialog(props: &SensorOptionalDialogProps) -> Html {
+
+    debug!("SensorDialog - Entry: {:?}", props.config);
+
+    let is_enabled = use_state( || props.config.is_some());
+
+    let enabled_change = {
+        let is_enabled = is_enabled.clone();
+        let emitter = props.on_update.clone();
+        let default = props.config.clone().unwrap_or_default();
+        Callback::from(move |e: Event| {
+
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("Sensor {} Checkbox {}", *is_enabled, input.checked(), );
+
+            is_enabled.set(input.checked());
+            if input.checked() {
+                emitter.emit(Some(default.clone()))
+            } else {
+                emitter.emit(None)
+            }
+        })
+    };
+
+    let sensor_change = {
+        let emitter = props.on_update.clone();
+        Callback::from(move |config: SensorSetup| {
+            debug!("Sensor Change {:?} propagate", config );
+            emitter.emit(Some(config));
+        })
+    };
+
+    html! {
+        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+            <div class="flex flex-col w-40">
+                <label for="sensor_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { "Sensor Model" }
+                </label>
+                <div id="sensor_label">
+                    <label class="relative inline-flex items-center cursor-pointer">
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                        <input type="checkbox" checked={*is_enabled} onchange={enabled_change} class="sr-only peer"/>
+                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                    </label>
+                </div>
+            </div>
+
+           if *is_enabled {
+                <SensorSetupDialog config={props.config.clone().unwrap_or_default()} on_update={ sensor_change } />
+           }
+        </form>
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct SensorSetupDialogProps {
+    pub config: SensorSetup,
+    pub on_update: Callback<SensorSetup>,
+}
+
+#[function_component(SensorSetupDialog)]
+pub fn sensor_setup_dialog(props: &SensorSetupDialogProps) -> Html {
+
+    let sensor = props.config.clone();
+    debug!("SensorSetupDialog - Entry: {:?}", sensor);
+
+    let lag_time_ref = use_node_ref();
+    let lag_time_handle = use_state(|| sensor.lag_time.to_string());
+    let lag_time_valid_handle = use_state(|| true);
+
+    let dead_time_ref = use_node_ref();
+    let dead_time_handle = use_state(|| sensor.dead_time.to_string());
+    let dead_time_valid_handle = use_state(|| true);
+
+    let offset_ref = use_node_ref();
+    let offset_handle = use_state(|| sensor.offset.to_string());
+    let offset_valid_handle = use_state(|| true);
+
+    let bits_ref = use_node_ref();
+    let bits_handle = use_state(|| sensor.bits.to_string());
+    let bits_valid_handle = use_state(|| true);
+
+    let minimum_ref = use_node_ref();
+    let minimum_handle = use_state(|| sensor.minimum.to_string());
+    let minimum_valid_handle = use_state(|| true);
+
+    let maximum_ref = use_node_ref();
+    let maximum_handle = use_state(|| sensor.maximum.to_string());
+    let maximum_valid_handle = use_state(|| true);
+
+    let updated = sensor.clone()
+        .set_lag_time((*lag_time_handle).parse::<f64>().unwrap_or_default())
+        .set_dead_time((*dead_time_handle).parse::<f64>().unwrap_or_default())
+        .set_offset((*offset_handle).parse::<f64>().unwrap_or_default())
+        .set_bits((*bits_handle).parse::<u32>().unwrap_or_default())
+        .set_minimum((*minimum_handle).parse::<f64>().unwrap_or(sensor.minimum))
+        .set_maximum((*maximum_handle).parse::<f64>().unwrap_or(sensor.maximum));
+    use_emit_changed(updated, sensor, props.on_update.clone());
+
+    html! {
+        <div class="flex flex-row flex-wrap">
+            <Input
+                r#type="number"
+                name="sensor_lag_time"
+                r#ref={lag_time_ref}
+                handle={lag_time_handle}
+                valid_handle={lag_time_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Lag Time [ms]"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="sensor_dead_time"
+                r#ref={dead_time_ref}
+                handle={dead_time_handle}
+                valid_handle={dead_time_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Dead Time [ms]"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="sensor_offset"
+                r#ref={offset_ref}
+                handle={offset_handle}
+                valid_handle={offset_valid_handle}
+                validate_function={always_valid}
+
+                label="Offset"
+                required={true}
+                error_message="Must be a number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="sensor_bits"
+                r#ref={bits_ref}
+                handle={bits_handle}
+                valid_handle={bits_valid_handle}
+                validate_function={bits_valid}
+
+                label="ADC Bits (0 = off)"
+                required={true}
+                error_message="Must be an integer up to 32"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="sensor_minimum"
+                r#ref={minimum_ref}
+                handle={minimum_handle}
+                valid_handle={minimum_valid_handle}
+                validate_function={always_valid}
+
+                label="ADC Minimum"
+                required={true}
+                error_message="Must be a number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+            <Input
+                r#type="number"
+                name="sensor_maximum"
+                r#ref={maximum_ref}
+                handle={maximum_handle}
+                valid_handle={maximum_valid_handle}
+                validate_function={always_valid}
+
+                label="ADC Maximum"
+                required={true}
+                error_message="Must be a number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+        </div>
+    }
+}
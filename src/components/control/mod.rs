@@ -1,5 +1,16 @@
 pub mod controller;
+pub mod feedforward;
+pub mod gain_schedule;
+pub mod instrument;
+pub mod mode_switch;
+pub mod mpc;
 pub mod pid_core;
 pub mod pid_output;
+pub mod pid_two_dof;
 pub mod pid_input;
 pub mod pid_dead_band;
+pub mod pid_design;
+pub mod pid_form;
+pub mod smith_predictor;
+pub mod state_feedback;
+pub mod timing;
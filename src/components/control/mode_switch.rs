@@ -0,0 +1,108 @@
+use input_rs::yew::Input;
+use yew::prelude::*;
+use log::debug;
+
+use crate::components::hooks::use_emit_changed;
+use crate::control::mode_switch::ModeSwitchSetup;
+
+
+#[derive(Properties, PartialEq)]
+pub struct ModeSwitchDialogProps {
+    pub config: ModeSwitchSetup,
+    pub on_update: Callback<ModeSwitchSetup>,
+}
+
+#[function_component(ModeSwitchDialog)]
+pub fn mode_switch_dialog(props: &ModeSwitchDialogProps) -> Html {
+
+    fn not_negative_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value >= 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    let config = props.config.clone();
+    debug!("ModeSwitchDialog - Entry: {:?}", config);
+
+    let switch_time_ref = use_node_ref();
+    let switch_time_handle =  use_state(|| config.switch_time.to_string());
+    let switch_time_valid_handle = use_state(|| true);
+
+    let is_auto_first = use_state(|| !config.manual_first);
+    let direction_change = {
+        let is_auto_first = is_auto_first.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("Automatic first {}", input.checked(), );
+            is_auto_first.set(input.checked());
+        })
+    };
+
+    let is_bumpless = use_state(|| config.bumpless);
+    let bumpless_change = {
+        let is_bumpless = is_bumpless.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("Bumpless transfer {}", input.checked(), );
+            is_bumpless.set(input.checked());
+        })
+    };
+
+    let updated = config.clone()
+        .set_switch_time((*switch_time_handle).parse::<f64>().unwrap_or_default())
+        .set_manual_first(!*is_auto_first)
+        .set_bumpless(*is_bumpless);
+    use_emit_changed(updated, config, props.on_update.clone());
+
+    html! {
+        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+
+            <Input
+                r#type="number"
+                name="switch_time"
+                r#ref={switch_time_ref}
+                handle={switch_time_handle}
+                valid_handle={switch_time_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Switch Time [ms]"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+
+            <div class="flex flex-col w-64 pl-2 pr-2">
+                <label for="mode_direction_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { "Switch" }
+                </label>
+                <div id="mode_direction_label">
+                    <label class="relative inline-flex items-center cursor-pointer">
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Manual to Auto"}</span>
+                        <input type="checkbox" checked={*is_auto_first} onchange={direction_change} class="sr-only peer"/>
+                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Auto to Manual"}</span>
+                    </label>
+                </div>
+            </div>
+
+            <div class="flex flex-col w-40 pl-2 pr-2">
+                <label for="bumpless_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { "Bumpless Transfer" }
+                </label>
+                <div id="bumpless_label">
+                    <label class="relative inline-flex items-center cursor-pointer">
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                        <input type="checkbox" checked={*is_bumpless} onchange={bumpless_change} class="sr-only peer"/>
+                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                    </label>
+                </div>
+            </div>
+
+        </form>
+    }
+}
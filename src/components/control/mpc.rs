@@ -0,0 +1,368 @@
+use input_rs::yew::Input;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use log::debug;
+
+use crate::components::hooks::use_emit_changed;
+use crate::control::mpc::{Mpc, MpcSetup};
+use crate::plant::named_element::NamedElement;
+
+
+#[derive(Properties, PartialEq)]
+pub struct MpcOptionalDialogProps {
+    pub config: Option<MpcSetup>,
+    /// Candidates for the internal model
+    pub elements: Vec<NamedElement<f64>>,
+    pub on_update: Callback<Option<MpcSetup>>,
+}
+
+
+#[function_component(MpcDialog)]
+pub fn mpc_dialog(props: &MpcOptionalDialogProps) -> Html {
+
+    debug!("MpcDialog - Entry: {:?}", props.config);
+
+    let is_mpc = use_state( || props.config.is_some());
+
+    let mpc_change = {
+        let is_mpc = is_mpc.clone();
+        let emitter = props.on_update.clone();
+        let initial = props.config.clone().unwrap_or_else(|| {
+            let model = props.elements.first().cloned().unwrap_or_default();
+            MpcSetup::default().set_model(model)
+        });
+        Callback::from(move |e: Event| {
+
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("MPC {} Checkbox {}", *is_mpc, input.checked(), );
+
+            is_mpc.set(input.checked());
+            if input.checked() {
+                emitter.emit(Some(initial.clone()))
+            } else {
+                emitter.emit(None)
+            }
+        })
+    };
+
+    let setup_change = {
+        let emitter = props.on_update.clone();
+        Callback::from(move |config: MpcSetup| {
+            debug!("MPC Change {:?} propagate", config );
+            emitter.emit(Some(config));
+        })
+    };
+
+    html! {
+        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+            <div class="flex flex-col w-40">
+                <label for="mpc_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { "MPC instead of PID" }
+                </label>
+                <div id="mpc_label">
+                    <label class="relative inline-flex items-center cursor-pointer">
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                        <input type="checkbox" checked={*is_mpc} onchange={mpc_change} class="sr-only peer"/>
+                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                    </label>
+                </div>
+            </div>
+
+           if *is_mpc {
+                <MpcSetupDialog config={
+                    match props.config.clone() {
+                        Some(c) => c,
+                        None => MpcSetup::default(),
+                    }
+                } elements={props.elements.clone()} on_update={ setup_change } />
+           }
+        </form>
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct MpcSetupDialogProps {
+    pub config: MpcSetup,
+    pub elements: Vec<NamedElement<f64>>,
+    pub on_update: Callback<MpcSetup>,
+}
+
+#[function_component(MpcSetupDialog)]
+pub fn mpc_setup_dialog(props: &MpcSetupDialogProps) -> Html {
+
+    fn horizon_valid(s: String) -> bool {
+        match s.parse::<usize>() {
+            Ok(value) => value > 0 ,
+            Err(_) => false,
+        }
+    }
+
+    fn positive_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value > 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    fn float_valid(s: String) -> bool {
+        s.parse::<f64>().is_ok()
+    }
+
+    let config = props.config.clone();
+    debug!("MpcSetupDialog - Entry: {:?}", config);
+
+    let initial_selected_model = props
+        .elements
+        .iter()
+        .position(|element| element.name == config.model.name)
+        .unwrap_or(0)
+        .to_string();
+    let selected_model = use_state(|| initial_selected_model);
+    let selected_model_clone = selected_model.clone();
+    let on_model_change = Callback::from(move |event: Event| {
+        let target = event.target_dyn_into::<HtmlSelectElement>();
+        if let Some(select) = target {
+            selected_model_clone.set(select.value());
+        }
+    });
+    let model_names = props
+        .elements
+        .iter()
+        .enumerate()
+        .map(|(index, element)| {
+            html! {
+                <option value={index.to_string()} selected={*selected_model == index.to_string()}>
+                    { element.name.clone() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let prediction_horizon_ref = use_node_ref();
+    let prediction_horizon_handle = use_state(|| config.prediction_horizon.to_string());
+    let prediction_horizon_valid_handle = use_state(|| true);
+
+    let control_horizon_ref = use_node_ref();
+    let control_horizon_handle = use_state(|| config.control_horizon.to_string());
+    let control_horizon_valid_handle = use_state(|| true);
+
+    let output_weight_ref = use_node_ref();
+    let output_weight_handle = use_state(|| config.output_weight.to_string());
+    let output_weight_valid_handle = use_state(|| true);
+
+    let rate_weight_ref = use_node_ref();
+    let rate_weight_handle = use_state(|| config.rate_weight.to_string());
+    let rate_weight_valid_handle = use_state(|| true);
+
+    let is_input_limit = use_state(|| config.input_limit.is_some());
+    let input_limit_change = {
+        let is_input_limit = is_input_limit.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            is_input_limit.set(input.checked());
+        })
+    };
+    let (minimum, maximum) = config.input_limit.unwrap_or((-10.0, 10.0));
+
+    let minimum_ref = use_node_ref();
+    let minimum_handle = use_state(|| minimum.to_string());
+    let minimum_valid_handle = use_state(|| true);
+
+    let maximum_ref = use_node_ref();
+    let maximum_handle = use_state(|| maximum.to_string());
+    let maximum_valid_handle = use_state(|| true);
+
+    let is_rate_limit = use_state(|| config.rate_limit.is_some());
+    let rate_limit_change = {
+        let is_rate_limit = is_rate_limit.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            is_rate_limit.set(input.checked());
+        })
+    };
+
+    let rate_limit_ref = use_node_ref();
+    let rate_limit_handle = use_state(|| config.rate_limit.unwrap_or(1.0).to_string());
+    let rate_limit_valid_handle = use_state(|| true);
+
+    let parse = |handle: &UseStateHandle<String>| (**handle).parse::<f64>().unwrap_or_default();
+    let model = selected_model
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| props.elements.get(index).cloned())
+        .unwrap_or(config.model.clone());
+    let updated = config.clone()
+        .set_model(model)
+        .set_prediction_horizon((*prediction_horizon_handle).parse::<usize>().unwrap_or(config.prediction_horizon))
+        .set_control_horizon((*control_horizon_handle).parse::<usize>().unwrap_or(config.control_horizon))
+        .set_output_weight(parse(&output_weight_handle))
+        .set_rate_weight(parse(&rate_weight_handle))
+        .set_input_limit(is_input_limit.then(|| (parse(&minimum_handle), parse(&maximum_handle))))
+        .set_rate_limit(is_rate_limit.then(|| parse(&rate_limit_handle)));
+    use_emit_changed(updated.clone(), config, props.on_update.clone());
+    let setup_error = Mpc::new(&updated, 1).err();
+
+    html! {
+        <div class="flex flex-col">
+            <div class="flex flex-row flex-wrap">
+                <div class="flex flex-col w-48 pl-2 pr-2">
+                    <label for="mpc_model_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Internal Model" } </label>
+                    <select name={"mpc_model"} onchange={on_model_change}
+                        class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                    id="mpc_model_label">
+                        { model_names }
+                    </select>
+                </div>
+                <Input
+                    r#type="number"
+                    name="prediction_horizon"
+                    r#ref={prediction_horizon_ref}
+                    handle={prediction_horizon_handle}
+                    valid_handle={prediction_horizon_valid_handle}
+                    validate_function={horizon_valid}
+
+                    label="Prediction Horizon [samples]"
+                    required={true}
+                    error_message="Must be a positive integer"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+                <Input
+                    r#type="number"
+                    name="control_horizon"
+                    r#ref={control_horizon_ref}
+                    handle={control_horizon_handle}
+                    valid_handle={control_horizon_valid_handle}
+                    validate_function={horizon_valid}
+
+                    label="Control Horizon [samples]"
+                    required={true}
+                    error_message="Must be a positive integer"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+                <Input
+                    r#type="number"
+                    name="mpc_output_weight"
+                    r#ref={output_weight_ref}
+                    handle={output_weight_handle}
+                    valid_handle={output_weight_valid_handle}
+                    validate_function={positive_valid}
+
+                    label="Tracking Weight"
+                    required={true}
+                    error_message="Must be a positive number"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+                <Input
+                    r#type="number"
+                    name="mpc_rate_weight"
+                    r#ref={rate_weight_ref}
+                    handle={rate_weight_handle}
+                    valid_handle={rate_weight_valid_handle}
+                    validate_function={positive_valid}
+
+                    label="Input Move Weight"
+                    required={true}
+                    error_message="Must be a positive number"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+            </div>
+            <div class="flex flex-row flex-wrap">
+                <div class="flex flex-col w-40 pl-2 pr-2">
+                    <label for="mpc_input_limit_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                        { "Input Limit" }
+                    </label>
+                    <div id="mpc_input_limit_label">
+                        <label class="relative inline-flex items-center cursor-pointer">
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                            <input type="checkbox" checked={*is_input_limit} onchange={input_limit_change} class="sr-only peer"/>
+                            <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                        </label>
+                    </div>
+                </div>
+                if *is_input_limit {
+                    <Input
+                        r#type="number"
+                        name="mpc_minimum"
+                        r#ref={minimum_ref}
+                        handle={minimum_handle}
+                        valid_handle={minimum_valid_handle}
+                        validate_function={float_valid}
+
+                        label="Minimum"
+                        required={true}
+                        error_message="Must be a number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                    <Input
+                        r#type="number"
+                        name="mpc_maximum"
+                        r#ref={maximum_ref}
+                        handle={maximum_handle}
+                        valid_handle={maximum_valid_handle}
+                        validate_function={float_valid}
+
+                        label="Maximum"
+                        required={true}
+                        error_message="Must be a number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                }
+                <div class="flex flex-col w-40 pl-2 pr-2">
+                    <label for="mpc_rate_limit_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                        { "Rate Limit" }
+                    </label>
+                    <div id="mpc_rate_limit_label">
+                        <label class="relative inline-flex items-center cursor-pointer">
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                            <input type="checkbox" checked={*is_rate_limit} onchange={rate_limit_change} class="sr-only peer"/>
+                            <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                        </label>
+                    </div>
+                </div>
+                if *is_rate_limit {
+                    <Input
+                        r#type="number"
+                        name="mpc_rate_limit"
+                        r#ref={rate_limit_ref}
+                        handle={rate_limit_handle}
+                        valid_handle={rate_limit_valid_handle}
+                        validate_function={positive_valid}
+
+                        label="Max Change per Sample"
+                        required={true}
+                        error_message="Must be a positive number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                }
+            </div>
+            if let Some(error) = setup_error {
+                <span class="pl-2 text-red-800 dark:text-red-200">{ format!("{}, the PID is used", error) }</span>
+            }
+        </div>
+    }
+}
@@ -70,43 +70,38 @@ pub fn pid_builder_dialog(props: &PidControllerDialogProps) -> Html {
         let reset_time =(*reset_time_value_handle).parse::<f64>().unwrap_or_default();
         let hold_time =(*hold_time_value_handle).parse::<f64>().unwrap_or_default();
         debug!("Update reset time {:?} hold time {:?}", reset_time, hold_time);
-        let updated = updated.reset_time(reset_time).hold_time(hold_time);
-        // update the corresponding input values - ONLY if changed
-        let field_ki = (*ki_value_handle).parse::<f64>().unwrap_or_default();
-        let desired_ki = updated.get_ki();
-        if field_ki != desired_ki {
-            ki_value_handle.set(desired_ki.to_string());
-        }
-        let field_kd = (*kd_value_handle).parse::<f64>().unwrap_or_default();
-        let desired_kd = updated.get_kd();
-        if field_kd != desired_kd {
-            kd_value_handle.set(desired_kd.to_string());
-        }
-        updated
+        updated.reset_time(reset_time).hold_time(hold_time)
     } else {
         let ki = (*ki_value_handle).parse::<f64>().unwrap_or_default();
         let kd = (*kd_value_handle).parse::<f64>().unwrap_or_default();
         debug!("Update ki {:?} kd {:?}", ki, kd);
-        let updated = updated.ki(ki).kd(kd);
-
-        // update the corresponding input values - ONLY if changed
-        let field_reset_time = (*reset_time_value_handle).parse::<f64>().unwrap_or_default();
-        let desired_reset_time = updated.get_reset_time();
-        if field_reset_time != desired_reset_time {
-            reset_time_value_handle.set(desired_reset_time.to_string());
-        }
-        let field_hold_time = (*hold_time_value_handle).parse::<f64>().unwrap_or_default();
-        let desired_hold_time = updated.get_hold_time();
-        if field_hold_time != desired_hold_time {
-            hold_time_value_handle.set(desired_hold_time.to_string());
-        }
-        updated
+        updated.ki(ki).kd(kd)
     };
 
-    if props.builder != updated {
-        props.on_update.emit(updated);
+    // update the corresponding input values after the render - ONLY if changed
+    {
+        let (first_handle, second_handle, first, second) = if *is_time_mode {
+            (ki_value_handle.clone(), kd_value_handle.clone(), updated.get_ki(), updated.get_kd())
+        } else {
+            (
+                reset_time_value_handle.clone(),
+                hold_time_value_handle.clone(),
+                updated.get_reset_time(),
+                updated.get_hold_time(),
+            )
+        };
+        use_effect_with((*is_time_mode, first, second), move |&(_, first, second)| {
+            if (*first_handle).parse::<f64>().unwrap_or_default() != first {
+                first_handle.set(first.to_string());
+            }
+            if (*second_handle).parse::<f64>().unwrap_or_default() != second {
+                second_handle.set(second.to_string());
+            }
+        });
     }
 
+    use_emit_changed(updated, props.builder.clone(), props.on_update.clone());
+
     html! {
        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
             <Input
@@ -94,9 +94,7 @@ pub fn pid_controller_dead_band_dialog(props: &PidControllerDeadBandToleranceDia
     let dead_band_valid_handle = use_state(|| true);
 
     let updated = (*dead_band_handle).parse::<f64>().unwrap_or_default();
-    if updated != props.config {
-        props.on_update.emit(updated);
-    }
+    use_emit_changed(updated, props.config, props.on_update.clone());
 
     html! {
         <div class="flex flex-col w-32">
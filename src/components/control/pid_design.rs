@@ -0,0 +1,276 @@
+use input_rs::yew::Input;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use log::{debug, info};
+
+use cb_controller::pid::PidCoreBuilder;
+
+use crate::control::pid_design::{design, DesignGoal, PlantModel, Pole};
+use crate::plant::named_element::NamedElement;
+
+/// Frequencies are entered in rad/s, the simulation runs in ms
+const MS_PER_S: f64 = 1000.0;
+
+
+#[derive(Properties, PartialEq)]
+pub struct PidDesignDialogProps {
+    pub builder: PidCoreBuilder<f64>,
+    /// Candidates for the plant to design for
+    pub elements: Vec<NamedElement<f64>>,
+    pub on_update: Callback<PidCoreBuilder<f64>>,
+}
+
+/// Computes PID gains from desired closed-loop poles or crossover frequency and phase margin
+#[function_component(PidDesignDialog)]
+pub fn pid_design_dialog(props: &PidDesignDialogProps) -> Html {
+
+    fn positive_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value > 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    let selected_plant = use_state(|| "0".to_string());
+    let selected_plant_clone = selected_plant.clone();
+    let on_plant_change = Callback::from(move |event: Event| {
+        let target = event.target_dyn_into::<HtmlSelectElement>();
+        if let Some(select) = target {
+            selected_plant_clone.set(select.value());
+        }
+    });
+    let plant_names = props
+        .elements
+        .iter()
+        .enumerate()
+        .map(|(index, element)| {
+            html! {
+                <option value={index.to_string()} selected={*selected_plant == index.to_string()}>
+                    { element.name.clone() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let is_loop_shaping = use_state(|| false);
+    let goal_change = {
+        let is_loop_shaping = is_loop_shaping.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("Loop shaping {}", input.checked(), );
+            is_loop_shaping.set(input.checked());
+        })
+    };
+
+    let with_derivative = use_state(|| true);
+    let derivative_change = {
+        let with_derivative = with_derivative.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            with_derivative.set(input.checked());
+        })
+    };
+
+    let omega_ref = use_node_ref();
+    let omega_handle = use_state(|| "10".to_string());
+    let omega_valid_handle = use_state(|| true);
+
+    let damping_ref = use_node_ref();
+    let damping_handle = use_state(|| "0.7".to_string());
+    let damping_valid_handle = use_state(|| true);
+
+    let crossover_ref = use_node_ref();
+    let crossover_handle = use_state(|| "10".to_string());
+    let crossover_valid_handle = use_state(|| true);
+
+    let phase_margin_ref = use_node_ref();
+    let phase_margin_handle = use_state(|| "60".to_string());
+    let phase_margin_valid_handle = use_state(|| true);
+
+    let goal = if *is_loop_shaping {
+        DesignGoal::LoopShaping {
+            crossover: (*crossover_handle).parse::<f64>().unwrap_or_default() / MS_PER_S,
+            phase_margin: (*phase_margin_handle).parse::<f64>().unwrap_or_default(),
+            with_derivative: *with_derivative,
+        }
+    } else {
+        DesignGoal::PolePlacement {
+            omega: (*omega_handle).parse::<f64>().unwrap_or_default() / MS_PER_S,
+            damping: (*damping_handle).parse::<f64>().unwrap_or_default(),
+        }
+    };
+    let plant = selected_plant
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| props.elements.get(index))
+        .and_then(PlantModel::from_element);
+    let result = plant.map(|plant| design(&plant, &goal));
+    debug!("PidDesignDialog - {:?} {:?}: {:?}", plant, goal, result);
+
+    let on_apply = {
+        let emitter = props.on_update.clone();
+        let builder = props.builder.clone();
+        let result = result.clone();
+        Callback::from(move |_| {
+            if let Some(Ok(design)) = &result {
+                info!("Apply PID design {:?}", design);
+                emitter.emit(builder.clone().kp(design.kp).ki(design.ki).kd(design.kd));
+            }
+        })
+    };
+
+    let result_view = match &result {
+        None => html! {
+            <span class="text-red-800 dark:text-red-200">{ "Select a PT1 or PT2 element" }</span>
+        },
+        Some(Err(error)) => html! {
+            <span class="text-red-800 dark:text-red-200">{ error.to_string() }</span>
+        },
+        Some(Ok(design)) => {
+            let poles = design
+                .poles
+                .iter()
+                .map(|pole| {
+                    let pole = Pole { re: pole.re * MS_PER_S, im: pole.im * MS_PER_S };
+                    html! { <span class="pr-4">{ pole.to_string() }</span> }
+                })
+                .collect::<Html>();
+            html! {
+                <>
+                    <div class="flex flex-row text-sm">
+                        <span class="w-48 font-medium">{ "PID Gains" }</span>
+                        <span class="pr-4">{ format!("Kp = {:.6}", design.kp) }</span>
+                        <span class="pr-4">{ format!("Ki = {:.6}", design.ki) }</span>
+                        <span class="pr-4">{ format!("Kd = {:.6}", design.kd) }</span>
+                    </div>
+                    <div class="flex flex-row text-sm">
+                        <span class="w-48 font-medium">{ "Closed-Loop Poles [rad/s]" }</span>
+                        { poles }
+                    </div>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <form  class="flex flex-col m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+            <div class="flex flex-row">
+                <div class="flex flex-col w-48 pl-2 pr-2">
+                    <label for="design_plant_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Design for Plant" } </label>
+                    <select name={"design_plant"} onchange={on_plant_change}
+                        class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                    id="design_plant_label">
+                        { plant_names }
+                    </select>
+                </div>
+
+                <div class="flex flex-col w-64 pl-2 pr-2">
+                    <label for="design_goal_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                        { "Design Goal" }
+                    </label>
+                    <div id="design_goal_label">
+                        <label class="relative inline-flex items-center cursor-pointer">
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Poles"}</span>
+                            <input type="checkbox" checked={*is_loop_shaping} onchange={goal_change} class="sr-only peer"/>
+                            <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Loop Shaping"}</span>
+                        </label>
+                    </div>
+                </div>
+
+                if *is_loop_shaping {
+                    <Input
+                        r#type="number"
+                        name="crossover"
+                        r#ref={crossover_ref}
+                        handle={crossover_handle}
+                        valid_handle={crossover_valid_handle}
+                        validate_function={positive_valid}
+
+                        label="Crossover [rad/s]"
+                        required={true}
+                        error_message="Must be a positive number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                    <Input
+                        r#type="number"
+                        name="phase_margin"
+                        r#ref={phase_margin_ref}
+                        handle={phase_margin_handle}
+                        valid_handle={phase_margin_valid_handle}
+                        validate_function={positive_valid}
+
+                        label="Phase Margin [°]"
+                        required={true}
+                        error_message="Must be a positive number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                    <div class="flex flex-col w-40 pl-2 pr-2">
+                        <label for="design_derivative_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                            { "Controller" }
+                        </label>
+                        <div id="design_derivative_label">
+                            <label class="relative inline-flex items-center cursor-pointer">
+                                <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"PI"}</span>
+                                <input type="checkbox" checked={*with_derivative} onchange={derivative_change} class="sr-only peer"/>
+                                <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                                <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"PID"}</span>
+                            </label>
+                        </div>
+                    </div>
+                } else {
+                    <Input
+                        r#type="number"
+                        name="natural_frequency"
+                        r#ref={omega_ref}
+                        handle={omega_handle}
+                        valid_handle={omega_valid_handle}
+                        validate_function={positive_valid}
+
+                        label="Natural Frequency [rad/s]"
+                        required={true}
+                        error_message="Must be a positive number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                    <Input
+                        r#type="number"
+                        name="damping"
+                        r#ref={damping_ref}
+                        handle={damping_handle}
+                        valid_handle={damping_valid_handle}
+                        validate_function={positive_valid}
+
+                        label="Damping"
+                        required={true}
+                        error_message="Must be a positive number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                }
+
+                <div class="flex flex-col justify-end pl-2 pr-2">
+                    <button type="button" onclick={on_apply} disabled={!matches!(result, Some(Ok(_)))}
+                        class="bg-blue-600 hover:bg-blue-700 text-white p-2 rounded-lg"
+                        aria-label="Apply the designed gains to the controller"
+                    >
+                        { "Apply" }
+                    </button>
+                </div>
+            </div>
+            <div class="flex flex-col pt-2">
+                { result_view }
+            </div>
+        </form>
+    }
+}
@@ -0,0 +1,226 @@
+use input_rs::yew::Input;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use log::{debug, info};
+
+use cb_controller::pid::PidCoreBuilder;
+
+use crate::control::pid_form::{PidForm, PidParameters};
+
+
+#[derive(Properties, PartialEq)]
+pub struct PidFormDialogProps {
+    pub builder: PidCoreBuilder<f64>,
+    pub on_update: Callback<PidCoreBuilder<f64>>,
+}
+
+/// Integral time 0 stands for no integral action, like on most vendor HMIs
+fn to_display(form: PidForm, index: usize, value: f64) -> f64 {
+    if form != PidForm::Parallel && index == 1 && value.is_infinite() { 0.0 } else { value }
+}
+
+fn from_display(form: PidForm, index: usize, value: f64) -> f64 {
+    if form != PidForm::Parallel && index == 1 && value == 0.0 { f64::INFINITY } else { value }
+}
+
+fn current_parameters(builder: &PidCoreBuilder<f64>) -> PidParameters {
+    PidParameters::parallel(builder.kp, builder.get_ki(), builder.get_kd())
+}
+
+/// Shows the PID gains in parallel, ideal and series form and accepts parameters in any of them
+#[function_component(PidFormDialog)]
+pub fn pid_form_dialog(props: &PidFormDialogProps) -> Html {
+
+    fn not_negative_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value >= 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    let current = current_parameters(&props.builder);
+    debug!("PidFormDialog - Entry: {:?}", current);
+
+    let selected_form = use_state(PidForm::default);
+
+    let gain_ref = use_node_ref();
+    let gain_handle = use_state(|| current.gain.to_string());
+    let gain_valid_handle = use_state(|| true);
+
+    let integral_ref = use_node_ref();
+    let integral_handle = use_state(|| current.integral.to_string());
+    let integral_valid_handle = use_state(|| true);
+
+    let derivative_ref = use_node_ref();
+    let derivative_handle = use_state(|| current.derivative.to_string());
+    let derivative_valid_handle = use_state(|| true);
+
+    // a form change loads the recent controller in that form
+    let on_form_change = {
+        let selected_form = selected_form.clone();
+        let handles = [gain_handle.clone(), integral_handle.clone(), derivative_handle.clone()];
+        Callback::from(move |event: Event| {
+            let target = event.target_dyn_into::<HtmlSelectElement>();
+            if let Some(select) = target {
+                let form = select
+                    .value()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| PidForm::ALL.get(index).copied())
+                    .unwrap_or_default();
+                selected_form.set(form);
+                if let Ok(parameters) = current.convert(form) {
+                    for (index, (handle, value)) in handles.iter().zip(parameters.values()).enumerate() {
+                        handle.set(to_display(form, index, value).to_string());
+                    }
+                }
+            }
+        })
+    };
+
+    let form = *selected_form;
+    let entered = PidParameters::new(
+        form,
+        from_display(form, 0, (*gain_handle).parse::<f64>().unwrap_or_default()),
+        from_display(form, 1, (*integral_handle).parse::<f64>().unwrap_or_default()),
+        from_display(form, 2, (*derivative_handle).parse::<f64>().unwrap_or_default()),
+    );
+
+    let on_apply = {
+        let emitter = props.on_update.clone();
+        let builder = props.builder.clone();
+        Callback::from(move |_| {
+            // every form is convertible into the parallel form
+            match entered.convert(PidForm::Parallel) {
+                Ok(parallel) => {
+                    info!("Apply PID parameters {:?} as {:?}", entered, parallel);
+                    emitter.emit(builder.clone().kp(parallel.gain).ki(parallel.integral).kd(parallel.derivative));
+                }
+                Err(error) => info!("PID parameters {:?} not applied: {}", entered, error),
+            }
+        })
+    };
+
+    let form_names = PidForm::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, f)| {
+            html! {
+                <option value={index.to_string()} selected={*f == form}>
+                    { f.to_string() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let names = form.parameter_names();
+    let integral_label = match form {
+        PidForm::Parallel => names[1],
+        PidForm::Ideal => "Ti (0 is off)",
+        PidForm::Series => "Ti' (0 is off)",
+    };
+
+    let conversions = PidForm::ALL
+        .iter()
+        .map(|f| {
+            let values = match current.convert(*f) {
+                Ok(parameters) => parameters
+                    .values()
+                    .iter()
+                    .zip(f.parameter_names())
+                    .map(|(value, name)| {
+                        let value = if value.is_infinite() { "∞".to_string() } else { format!("{:.6}", value) };
+                        html! { <span class="pr-4">{ format!("{} = {}", name, value) }</span> }
+                    })
+                    .collect::<Html>(),
+                Err(error) => html! {
+                    <span class="text-red-800 dark:text-red-200">{ error.to_string() }</span>
+                },
+            };
+            html! {
+                <div class="flex flex-row text-sm">
+                    <span class="w-48 font-medium">{ f.to_string() }</span>
+                    { values }
+                </div>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    html! {
+        <form  class="flex flex-col m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+            <div class="flex flex-col pb-2">
+                { conversions }
+            </div>
+            <div class="flex flex-row">
+                <div class="flex flex-col w-48 pl-2 pr-2">
+                    <label for="pid_form_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Enter Parameters in Form" } </label>
+                    <select name={"pid_form"} onchange={on_form_change}
+                        class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                    id="pid_form_label">
+                        { form_names }
+                    </select>
+                </div>
+
+                <Input
+                    r#type="number"
+                    name="form_gain"
+                    r#ref={gain_ref}
+                    handle={gain_handle}
+                    valid_handle={gain_valid_handle}
+                    validate_function={not_negative_valid}
+
+                    label={names[0]}
+                    required={true}
+                    error_message="Must be a not-negative number"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+
+                <Input
+                    r#type="number"
+                    name="form_integral"
+                    r#ref={integral_ref}
+                    handle={integral_handle}
+                    valid_handle={integral_valid_handle}
+                    validate_function={not_negative_valid}
+
+                    label={integral_label}
+                    required={true}
+                    error_message="Must be a not-negative number"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+
+                <Input
+                    r#type="number"
+                    name="form_derivative"
+                    r#ref={derivative_ref}
+                    handle={derivative_handle}
+                    valid_handle={derivative_valid_handle}
+                    validate_function={not_negative_valid}
+
+                    label={names[2]}
+                    required={true}
+                    error_message="Must be a not-negative number"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+
+                <div class="flex flex-col justify-end pl-2 pr-2">
+                    <button type="button" onclick={on_apply}
+                        class="bg-blue-600 hover:bg-blue-700 text-white p-2 rounded-lg"
+                        aria-label="Apply the parameters to the controller"
+                    >
+                        { "Apply" }
+                    </button>
+                </div>
+            </div>
+        </form>
+    }
+}
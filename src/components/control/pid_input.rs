@@ -107,9 +107,7 @@ pub fn pid_controller_limited_input_dialog(props: &PidControllerInputDialogProps
         )
         .out_of_band_output((*off_band_output_handle).parse::<f64>().unwrap_or_default())
         ;
-    if updated != input_config {
-        props.on_update.emit(updated);
-    }
+    use_emit_changed(updated, input_config, props.on_update.clone());
 
 
     html! {
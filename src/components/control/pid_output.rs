@@ -1,14 +1,20 @@
 use input_rs::yew::Input;
+use web_sys::HtmlSelectElement;
 use yew::prelude::*;
 use log::debug;
 
 use cb_controller::pid::PidOutputLimit;
 
+use crate::components::hooks::use_emit_changed;
+use crate::control::pid_law::AntiWindup;
+
 
 #[derive(Properties, PartialEq)]
 pub struct PidControllerOptionalOutputDialogProps {
     pub config: Option<PidOutputLimit<f64>>,
     pub on_update: Callback<Option<PidOutputLimit<f64>>>,
+    pub anti_windup: AntiWindup,
+    pub on_anti_windup_update: Callback<AntiWindup>,
 }
 
 
@@ -64,7 +70,8 @@ pub fn pid_controller_output_dialog(props: &PidControllerOptionalOutputDialogPro
                         Some(c) => c,
                         None => PidOutputLimit::default(),
                     }
-                } on_update={ output_change } />
+                } on_update={ output_change }
+                anti_windup={props.anti_windup} on_anti_windup_update={props.on_anti_windup_update.clone()} />
            }
         </form>
     }
@@ -75,6 +82,8 @@ pub fn pid_controller_output_dialog(props: &PidControllerOptionalOutputDialogPro
 pub struct PidControllerOutputDialogProps {
     pub config: PidOutputLimit<f64>,
     pub on_update: Callback<PidOutputLimit<f64>>,
+    pub anti_windup: AntiWindup,
+    pub on_anti_windup_update: Callback<AntiWindup>,
 }
 
 #[function_component(PidControllerLimitedOutputDialog)]
@@ -84,6 +93,13 @@ pub fn pid_controller_limited_output_dialog(props: &PidControllerOutputDialogPro
         true
     }
 
+    fn positive_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value > 0.0 ,
+            Err(_) => false,
+        }
+    }
+
     let output_config = props.config.clone();
     debug!("PidControllerLimitedOutputDialog - Entry: {:?}", output_config);
 
@@ -95,28 +111,46 @@ pub fn pid_controller_limited_output_dialog(props: &PidControllerOutputDialogPro
     let output_max_handle =  use_state(|| output_config.maximum().to_string());
     let output_max_valid_handle = use_state(|| true);
 
-    let anti_windup = use_state(|| output_config.anti_windup.clone());
+    let anti_windup_mode = use_state(|| match props.anti_windup {
+        AntiWindup::None => "none".to_string(),
+        AntiWindup::Clamping => "clamping".to_string(),
+        AntiWindup::BackCalculation { .. } => "back_calculation".to_string(),
+    });
 
     let windup_change = {
-        let anti_windup = anti_windup.clone();
-        Callback::from(move |e: Event| {
-            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
-            debug!("Anti-Windup {}", input.checked(), );
-            anti_windup.set(input.checked());
-
+        let anti_windup_mode = anti_windup_mode.clone();
+        Callback::from(move |event: Event| {
+            let target = event.target_dyn_into::<HtmlSelectElement>();
+            if let Some(select) = target {
+                debug!("Anti-Windup {}", select.value(), );
+                anti_windup_mode.set(select.value());
+            }
         })
     };
 
+    let tracking_time_ref = use_node_ref();
+    let tracking_time_handle =  use_state(|| match props.anti_windup {
+        AntiWindup::BackCalculation { tracking_time } => tracking_time.to_string(),
+        _ => "10".to_string(),
+    });
+    let tracking_time_valid_handle = use_state(|| true);
+
     let updated = output_config.clone()
         .range(
             (*output_min_handle).parse::<f64>().unwrap_or_default(),
             (*output_max_handle).parse::<f64>().unwrap_or_default(),
         )
-        .anti_windup(*anti_windup)
         ;
-    if updated != output_config {
-        props.on_update.emit(updated);
-    }
+    use_emit_changed(updated, output_config, props.on_update.clone());
+
+    let anti_windup = match (*anti_windup_mode).as_str() {
+        "clamping" => AntiWindup::Clamping,
+        "back_calculation" => AntiWindup::BackCalculation {
+            tracking_time: (*tracking_time_handle).parse::<f64>().unwrap_or(10.0),
+        },
+        _ => AntiWindup::None,
+    };
+    use_emit_changed(anti_windup, props.anti_windup, props.on_anti_windup_update.clone());
 
 
     html! {
@@ -156,19 +190,38 @@ pub fn pid_controller_limited_output_dialog(props: &PidControllerOutputDialogPro
                 error_class="error-text"
             />
 
-            <div class=" w-48">
-                <label for="open_loop_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
-                    { "Integral Adjustment" }
+            <div class="flex flex-col w-48 pl-2 pr-2">
+                <label for="anti_windup_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { "Anti-Windup" }
                 </label>
-                <div id="open_loop_label">
-                    <label class="relative inline-flex items-center cursor-pointer">
-                        <input type="checkbox" checked={*anti_windup} onchange={windup_change} class="sr-only peer"/>
-                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
-                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Anti-Windup"}</span>
-                    </label>
-                </div>
+                <select name={"anti_windup"} onchange={windup_change}
+                    class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                id="anti_windup_label">
+                    <option value="none" selected={*anti_windup_mode == "none"}>{ "None" }</option>
+                    <option value="clamping" selected={*anti_windup_mode == "clamping"}>{ "Clamping" }</option>
+                    <option value="back_calculation" selected={*anti_windup_mode == "back_calculation"}>{ "Back-Calculation" }</option>
+                </select>
             </div>
 
+            if *anti_windup_mode == "back_calculation" {
+                <Input
+                    r#type="number"
+                    name="tracking_time"
+                    r#ref={tracking_time_ref}
+                    handle={tracking_time_handle}
+                    valid_handle={tracking_time_valid_handle}
+                    validate_function={positive_valid}
+
+                    label="Tracking Time [ms]"
+                    required={true}
+                    error_message="Must be a positive number"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+            }
+
         </div>
     }
 }
\ No newline at end of file
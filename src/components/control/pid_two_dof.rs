@@ -0,0 +1,248 @@
+use input_rs::yew::Input;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use log::debug;
+
+use crate::components::hooks::use_emit_changed;
+use crate::control::pid_law::{DerivativeMode, Discretization, PidOptions};
+use crate::control::precision::Precision;
+
+
+#[derive(Properties, PartialEq)]
+pub struct PidTwoDofDialogProps {
+    pub config: PidOptions,
+    pub on_update: Callback<PidOptions>,
+}
+
+#[function_component(PidTwoDofDialog)]
+pub fn pid_two_dof_dialog(props: &PidTwoDofDialogProps) -> Html {
+
+    fn always_valid(_s: String) -> bool {
+        true
+    }
+
+    fn not_negative_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value >= 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    fn positive_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value > 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    let options = props.config.clone();
+    debug!("PidTwoDofDialog - Entry: {:?}", options);
+
+    let weight_p_ref = use_node_ref();
+    let weight_p_handle =  use_state(|| options.setpoint_weight_p.to_string());
+    let weight_p_valid_handle = use_state(|| true);
+
+    let weight_d_ref = use_node_ref();
+    let weight_d_handle =  use_state(|| options.setpoint_weight_d.to_string());
+    let weight_d_valid_handle = use_state(|| true);
+
+    // zero disables the derivative filter
+    let filter_ref = use_node_ref();
+    let filter_handle =  use_state(|| options.derivative_filter.unwrap_or_default().to_string());
+    let filter_valid_handle = use_state(|| true);
+
+    let is_on_measurement = use_state(|| options.derivative == DerivativeMode::OnMeasurement);
+
+    let derivative_mode_change = {
+        let is_on_measurement = is_on_measurement.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("Derivative on measurement {}", input.checked(), );
+            is_on_measurement.set(input.checked());
+        })
+    };
+
+    let discretization = use_state(|| options.discretization);
+    let on_discretization_change = {
+        let discretization = discretization.clone();
+        Callback::from(move |event: Event| {
+            let target = event.target_dyn_into::<HtmlSelectElement>();
+            if let Some(select) = target {
+                let selected = select
+                    .value()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| Discretization::ALL.get(index).copied())
+                    .unwrap_or_default();
+                debug!("Discretization {}", selected);
+                discretization.set(selected);
+            }
+        })
+    };
+    let discretization_names = Discretization::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, d)| {
+            html! {
+                <option value={index.to_string()} selected={*d == *discretization}>
+                    { d.to_string() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let precision = use_state(|| options.precision);
+    let on_precision_change = {
+        let precision = precision.clone();
+        Callback::from(move |event: Event| {
+            let target = event.target_dyn_into::<HtmlSelectElement>();
+            if let Some(select) = target {
+                let selected = select
+                    .value()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| Precision::ALL.get(index).copied())
+                    .unwrap_or_default();
+                debug!("Precision {}", selected);
+                precision.set(selected);
+            }
+        })
+    };
+    let precision_names = Precision::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, p)| {
+            html! {
+                <option value={index.to_string()} selected={*p == *precision}>
+                    { p.to_string() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let full_scale_ref = use_node_ref();
+    let full_scale_handle =  use_state(|| options.full_scale.to_string());
+    let full_scale_valid_handle = use_state(|| true);
+    let is_fixed_point = matches!(*precision, Precision::Q15 | Precision::Q31);
+
+    let derivative_filter = (*filter_handle).parse::<f64>().unwrap_or_default();
+    let updated = options.clone()
+        .set_setpoint_weight_p((*weight_p_handle).parse::<f64>().unwrap_or(1.0))
+        .set_setpoint_weight_d((*weight_d_handle).parse::<f64>().unwrap_or(1.0))
+        .set_derivative_filter(if derivative_filter > 0.0 { Some(derivative_filter) } else { None })
+        .set_derivative(if *is_on_measurement { DerivativeMode::OnMeasurement } else { DerivativeMode::OnError })
+        .set_discretization(*discretization)
+        .set_precision(*precision)
+        .set_full_scale((*full_scale_handle).parse::<f64>().ok().filter(|v| *v > 0.0).unwrap_or(options.full_scale));
+    use_emit_changed(updated, options, props.on_update.clone());
+
+    html! {
+        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+
+            <Input
+                r#type="number"
+                name="setpoint_weight_p"
+                r#ref={weight_p_ref}
+                handle={weight_p_handle}
+                valid_handle={weight_p_valid_handle}
+                validate_function={always_valid}
+
+                label="Setpoint Weight b (P)"
+                required={true}
+                error_message="Must be a number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+
+            <Input
+                r#type="number"
+                name="setpoint_weight_d"
+                r#ref={weight_d_ref}
+                handle={weight_d_handle}
+                valid_handle={weight_d_valid_handle}
+                validate_function={always_valid}
+                disabled={*is_on_measurement}
+
+                label="Setpoint Weight c (D)"
+                required={true}
+                error_message="Must be a number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class={ if *is_on_measurement { "w-full p-2"}
+                    else { "w-full p-2 border border-gray-400 dark:border-gray-600 rounded" }}
+                error_class="text-red-800 dark:text-red-200"
+            />
+
+            <Input
+                r#type="number"
+                name="derivative_filter"
+                r#ref={filter_ref}
+                handle={filter_handle}
+                valid_handle={filter_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Derivative Filter N"
+                required={true}
+                error_message="Must be a not-negative number, 0 is off"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+
+            <div class="flex flex-col w-56 pl-2 pr-2">
+                <label for="derivative_mode_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { "Derivative on" }
+                </label>
+                <div id="derivative_mode_label">
+                    <label class="relative inline-flex items-center cursor-pointer">
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Error"}</span>
+                        <input type="checkbox" checked={*is_on_measurement} onchange={derivative_mode_change} class="sr-only peer"/>
+                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Measurement"}</span>
+                    </label>
+                </div>
+            </div>
+
+            <div class="flex flex-col w-48 pl-2 pr-2">
+                <label for="discretization_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Discretization" } </label>
+                <select name={"discretization"} onchange={on_discretization_change}
+                    class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                id="discretization_label">
+                    { discretization_names }
+                </select>
+            </div>
+
+            <div class="flex flex-col w-32 pl-2 pr-2">
+                <label for="precision_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Precision" } </label>
+                <select name={"precision"} onchange={on_precision_change}
+                    class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                id="precision_label">
+                    { precision_names }
+                </select>
+            </div>
+
+            if is_fixed_point {
+                <Input
+                    r#type="number"
+                    name="full_scale"
+                    r#ref={full_scale_ref}
+                    handle={full_scale_handle}
+                    valid_handle={full_scale_valid_handle}
+                    validate_function={positive_valid}
+
+                    label="Full Scale"
+                    required={true}
+                    error_message="Must be a positive number"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+            }
+
+        </form>
+    }
+}
@@ -0,0 +1,178 @@
+use input_rs::yew::Input;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use log::debug;
+
+use crate::components::hooks::use_emit_changed;
+use crate::control::smith_predictor::SmithPredictorSetup;
+use crate::plant::named_element::NamedElement;
+
+
+#[derive(Properties, PartialEq)]
+pub struct SmithPredictorOptionalDialogProps {
+    pub config: Option<SmithPredictorSetup>,
+    /// Candidates for the internal model
+    pub elements: Vec<NamedElement<f64>>,
+    pub on_update: Callback<Option<SmithPredictorSetup>>,
+}
+
+
+#[function_component(SmithPredictorDialog)]
+pub fn smith_predictor_dialog(props: &SmithPredictorOptionalDialogProps) -> Html {
+
+    debug!("SmithPredictorDialog - Entry: {:?}", props.config);
+
+    let is_smith_predictor = use_state( || props.config.is_some());
+
+    let smith_predictor_change = {
+        let is_smith_predictor = is_smith_predictor.clone();
+        let emitter = props.on_update.clone();
+        let initial = props.config.clone().unwrap_or_else(|| {
+            let model = props.elements.first().cloned().unwrap_or_default();
+            SmithPredictorSetup::default().set_model(model)
+        });
+        Callback::from(move |e: Event| {
+
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("Smith Predictor {} Checkbox {}", *is_smith_predictor, input.checked(), );
+
+            is_smith_predictor.set(input.checked());
+            if input.checked() {
+                emitter.emit(Some(initial.clone()))
+            } else {
+                emitter.emit(None)
+            }
+        })
+    };
+
+    let model_change = {
+        let emitter = props.on_update.clone();
+        Callback::from(move |config: SmithPredictorSetup| {
+            debug!("Smith Predictor Change {:?} propagate", config );
+            emitter.emit(Some(config));
+        })
+    };
+
+    html! {
+        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+            <div class="flex flex-col w-40">
+                <label for="smith_predictor_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { "Smith Predictor" }
+                </label>
+                <div id="smith_predictor_label">
+                    <label class="relative inline-flex items-center cursor-pointer">
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                        <input type="checkbox" checked={*is_smith_predictor} onchange={smith_predictor_change} class="sr-only peer"/>
+                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                    </label>
+                </div>
+            </div>
+
+           if *is_smith_predictor {
+                <SmithPredictorModelDialog config={
+                    match props.config.clone() {
+                        Some(c) => c,
+                        None => SmithPredictorSetup::default(),
+                    }
+                } elements={props.elements.clone()} on_update={ model_change } />
+           }
+        </form>
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct SmithPredictorModelDialogProps {
+    pub config: SmithPredictorSetup,
+    pub elements: Vec<NamedElement<f64>>,
+    pub on_update: Callback<SmithPredictorSetup>,
+}
+
+#[function_component(SmithPredictorModelDialog)]
+pub fn smith_predictor_model_dialog(props: &SmithPredictorModelDialogProps) -> Html {
+
+    fn not_negative_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value >= 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    let smith_config = props.config.clone();
+    debug!("SmithPredictorModelDialog - Entry: {:?}", smith_config);
+
+    let initial_selected_model = props
+        .elements
+        .iter()
+        .position(|element| element.name == smith_config.model.name)
+        .unwrap_or(0)
+        .to_string();
+    let selected_model = use_state(|| initial_selected_model);
+
+    let selected_model_clone = selected_model.clone();
+    let on_model_change = Callback::from(move |event: Event| {
+        let target = event.target_dyn_into::<HtmlSelectElement>();
+        if let Some(select) = target {
+            selected_model_clone.set(select.value());
+        }
+    });
+
+    let model_names = props
+        .elements
+        .iter()
+        .enumerate()
+        .map(|(index, element)| {
+            html! {
+                <option value={index.to_string()} selected={*selected_model == index.to_string()}>
+                    { element.name.clone() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let dead_time_ref = use_node_ref();
+    let dead_time_handle =  use_state(|| smith_config.dead_time.to_string());
+    let dead_time_valid_handle = use_state(|| true);
+
+    // follows changes of the element list, too
+    let model = selected_model
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| props.elements.get(index).cloned())
+        .unwrap_or(smith_config.model.clone());
+    let updated = smith_config.clone()
+        .set_model(model)
+        .set_dead_time((*dead_time_handle).parse::<f64>().unwrap_or_default());
+    use_emit_changed(updated, smith_config, props.on_update.clone());
+
+    html! {
+        <div class="flex flex-row">
+            <div class="flex flex-col w-48 pl-2 pr-2">
+                <label for="smith_model_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Internal Model" } </label>
+                <select name={"smith_model"} onchange={on_model_change}
+                    class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                id="smith_model_label">
+                    { model_names }
+                </select>
+            </div>
+
+            <Input
+                r#type="number"
+                name="model_dead_time"
+                r#ref={dead_time_ref}
+                handle={dead_time_handle}
+                valid_handle={dead_time_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Model Dead Time [ms]"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+        </div>
+    }
+}
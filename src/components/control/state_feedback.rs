@@ -0,0 +1,429 @@
+use input_rs::yew::Input;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use log::debug;
+
+use crate::components::hooks::use_emit_changed;
+use crate::control::state_feedback::{GainDesign, ObserverDesign, StateFeedbackSetup};
+use crate::plant::named_element::NamedElement;
+
+/// Frequencies are entered in rad/s, the simulation runs in ms
+const MS_PER_S: f64 = 1000.0;
+
+
+#[derive(Properties, PartialEq)]
+pub struct StateFeedbackOptionalDialogProps {
+    pub config: Option<StateFeedbackSetup>,
+    pub sampling_interval: f64,
+    /// Candidates for the design model
+    pub elements: Vec<NamedElement<f64>>,
+    pub on_update: Callback<Option<StateFeedbackSetup>>,
+}
+
+
+#[function_component(StateFeedbackDialog)]
+pub fn state_feedback_dialog(props: &StateFeedbackOptionalDialogProps) -> Html {
+
+    debug!("StateFeedbackDialog - Entry: {:?}", props.config);
+
+    let is_state_feedback = use_state( || props.config.is_some());
+
+    let state_feedback_change = {
+        let is_state_feedback = is_state_feedback.clone();
+        let emitter = props.on_update.clone();
+        let initial = props.config.clone().unwrap_or_else(|| {
+            let model = props.elements.first().cloned().unwrap_or_default();
+            StateFeedbackSetup::default().set_model(model)
+        });
+        Callback::from(move |e: Event| {
+
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            debug!("State Feedback {} Checkbox {}", *is_state_feedback, input.checked(), );
+
+            is_state_feedback.set(input.checked());
+            if input.checked() {
+                emitter.emit(Some(initial.clone()))
+            } else {
+                emitter.emit(None)
+            }
+        })
+    };
+
+    let design_change = {
+        let emitter = props.on_update.clone();
+        Callback::from(move |config: StateFeedbackSetup| {
+            debug!("State Feedback Change {:?} propagate", config );
+            emitter.emit(Some(config));
+        })
+    };
+
+    html! {
+        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+            <div class="flex flex-col w-40">
+                <label for="state_feedback_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                    { "State Feedback instead of PID" }
+                </label>
+                <div id="state_feedback_label">
+                    <label class="relative inline-flex items-center cursor-pointer">
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                        <input type="checkbox" checked={*is_state_feedback} onchange={state_feedback_change} class="sr-only peer"/>
+                        <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                        <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                    </label>
+                </div>
+            </div>
+
+           if *is_state_feedback {
+                <StateFeedbackDesignDialog config={
+                    match props.config.clone() {
+                        Some(c) => c,
+                        None => StateFeedbackSetup::default(),
+                    }
+                } sampling_interval={props.sampling_interval} elements={props.elements.clone()} on_update={ design_change } />
+           }
+        </form>
+    }
+}
+
+
+#[derive(Properties, PartialEq)]
+pub struct StateFeedbackDesignDialogProps {
+    pub config: StateFeedbackSetup,
+    pub sampling_interval: f64,
+    pub elements: Vec<NamedElement<f64>>,
+    pub on_update: Callback<StateFeedbackSetup>,
+}
+
+#[function_component(StateFeedbackDesignDialog)]
+pub fn state_feedback_design_dialog(props: &StateFeedbackDesignDialogProps) -> Html {
+
+    fn positive_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value > 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    fn not_negative_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value >= 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    let config = props.config.clone();
+    debug!("StateFeedbackDesignDialog - Entry: {:?}", config);
+
+    let initial_selected_model = props
+        .elements
+        .iter()
+        .position(|element| element.name == config.model.name)
+        .unwrap_or(0)
+        .to_string();
+    let selected_model = use_state(|| initial_selected_model);
+    let selected_model_clone = selected_model.clone();
+    let on_model_change = Callback::from(move |event: Event| {
+        let target = event.target_dyn_into::<HtmlSelectElement>();
+        if let Some(select) = target {
+            selected_model_clone.set(select.value());
+        }
+    });
+    let model_names = props
+        .elements
+        .iter()
+        .enumerate()
+        .map(|(index, element)| {
+            html! {
+                <option value={index.to_string()} selected={*selected_model == index.to_string()}>
+                    { element.name.clone() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let (omega, damping, output_weight, derivative_weight, input_weight) = match config.gain_design {
+        GainDesign::PolePlacement { omega, damping } => (omega * MS_PER_S, damping, 1.0, 0.0, 1.0),
+        GainDesign::Lqr { output_weight, derivative_weight, input_weight } => (10.0, 0.7, output_weight, derivative_weight, input_weight),
+    };
+    let is_lqr = use_state(|| matches!(config.gain_design, GainDesign::Lqr { .. }));
+    let lqr_change = {
+        let is_lqr = is_lqr.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            is_lqr.set(input.checked());
+        })
+    };
+
+    let omega_ref = use_node_ref();
+    let omega_handle = use_state(|| omega.to_string());
+    let omega_valid_handle = use_state(|| true);
+
+    let damping_ref = use_node_ref();
+    let damping_handle = use_state(|| damping.to_string());
+    let damping_valid_handle = use_state(|| true);
+
+    let output_weight_ref = use_node_ref();
+    let output_weight_handle = use_state(|| output_weight.to_string());
+    let output_weight_valid_handle = use_state(|| true);
+
+    let derivative_weight_ref = use_node_ref();
+    let derivative_weight_handle = use_state(|| derivative_weight.to_string());
+    let derivative_weight_valid_handle = use_state(|| true);
+
+    let input_weight_ref = use_node_ref();
+    let input_weight_handle = use_state(|| input_weight.to_string());
+    let input_weight_valid_handle = use_state(|| true);
+
+    let (speed_factor, process_noise, measurement_noise) = match config.observer {
+        ObserverDesign::Luenberger { speed_factor } => (speed_factor, 0.01, 0.1),
+        ObserverDesign::Kalman { process_noise, measurement_noise } => (3.0, process_noise, measurement_noise),
+    };
+    let is_kalman = use_state(|| matches!(config.observer, ObserverDesign::Kalman { .. }));
+    let kalman_change = {
+        let is_kalman = is_kalman.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            is_kalman.set(input.checked());
+        })
+    };
+
+    let speed_factor_ref = use_node_ref();
+    let speed_factor_handle = use_state(|| speed_factor.to_string());
+    let speed_factor_valid_handle = use_state(|| true);
+
+    let process_noise_ref = use_node_ref();
+    let process_noise_handle = use_state(|| process_noise.to_string());
+    let process_noise_valid_handle = use_state(|| true);
+
+    let measurement_noise_ref = use_node_ref();
+    let measurement_noise_handle = use_state(|| measurement_noise.to_string());
+    let measurement_noise_valid_handle = use_state(|| true);
+
+    let parse = |handle: &UseStateHandle<String>| (**handle).parse::<f64>().unwrap_or_default();
+    let gain_design = if *is_lqr {
+        GainDesign::Lqr {
+            output_weight: parse(&output_weight_handle),
+            derivative_weight: parse(&derivative_weight_handle),
+            input_weight: parse(&input_weight_handle),
+        }
+    } else {
+        GainDesign::PolePlacement {
+            omega: parse(&omega_handle) / MS_PER_S,
+            damping: parse(&damping_handle),
+        }
+    };
+    let observer = if *is_kalman {
+        ObserverDesign::Kalman {
+            process_noise: parse(&process_noise_handle),
+            measurement_noise: parse(&measurement_noise_handle),
+        }
+    } else {
+        ObserverDesign::Luenberger { speed_factor: parse(&speed_factor_handle) }
+    };
+    // follows changes of the element list, too
+    let model = selected_model
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| props.elements.get(index).cloned())
+        .unwrap_or(config.model.clone());
+    let updated = config.clone()
+        .set_model(model)
+        .set_gain_design(gain_design)
+        .set_observer(observer);
+    use_emit_changed(updated.clone(), config, props.on_update.clone());
+
+    let result_view = match updated.design(props.sampling_interval) {
+        Ok(gains) => html! {
+            <div class="flex flex-row text-sm">
+                <span class="pr-4">{ format!("K = {:.6}", gains.k) }</span>
+                <span class="pr-4">{ format!("L = {:.6}", gains.l) }</span>
+                <span class="pr-4">{ format!("N = {:.6}", gains.prefilter) }</span>
+            </div>
+        },
+        Err(error) => html! {
+            <span class="text-red-800 dark:text-red-200">{ format!("{}, the PID is used", error) }</span>
+        },
+    };
+
+    html! {
+        <div class="flex flex-col">
+            <div class="flex flex-row flex-wrap">
+                <div class="flex flex-col w-48 pl-2 pr-2">
+                    <label for="state_feedback_model_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Design Model" } </label>
+                    <select name={"state_feedback_model"} onchange={on_model_change}
+                        class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                    id="state_feedback_model_label">
+                        { model_names }
+                    </select>
+                </div>
+
+                <div class="flex flex-col w-48 pl-2 pr-2">
+                    <label for="gain_design_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                        { "Gain Design" }
+                    </label>
+                    <div id="gain_design_label">
+                        <label class="relative inline-flex items-center cursor-pointer">
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Poles"}</span>
+                            <input type="checkbox" checked={*is_lqr} onchange={lqr_change} class="sr-only peer"/>
+                            <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"LQR"}</span>
+                        </label>
+                    </div>
+                </div>
+
+                if *is_lqr {
+                    <Input
+                        r#type="number"
+                        name="output_weight"
+                        r#ref={output_weight_ref}
+                        handle={output_weight_handle}
+                        valid_handle={output_weight_valid_handle}
+                        validate_function={not_negative_valid}
+
+                        label="Q Output"
+                        required={true}
+                        error_message="Must be a not-negative number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                    <Input
+                        r#type="number"
+                        name="derivative_weight"
+                        r#ref={derivative_weight_ref}
+                        handle={derivative_weight_handle}
+                        valid_handle={derivative_weight_valid_handle}
+                        validate_function={not_negative_valid}
+
+                        label="Q Derivative"
+                        required={true}
+                        error_message="Must be a not-negative number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                    <Input
+                        r#type="number"
+                        name="input_weight"
+                        r#ref={input_weight_ref}
+                        handle={input_weight_handle}
+                        valid_handle={input_weight_valid_handle}
+                        validate_function={positive_valid}
+
+                        label="R Input"
+                        required={true}
+                        error_message="Must be a positive number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                } else {
+                    <Input
+                        r#type="number"
+                        name="state_feedback_omega"
+                        r#ref={omega_ref}
+                        handle={omega_handle}
+                        valid_handle={omega_valid_handle}
+                        validate_function={positive_valid}
+
+                        label="Natural Frequency [rad/s]"
+                        required={true}
+                        error_message="Must be a positive number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                    <Input
+                        r#type="number"
+                        name="state_feedback_damping"
+                        r#ref={damping_ref}
+                        handle={damping_handle}
+                        valid_handle={damping_valid_handle}
+                        validate_function={positive_valid}
+
+                        label="Damping"
+                        required={true}
+                        error_message="Must be a positive number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                }
+
+                <div class="flex flex-col w-48 pl-2 pr-2">
+                    <label for="observer_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                        { "Observer" }
+                    </label>
+                    <div id="observer_label">
+                        <label class="relative inline-flex items-center cursor-pointer">
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Luenberger"}</span>
+                            <input type="checkbox" checked={*is_kalman} onchange={kalman_change} class="sr-only peer"/>
+                            <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                            <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Kalman"}</span>
+                        </label>
+                    </div>
+                </div>
+
+                if *is_kalman {
+                    <Input
+                        r#type="number"
+                        name="process_noise"
+                        r#ref={process_noise_ref}
+                        handle={process_noise_handle}
+                        valid_handle={process_noise_valid_handle}
+                        validate_function={not_negative_valid}
+
+                        label="Process Noise"
+                        required={true}
+                        error_message="Must be a not-negative number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                    <Input
+                        r#type="number"
+                        name="measurement_noise"
+                        r#ref={measurement_noise_ref}
+                        handle={measurement_noise_handle}
+                        valid_handle={measurement_noise_valid_handle}
+                        validate_function={positive_valid}
+
+                        label="Measurement Noise"
+                        required={true}
+                        error_message="Must be a positive number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                } else {
+                    <Input
+                        r#type="number"
+                        name="speed_factor"
+                        r#ref={speed_factor_ref}
+                        handle={speed_factor_handle}
+                        valid_handle={speed_factor_valid_handle}
+                        validate_function={positive_valid}
+
+                        label="Observer Speed Factor"
+                        required={true}
+                        error_message="Must be a positive number"
+                        class="form-field w-32 pl-2 pr-2"
+                        label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                        input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                        error_class="text-red-800 dark:text-red-200"
+                    />
+                }
+            </div>
+            <div class="flex flex-col pt-2 pl-2">
+                { result_view }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,151 @@
+use input_rs::yew::Input;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use log::debug;
+
+use crate::components::hooks::use_emit_changed;
+use crate::control::timing::{Jitter, TimingSetup};
+
+
+#[derive(Properties, PartialEq)]
+pub struct TimingDialogProps {
+    pub config: TimingSetup,
+    pub on_update: Callback<TimingSetup>,
+}
+
+/// Jitter of the sampling instants and computation delay of the controller
+#[function_component(TimingDialog)]
+pub fn timing_dialog(props: &TimingDialogProps) -> Html {
+
+    fn not_negative_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value >= 0.0 ,
+            Err(_) => false,
+        }
+    }
+
+    fn seed_valid(s: String) -> bool {
+        s.parse::<u64>().is_ok()
+    }
+
+    let config = props.config.clone();
+    debug!("TimingDialog - Entry: {:?}", config);
+
+    let jitter_name = use_state(|| config.jitter.name());
+    let on_jitter_change = {
+        let jitter_name = jitter_name.clone();
+        Callback::from(move |event: Event| {
+            let target = event.target_dyn_into::<HtmlSelectElement>();
+            if let Some(select) = target {
+                let selected = select
+                    .value()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| Jitter::NAMES.get(index).copied())
+                    .unwrap_or(Jitter::NAMES[0]);
+                debug!("Jitter {}", selected);
+                jitter_name.set(selected);
+            }
+        })
+    };
+    let jitter_names = Jitter::NAMES
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            html! {
+                <option value={index.to_string()} selected={*name == *jitter_name}>
+                    { *name }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let amount_ref = use_node_ref();
+    let amount_handle = use_state(|| config.jitter.amount().to_string());
+    let amount_valid_handle = use_state(|| true);
+
+    let delay_ref = use_node_ref();
+    let delay_handle = use_state(|| config.computation_delay.to_string());
+    let delay_valid_handle = use_state(|| true);
+
+    let seed_ref = use_node_ref();
+    let seed_handle = use_state(|| config.seed.to_string());
+    let seed_valid_handle = use_state(|| true);
+
+    let jitter = Jitter::from_name(*jitter_name, (*amount_handle).parse::<f64>().unwrap_or_default());
+    let updated = config.clone()
+        .set_jitter(jitter)
+        .set_computation_delay((*delay_handle).parse::<f64>().unwrap_or_default())
+        .set_seed((*seed_handle).parse::<u64>().unwrap_or(config.seed));
+    use_emit_changed(updated, config, props.on_update.clone());
+
+    let amount_label = match jitter {
+        Jitter::Random { .. } => "Jitter Std. Deviation [ms]",
+        _ => "Jitter Bound [ms]",
+    };
+
+    html! {
+        <form  class="flex flex-row m-2 rounded border p-2 border-gray-400 dark:border-gray-600">
+            <div class="flex flex-col w-40 pl-2 pr-2">
+                <label for="jitter_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Sampling Jitter" } </label>
+                <select name={"jitter"} onchange={on_jitter_change}
+                    class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                id="jitter_label">
+                    { jitter_names }
+                </select>
+            </div>
+
+            if jitter != Jitter::None {
+                <Input
+                    r#type="number"
+                    name="jitter_amount"
+                    r#ref={amount_ref}
+                    handle={amount_handle}
+                    valid_handle={amount_valid_handle}
+                    validate_function={not_negative_valid}
+
+                    label={amount_label}
+                    required={true}
+                    error_message="Must be a not-negative number"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+                <Input
+                    r#type="number"
+                    name="jitter_seed"
+                    r#ref={seed_ref}
+                    handle={seed_handle}
+                    valid_handle={seed_valid_handle}
+                    validate_function={seed_valid}
+
+                    label="Seed"
+                    required={true}
+                    error_message="Must be a not-negative integer"
+                    class="form-field w-32 pl-2 pr-2"
+                    label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                    input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                    error_class="text-red-800 dark:text-red-200"
+                />
+            }
+
+            <Input
+                r#type="number"
+                name="computation_delay"
+                r#ref={delay_ref}
+                handle={delay_handle}
+                valid_handle={delay_valid_handle}
+                validate_function={not_negative_valid}
+
+                label="Computation Delay [ms]"
+                required={true}
+                error_message="Must be a not-negative number"
+                class="form-field w-32 pl-2 pr-2"
+                label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                error_class="text-red-800 dark:text-red-200"
+            />
+        </form>
+    }
+}
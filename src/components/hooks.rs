@@ -0,0 +1,17 @@
+use yew::prelude::*;
+
+/// Emits the value a dialog derives from its inputs after the render, not during it.
+///
+/// The value is emitted when it changed since the last render and differs from `current`,
+/// the value the dialog was given. A change of `current` alone is not sent back.
+#[hook]
+pub fn use_emit_changed<T>(value: T, current: T, on_update: Callback<T>)
+where
+    T: Clone + PartialEq + 'static,
+{
+    use_effect_with(value, move |value| {
+        if *value != current {
+            on_update.emit(value.clone());
+        }
+    });
+}
@@ -1,8 +1,14 @@
 pub mod control;
 pub mod customized_about;
+pub mod download;
+pub mod hooks;
+pub mod plot_cascade;
 pub mod plot_element;
 pub mod plot_control;
+pub mod plot_live;
 pub mod plot_time_signal;
+pub mod sampled_plot;
+pub mod simulation;
 pub mod theme_toggle;
 pub mod time_range;
 
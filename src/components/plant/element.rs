@@ -45,7 +45,8 @@ pub fn accordeon_elements(props: &AccordeonElementsProps) -> Html {
 
         Callback::from(move |_| {
             let mut elements = (*elements_handle).clone();
-            let new = (*new_handle).clone();
+            // the name is taken from its input when the element is added, not on every render
+            let new = (*new_handle).clone().set_name((*name_handle).clone());
             info!("Add new element: {}", new);
             elements.push(new.clone());
             let new_name = format!("{}-{}", new.element.short_type_name(), elements.len() + 1);
@@ -130,10 +131,6 @@ pub fn accordeon_elements(props: &AccordeonElementsProps) -> Html {
         })
     };
 
-    let name = (*name_handle).parse::<String>().unwrap_or_default();
-    let new_value = (*new_handle).clone().set_name(name.clone());
-    new_handle.set(new_value);
-
     html! {
         <Accordion
             expand={expand}
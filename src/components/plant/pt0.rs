@@ -3,9 +3,10 @@ use log::info;
 use yew::prelude::*;
 
 
+use crate::components::hooks::use_emit_changed;
 use crate::components::plant::BoxedElementDialogProps;
 use crate::plant::registry::{register_element, YewElement};
-use cb_simulation_util::plant::{pt0::PT0, DynTransferTimeDomain, TypeIdentifier};
+use cb_simulation_util::plant::{pt0::PT0, BoxedTransferTimeDomain, DynTransferTimeDomain, TypeIdentifier};
 
 pub struct YewStep {
     element: PT0<f64>,
@@ -52,7 +53,7 @@ pub fn register() {
 pub fn pt0_element_dialog(props: &BoxedElementDialogProps) -> Html {
     // Runtime reflection (downcasting to concrete type)
     // Variable assignment must be done outside the html! macro
-    let updated = if let Some(pt0) = props.element.clone().as_any().downcast_ref::<PT0<f64>>() {
+    let current = if let Some(pt0) = props.element.clone().as_any().downcast_ref::<PT0<f64>>() {
         pt0.clone()
     } else {
         PT0::<f64>::default()
@@ -70,11 +71,11 @@ pub fn pt0_element_dialog(props: &BoxedElementDialogProps) -> Html {
     }
 
     let kp_ref = use_node_ref();
-    let kp_handle = use_state(|| updated.kp.to_string());
+    let kp_handle = use_state(|| current.kp.to_string());
     let kp_valid_handle = use_state(|| true);
 
     let t0_time_ref = use_node_ref();
-    let t0_time_handle = use_state(|| updated.t0_time.to_string());
+    let t0_time_handle = use_state(|| current.t0_time.to_string());
     let t0_time_valid_handle = use_state(|| true);
 
     let updated = PT0::<f64>::default()
@@ -82,7 +83,11 @@ pub fn pt0_element_dialog(props: &BoxedElementDialogProps) -> Html {
         .set_t0_time_or_default((*t0_time_handle).parse::<f64>().unwrap_or(1.0))
         .set_kp((*kp_handle).parse::<f64>().unwrap_or(1.0));
     info!("PT0 updated: {}", updated);
-    props.on_update.emit(Box::new(updated));
+    use_emit_changed(
+        updated,
+        current,
+        props.on_update.reform(|pt0: PT0<f64>| Box::new(pt0) as BoxedTransferTimeDomain<f64>),
+    );
 
     html! {
         <div>
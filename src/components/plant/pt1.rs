@@ -2,9 +2,10 @@ use input_rs::yew::Input;
 use log::info;
 use yew::prelude::*;
 
+use crate::components::hooks::use_emit_changed;
 use crate::components::plant::BoxedElementDialogProps;
 use crate::plant::registry::{register_element, YewElement};
-use cb_simulation_util::plant::{pt1::PT1, DynTransferTimeDomain, TypeIdentifier};
+use cb_simulation_util::plant::{pt1::PT1, BoxedTransferTimeDomain, DynTransferTimeDomain, TypeIdentifier};
 
 pub struct YewStep {
     element: PT1<f64>,
@@ -51,7 +52,7 @@ pub fn register() {
 pub fn pt1_element_dialog(props: &BoxedElementDialogProps) -> Html {
     // Runtime reflection (downcasting to concrete type)
     // Variable assignment must be done outside the html! macro
-    let updated = if let Some(pt1) = props.element.clone().as_any().downcast_ref::<PT1<f64>>() {
+    let current = if let Some(pt1) = props.element.clone().as_any().downcast_ref::<PT1<f64>>() {
         pt1.clone()
     } else {
         PT1::<f64>::default()
@@ -73,11 +74,11 @@ pub fn pt1_element_dialog(props: &BoxedElementDialogProps) -> Html {
     };
 
     let kp_ref = use_node_ref();
-    let kp_handle = use_state(|| updated.kp.to_string());
+    let kp_handle = use_state(|| current.kp.to_string());
     let kp_valid_handle = use_state(|| true);
 
     let t1_time_ref = use_node_ref();
-    let t1_time_handle = use_state(|| updated.t1_time.to_string());
+    let t1_time_handle = use_state(|| current.t1_time.to_string());
     let t1_time_valid_handle = use_state(|| true);
 
     let updated = PT1::<f64>::default()
@@ -85,7 +86,11 @@ pub fn pt1_element_dialog(props: &BoxedElementDialogProps) -> Html {
         .set_t1_time_or_default((*t1_time_handle).parse::<f64>().unwrap_or_default())
         .set_kp((*kp_handle).parse::<f64>().unwrap_or(1.0));
     info!("PT1 updated: {}", updated);
-    props.on_update.emit(Box::new(updated));
+    use_emit_changed(
+        updated,
+        current,
+        props.on_update.reform(|pt1: PT1<f64>| Box::new(pt1) as BoxedTransferTimeDomain<f64>),
+    );
 
     html! {
         <div>
@@ -2,9 +2,10 @@ use input_rs::yew::Input;
 use log::info;
 use yew::prelude::*;
 
+use crate::components::hooks::use_emit_changed;
 use crate::components::plant::BoxedElementDialogProps;
 use crate::plant::registry::{register_element, YewElement};
-use cb_simulation_util::plant::{pt2::PT2, DynTransferTimeDomain, TypeIdentifier};
+use cb_simulation_util::plant::{pt2::PT2, BoxedTransferTimeDomain, DynTransferTimeDomain, TypeIdentifier};
 
 pub struct YewStep {
     element: PT2<f64>,
@@ -51,7 +52,7 @@ pub fn register() {
 pub fn pt2_element_dialog(props: &BoxedElementDialogProps) -> Html {
     // Runtime reflection (downcasting to concrete type)
     // Variable assignment must be done outside the html! macro
-    let updated = if let Some(pt2) = props.element.clone().as_any().downcast_ref::<PT2<f64>>() {
+    let current = if let Some(pt2) = props.element.clone().as_any().downcast_ref::<PT2<f64>>() {
         pt2.clone()
     } else {
         PT2::<f64>::default()
@@ -80,15 +81,15 @@ pub fn pt2_element_dialog(props: &BoxedElementDialogProps) -> Html {
     };
 
     let kp_ref = use_node_ref();
-    let kp_handle = use_state(|| updated.kp.to_string());
+    let kp_handle = use_state(|| current.kp.to_string());
     let kp_valid_handle = use_state(|| true);
 
     let t1_time_ref = use_node_ref();
-    let t1_time_handle = use_state(|| (1.0 / updated.omega).to_string());
+    let t1_time_handle = use_state(|| (1.0 / current.omega).to_string());
     let t1_time_valid_handle = use_state(|| true);
 
     let damping_ref = use_node_ref();
-    let damping_handle = use_state(|| updated.damping.to_string());
+    let damping_handle = use_state(|| current.damping.to_string());
     let damping_valid_handle = use_state(|| true);
 
 
@@ -98,7 +99,11 @@ pub fn pt2_element_dialog(props: &BoxedElementDialogProps) -> Html {
         .set_damping_or_default((*damping_handle).parse::<f64>().unwrap_or_default())
         .set_kp((*kp_handle).parse::<f64>().unwrap_or(1.0));
     info!("PT2 updated: {}", updated);
-    props.on_update.emit(Box::new(updated));
+    use_emit_changed(
+        updated,
+        current,
+        props.on_update.reform(|pt2: PT2<f64>| Box::new(pt2) as BoxedTransferTimeDomain<f64>),
+    );
 
     html! {
         <div>
@@ -0,0 +1,303 @@
+use std::rc::Rc;
+
+use accordion_rs::yew::{Accordion, Item, List};
+use accordion_rs::Size;
+use input_rs::yew::Input;
+use ndarray::Array1;
+use plotly::common::AxisSide;
+use plotly::{layout::Axis, Layout};
+use yew::prelude::*;
+use yew_plotly::plotly::common::{Mode, Title};
+use web_sys::HtmlSelectElement;
+use log::info;
+
+use crate::components::sampled_plot::{trace, SampledPlot, SampledPlotly};
+use crate::components::simulation::{use_simulation, Computation, Simulation, SimulationProgress};
+use crate::control::pid_setup::PidSetup;
+use crate::plant::named_element::NamedElement;
+use crate::simulation::{CascadeSetup, JobRun, SimulationJob, SimulationResult};
+use crate::time_signal::named_time_signal::NamedTimeSignal;
+use cb_simulation_util::signal::TimeRange;
+
+#[derive(Properties, PartialEq)]
+pub struct CascadeProps {
+    #[prop_or_default]
+    pub range: TimeRange,
+    #[prop_or_default]
+    pub signal: NamedTimeSignal<f64>,
+    #[prop_or_default]
+    pub outer_element: NamedElement<f64>,
+    #[prop_or_default]
+    pub inner_element: NamedElement<f64>,
+    #[prop_or_default]
+    pub outer_controller: PidSetup,
+    #[prop_or_default]
+    pub inner_controller: PidSetup,
+    /// The outer controller is executed at every `outer_ratio`-th sample of the inner loop
+    #[prop_or(1)]
+    pub outer_ratio: usize,
+}
+
+/// Plot of one loop of the cascade: setpoint and process variable left, control variable right
+fn loop_plot(title: &str, time: &Array1<f64>, r: &Array1<f64>, y: &Array1<f64>, u: &Array1<f64>) -> SampledPlot {
+    let mut plot = SampledPlot::new();
+    let setpoint_trace = trace()
+        .mode(Mode::LinesMarkers)
+        .show_legend(true)
+        .name("r: Setpoint");
+    let process_variable_trace = trace()
+        .mode(Mode::LinesMarkers)
+        .show_legend(true)
+        .name("y: Process Variable");
+    let control_variable_trace = trace()
+        .mode(Mode::LinesMarkers)
+        .show_legend(true)
+        .name("u: Control Variable")
+        .y_axis("y2");
+
+    plot.add_trace(setpoint_trace, time, r);
+    plot.add_trace(control_variable_trace, time, u);
+    plot.add_trace(process_variable_trace, time, y);
+
+    let layout = Layout::new()
+        .title(format!("<b>{}</b>", title).as_str().into())
+        .x_axis(
+            Axis::new().title("time [ms]".into()), // plotly 0.8.3 does not support From<String>
+        )
+        .y_axis(Axis::new().title("Setpoint Process Variable".into()))
+        .y_axis2(
+            Axis::new()
+                .title(Title::from("Control Variable"))
+                .overlaying("y")
+                .side(AxisSide::Right),
+        );
+    plot.set_layout(layout);
+    plot
+}
+
+#[function_component(PlotCascade)]
+pub fn plotly_cascade(props: &CascadeProps) -> Html {
+    let setup = CascadeSetup::default()
+        .set_range(props.range.clone())
+        .set_setpoint(props.signal.clone())
+        .set_outer_element(props.outer_element.clone())
+        .set_inner_element(props.inner_element.clone())
+        .set_outer_controller(props.outer_controller.clone())
+        .set_inner_controller(props.inner_controller.clone())
+        .set_outer_ratio(props.outer_ratio);
+    // the cascade is only simulated again if one of its inputs changed
+    let computation = use_memo(setup, |setup| {
+        let local = setup.clone();
+        Rc::new(Computation::new(SimulationJob::cascade(setup), move || JobRun::cascade(local)))
+    });
+    let result = match use_simulation((*computation).clone()) {
+        Simulation::Done(result) => result,
+        Simulation::Running(fraction) => return html! { <SimulationProgress fraction={fraction} /> },
+        Simulation::Failed(error) => return html! { <div class="text-red-800 dark:text-red-200">{ error }</div> },
+    };
+    let SimulationResult::Cascade(result) = &*result else {
+        return html! {};
+    };
+    info!("Plot for cascade with {} samples", result.time.len());
+
+    let outer_plot = loop_plot(
+        "Outer Loop of Cascade in Time Domain",
+        &result.time,
+        &result.setpoint,
+        &result.y_outer,
+        &result.inner_setpoint,
+    );
+    let inner_plot = loop_plot(
+        "Inner Loop of Cascade in Time Domain",
+        &result.time,
+        &result.inner_setpoint,
+        &result.y_inner,
+        &result.u_inner,
+    );
+
+    html! {
+        <>
+            <SampledPlotly plot={outer_plot}/>
+            <SampledPlotly plot={inner_plot}/>
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct AccordeonPlotCascadeProps {
+    #[prop_or_default]
+    pub range: TimeRange,
+    pub signals: Vec<NamedTimeSignal<f64>>,
+    pub elements: Vec<NamedElement<f64>>,
+    pub outer_controller: PidSetup,
+    pub inner_controller: PidSetup,
+}
+
+#[function_component(AccordeonPlotCascade)]
+pub fn accordeon_plot_cascade(props: &AccordeonPlotCascadeProps) -> Html {
+    let expand = use_state(|| false);
+
+    let signal_names = props
+        .signals
+        .iter()
+        .enumerate()
+        .map(|(index, signal)| {
+            html! {
+                <option value={index.to_string()}
+                // if the list get changed always the first element is selected
+                selected={index == 0}>
+                    { signal.name.clone() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+    let element_names = props
+        .elements
+        .iter()
+        .enumerate()
+        .map(|(index, element)| {
+            html! {
+                <option value={index.to_string()}
+                // if the list get changed always the first element is selected
+                selected={index == 0}>
+                    { element.name.clone() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let initial_selected_signal = if !props.signals.is_empty() {
+        "0".to_string()
+    } else {
+        "".to_string()
+    };
+    let selected_signal = use_state(|| initial_selected_signal);
+    let initial_selected_element = if !props.elements.is_empty() {
+        "0".to_string()
+    } else {
+        "".to_string()
+    };
+    let selected_outer_element = use_state(|| initial_selected_element.clone());
+    let selected_inner_element = use_state(|| initial_selected_element);
+
+    let selected_signal_clone = selected_signal.clone();
+    let on_signal_change = Callback::from(move |event: Event| {
+        let target = event.target_dyn_into::<HtmlSelectElement>();
+        if let Some(select) = target {
+            selected_signal_clone.set(select.value());
+        }
+    });
+    let selected_outer_element_clone = selected_outer_element.clone();
+    let on_outer_element_change = Callback::from(move |event: Event| {
+        let target = event.target_dyn_into::<HtmlSelectElement>();
+        if let Some(select) = target {
+            selected_outer_element_clone.set(select.value());
+        }
+    });
+    let selected_inner_element_clone = selected_inner_element.clone();
+    let on_inner_element_change = Callback::from(move |event: Event| {
+        let target = event.target_dyn_into::<HtmlSelectElement>();
+        if let Some(select) = target {
+            selected_inner_element_clone.set(select.value());
+        }
+    });
+
+    fn ratio_valid(s: String) -> bool {
+        match s.parse::<usize>() {
+            Ok(value) => value >= 1,
+            Err(_) => false,
+        }
+    }
+
+    let ratio_ref = use_node_ref();
+    let ratio_handle = use_state(|| "1".to_string());
+    let ratio_valid_handle = use_state(|| true);
+    let outer_ratio = (*ratio_handle).parse::<usize>().unwrap_or(1).max(1);
+
+    html! {
+        <Accordion
+            expand={expand}
+            expanded={html! { "Plot Cascade Control" } }
+            collapsed={html! { "Show Plot of Cascade Control" } }
+            expanded_class=" bg-gradient-to-r from-blue-700 to-blue-500 text-white p-2 rounded"
+            collapsed_class="bg-gradient-to-r from-green-700 to-green-500 text-white p-2 rounded"
+            class="w-full p-4 rounded border border-gray-400 dark:border-gray-600"
+            size={Size::Custom("auto")}
+        >
+            <List>
+                <Item class="flex flex-row">
+                    <div class="flex flex-col w-64">
+                        <label for="cascade_signal_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Select Signal by Name" } </label>
+                        <select name={"signal"} onchange={on_signal_change}
+                            class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                        id="cascade_signal_label">
+                            { signal_names }
+                        </select>
+                    </div>
+                    <div class="flex flex-col w-64">
+                        <label for="cascade_outer_element_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Select Outer Element by Name" } </label>
+                        <select name={"outer_element"} onchange={on_outer_element_change}
+                            class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                        id="cascade_outer_element_label">
+                            { element_names.clone() }
+                        </select>
+                    </div>
+                    <div class="flex flex-col w-64">
+                        <label for="cascade_inner_element_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Select Inner Element by Name" } </label>
+                        <select name={"inner_element"} onchange={on_inner_element_change}
+                            class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                        id="cascade_inner_element_label">
+                            { element_names }
+                        </select>
+                    </div>
+                    <form class="flex flex-row">
+                        <Input
+                            r#type="number"
+                            min="1"
+                            name="outer_ratio"
+                            r#ref={ratio_ref}
+                            handle={ratio_handle}
+                            valid_handle={ratio_valid_handle}
+                            validate_function={ratio_valid}
+
+                            label="Inner samples per outer sample"
+                            required={true}
+                            error_message="Must be a positive integer"
+                            class="form-field w-64"
+                            label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                            input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                            error_class="text-red-800"
+                        />
+                    </form>
+                </Item>
+                {
+                    if props.signals.is_empty() {
+                        html! { <Item>{"No signals available"}</Item> }
+                    } else {
+                        let index = selected_signal.parse::<usize>().unwrap_or(0);
+                        let outer_index = selected_outer_element.parse::<usize>().unwrap_or(0);
+                        let inner_index = selected_inner_element.parse::<usize>().unwrap_or(0);
+
+                        match (props.signals.get(index), props.elements.get(outer_index), props.elements.get(inner_index)) {
+                            (Some(signal), Some(outer_element), Some(inner_element)) => html! {
+                                <Item>
+                                    <PlotCascade
+                                        range={props.range.clone()}
+                                        signal={signal.clone()}
+                                        outer_element={outer_element.clone()}
+                                        inner_element={inner_element.clone()}
+                                        outer_controller={props.outer_controller.clone()}
+                                        inner_controller={props.inner_controller.clone()}
+                                        outer_ratio={outer_ratio}
+                                    />
+                                </Item>
+                            },
+                            (None, _, _) => html! { <Item>{"No signals available"}</Item> },
+                            _ => html! { <Item>{"No elements available"}</Item> },
+                        }
+                    }
+                }
+            </List>
+        </Accordion>
+    }
+}
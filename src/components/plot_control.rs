@@ -1,83 +1,261 @@
+use std::rc::Rc;
+
 use accordion_rs::yew::{Accordion, Item, List};
 use accordion_rs::Size;
-use ndarray::{Array1};
-use plotly::common::AxisSide;
-use plotly::{layout::Axis, Layout, Scatter};
+use input_rs::yew::Input;
+use plotly::common::{AxisSide, Line, LineShape};
+use plotly::{layout::Axis, Layout};
 use yew::prelude::*;
 use yew_plotly::plotly::common::{Mode, Title};
-use yew_plotly::plotly::Plot;
-use yew_plotly::Plotly;
 use web_sys::HtmlSelectElement;
-use log::info;
+use log::{error, info};
 
+use crate::components::control::feedforward::FeedforwardDialog;
+use crate::components::control::instrument::{ActuatorDialog, SensorDialog};
+use crate::components::control::mode_switch::ModeSwitchDialog;
+use crate::components::control::timing::TimingDialog;
+use crate::components::download::download;
+use crate::components::sampled_plot::{trace, SampledPlot, SampledPlotly};
+use crate::components::simulation::{use_simulation, Computation, Simulation, SimulationProgress};
+use crate::components::time_signal::optional_signal_select::OptionalSignalSelect;
+use crate::control::feedforward::FeedforwardSetup;
+use crate::control::gain_schedule::SchedulingVariable;
+use crate::control::instrument::{ActuatorSetup, SensorSetup};
+use crate::control::mode_switch::ModeSwitchSetup;
+use crate::control::pid_law::Discretization;
+use crate::control::pid_setup::PidSetup;
+use crate::control::precision::Precision;
+use crate::control::timing::TimingSetup;
 use crate::plant::named_element::NamedElement;
+use crate::simulation::{JobRun, LoopResult, LoopSetup, SimulationJob, SimulationResult};
 use crate::time_signal::named_time_signal::NamedTimeSignal;
 use cb_simulation_util::signal::TimeRange;
 
-use cb_controller::pid::PidController;
-
 #[derive(Properties, PartialEq)]
 pub struct ControlProps {
     #[prop_or_default]
-    pub range: TimeRange,
-    #[prop_or_default]
-    pub signal: NamedTimeSignal<f64>,
+    pub setup: LoopSetup,
+    /// Adds the integrator state of the PID to the plot
     #[prop_or_default]
-    pub element: NamedElement<f64>,
+    pub show_integral: bool,
+    /// Overlays the responses of the other discretization methods
     #[prop_or_default]
-    pub controller: PidController<f64>,
+    pub compare_discretizations: bool,
+    /// Overlays the responses with the controller computed in the other number formats
     #[prop_or_default]
-    pub open_loop: bool,
+    pub compare_precisions: bool,
+}
+
+/// Variant of the loop overlaid on the plot for comparison
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    label: String,
+    /// Adds the control variable besides the process variable
+    control_variable: bool,
+    /// Draws the control variable held between the controller samples
+    held: bool,
+}
+
+impl Comparison {
+    fn new(label: &str, control_variable: bool, held: bool) -> Self {
+        Comparison { label: label.to_owned(), control_variable, held }
+    }
+}
+
+/// Variants of the loop compared with the configured one
+fn comparisons(setup: &LoopSetup, compare_discretizations: bool, compare_precisions: bool) -> Vec<(Comparison, LoopSetup)> {
+    let mut comparisons = Vec::new();
+    if setup.feedforward.is_active() {
+        // compare with pure feedback to visualize the benefit of feedforward
+        comparisons.push((
+            Comparison::new("feedback only", false, false),
+            setup.clone().set_feedforward(FeedforwardSetup::default()),
+        ));
+    }
+    if setup.controller.smith_predictor.is_some() {
+        // compare with the plain PID on the same dead-time plant
+        let plain_pid = setup.controller.clone().set_smith_predictor(None);
+        comparisons.push((Comparison::new("plain PID", false, false), setup.clone().set_controller(plain_pid)));
+    }
+    if setup.controller.mpc.is_some() {
+        // compare with the plain PID on the same, possibly constrained plant, neither MPC nor state feedback
+        let pid = setup.controller.clone().set_mpc(None).set_state_feedback(None);
+        comparisons.push((Comparison::new("PID", true, false), setup.clone().set_controller(pid)));
+    }
+    if compare_discretizations {
+        // all variants run through the same PID law, the difference grows with the sampling interval
+        for discretization in Discretization::ALL {
+            if discretization == setup.controller.options.discretization {
+                continue;
+            }
+            let options = setup.controller.options.clone().set_discretization(discretization);
+            let controller = setup.controller.clone().set_options(options);
+            comparisons.push((
+                Comparison::new(&discretization.to_string(), false, false),
+                setup.clone().set_controller(controller),
+            ));
+        }
+    }
+    if compare_precisions {
+        // all variants run through the same PID law, only its arithmetic is rounded;
+        // rounding and saturation of the target arithmetic show as limit cycles and offsets
+        for precision in Precision::ALL {
+            if precision == setup.controller.options.precision {
+                continue;
+            }
+            let options = setup.controller.options.clone().set_precision(precision);
+            let controller = setup.controller.clone().set_options(options);
+            comparisons.push((
+                Comparison::new(&precision.to_string(), true, true),
+                setup.clone().set_controller(controller),
+            ));
+        }
+    }
+    if setup.manual.is_some() && !setup.open_loop {
+        // the other transfer makes the bump at the mode switch visible
+        let mode_switch = setup.mode_switch.clone().set_bumpless(!setup.mode_switch.bumpless);
+        let label = if mode_switch.bumpless { "bumpless" } else { "with bump" };
+        comparisons.push((Comparison::new(label, true, false), setup.clone().set_mode_switch(mode_switch)));
+    }
+    if !setup.timing.is_ideal() {
+        // the sensitivity of the tuning shows in the deviation from the ideal sampling
+        comparisons.push((
+            Comparison::new("ideal timing", true, true),
+            setup.clone().set_timing(TimingSetup::default()),
+        ));
+    }
+    comparisons
 }
 
 #[function_component(PlotControl)]
 pub fn plotly_time_signal(props: &ControlProps) -> Html {
-    let time: Array1<f64> = props.range.collect();
-    let signal = props.signal.signal.clone();
-    let setpoint: Array1<f64> = time.iter().map(|v| signal.time_to_signal(*v)).collect();
-
-    let mut element = props.element.element.clone();
-
-    let mut pid = props.controller.clone();
-    info!("Plot for PID Controller: {:?}", pid);
-
-    let (control_variable, process_variable) = if props.open_loop {
-        let y_no_feedback = 0.0; // open loop update
-        // controller output u: aka control variable
-        let u_open_loop: Array1<f64> = setpoint.iter().map(|sp| pid.update(y_no_feedback, *sp)).collect();
-        // plant output y: aka process variable
-        let y_open_loop: Array1<f64> = u_open_loop.iter().map(|v| element.transfer_td(*v)).collect();
-        (u_open_loop, y_open_loop)
-    } else {
-        let dim = setpoint.len();
-        let mut u = Array1::zeros(dim);
-        let mut y = Array1::zeros(dim);
-        for i in 0..(dim-1) {
-            y[i] = element.transfer_td(u[i]);
-            u[i+1] = pid.update(setpoint[i], y[i]);
-        }
-        ( u, y)
+    let setup = &props.setup;
+    // the configured loop first, then the compared variants
+    let runs = use_memo(
+        (setup.clone(), props.show_integral, props.compare_discretizations, props.compare_precisions),
+        |(setup, show_integral, compare_discretizations, compare_precisions)| {
+            let (labels, variants): (Vec<Comparison>, Vec<LoopSetup>) =
+                comparisons(setup, *compare_discretizations, *compare_precisions).into_iter().unzip();
+            let setups: Vec<LoopSetup> =
+                std::iter::once(setup.clone().set_record_integral(*show_integral)).chain(variants).collect();
+            let computation = Computation::new(SimulationJob::loops(&setups), move || JobRun::loops(setups));
+            (labels, Rc::new(computation))
+        },
+    );
+    let (labels, computation) = &*runs;
+    let result = match use_simulation(computation.clone()) {
+        Simulation::Done(result) => result,
+        Simulation::Running(fraction) => return html! { <SimulationProgress fraction={fraction} /> },
+        Simulation::Failed(error) => return html! { <div class="text-red-800 dark:text-red-200">{ error }</div> },
+    };
+    let SimulationResult::Loops(results) = &*result else {
+        return html! {};
+    };
+    let Some((main, compared)) = results.split_first() else {
+        return html! {};
+    };
+    // the export keeps all samples, the plot shows them reduced
+    let on_download = {
+        let main = main.clone();
+        Callback::from(move |_| {
+            if let Err(error) = download("control_loop.csv", &main.to_csv(), "text/csv") {
+                error!("Download failed: {:?}", error);
+            }
+        })
     };
+    let LoopResult { time, setpoint, u: control_variable, applied, y: process_variable, measured, disturbance, output_disturbance, integral, .. } =
+        main.clone();
+    let measurement_error = &process_variable - &measured;
 
-    let mut plot = Plot::new();
-    let setpoint_trace = Scatter::from_array(time.clone(), setpoint)
+    let mut plot = SampledPlot::new();
+    let setpoint_trace = trace()
         .mode(Mode::LinesMarkers)
         .show_legend(true)
         .name("r: Setpoint");
-    let process_variable_trace = Scatter::from_array(time.clone(), process_variable)
+    let process_variable_trace = trace()
         .mode(Mode::LinesMarkers)
         .show_legend(true)
         .name("y: Process Variable");
-    let control_variable_trace = Scatter::from_array(time.clone(), control_variable)
+    // the control variable is held between the controller samples
+    let control_variable_trace = trace()
         .mode(Mode::LinesMarkers)
+        .line(Line::new().shape(LineShape::Hv))
         .show_legend(true)
         .name("u: Control Variable")
         .y_axis("y2");
 
 
-    plot.add_trace(setpoint_trace);
-    plot.add_trace(control_variable_trace);
-    plot.add_trace(process_variable_trace);
+    plot.add_trace(setpoint_trace, &time, &setpoint);
+    plot.add_trace(control_variable_trace, &time, &control_variable);
+    plot.add_trace(process_variable_trace, &time, &process_variable);
+
+    if let Some(integral) = integral {
+        let integral_trace = trace()
+            .mode(Mode::Lines)
+            .show_legend(true)
+            .name("I: Integrator State")
+            .y_axis("y2");
+        plot.add_trace(integral_trace, &time, &integral);
+    }
+    if setup.disturbance.is_some() {
+        let disturbance_trace = trace()
+            .mode(Mode::Lines)
+            .show_legend(true)
+            .name("d: Input Disturbance")
+            .y_axis("y2");
+        plot.add_trace(disturbance_trace, &time, &disturbance);
+    }
+    if setup.output_disturbance.is_some() {
+        let output_disturbance_trace = trace()
+            .mode(Mode::Lines)
+            .show_legend(true)
+            .name("d_o: Output Disturbance");
+        plot.add_trace(output_disturbance_trace, &time, &output_disturbance);
+    }
+    if setup.actuator.is_some() {
+        let applied_trace = trace()
+            .mode(Mode::Lines)
+            .line(Line::new().shape(LineShape::Hv))
+            .show_legend(true)
+            .name("u_a: Applied Control Variable")
+            .y_axis("y2");
+        plot.add_trace(applied_trace, &time, &applied);
+    }
+    if (setup.noise.is_some() || setup.sensor.is_some()) && !setup.open_loop {
+        let measured_trace = trace()
+            .mode(Mode::Lines)
+            .show_legend(true)
+            .name("y_m: Measured Process Variable");
+        let measurement_error_trace = trace()
+            .mode(Mode::Lines)
+            .show_legend(true)
+            .name("y - y_m: Measurement Error");
+        plot.add_trace(measured_trace, &time, &measured);
+        plot.add_trace(measurement_error_trace, &time, &measurement_error);
+    }
+    for (comparison, result) in labels.iter().zip(compared) {
+        if comparison.control_variable {
+            let control_trace = trace().mode(Mode::Lines);
+            let control_trace = if comparison.held {
+                control_trace.line(Line::new().shape(LineShape::Hv))
+            } else {
+                control_trace
+            };
+            plot.add_trace(
+                control_trace
+                    .show_legend(true)
+                    .name(format!("u: Control Variable ({})", comparison.label).as_str())
+                    .y_axis("y2"),
+                &time,
+                &result.u,
+            );
+        }
+        let process_trace = trace()
+            .mode(Mode::Lines)
+            .show_legend(true)
+            .name(format!("y: Process Variable ({})", comparison.label).as_str());
+        plot.add_trace(process_trace, &time, &result.y);
+    }
 
     let layout = Layout::new()
         .title("<b>Control Loop in Time Domain</b>".into())
@@ -94,7 +272,15 @@ pub fn plotly_time_signal(props: &ControlProps) -> Html {
     plot.set_layout(layout);
 
     html! {
-        <Plotly plot={plot}/>
+        <>
+            <SampledPlotly plot={plot}/>
+            <button onclick={on_download}
+                class="bg-blue-600 hover:bg-blue-700 text-white text-sm rounded-lg px-4 py-2"
+                aria-label="Download the control loop as CSV"
+            >
+                <span class="fa-solid fa-download mr-2"></span>{ "CSV" }
+            </button>
+        </>
     }
 }
 
@@ -104,7 +290,7 @@ pub struct AccordeonPlotControlProps {
     pub range: TimeRange,
     pub signals: Vec<NamedTimeSignal<f64>>,
     pub elements: Vec<NamedElement<f64>>,
-    pub controller: PidController<f64>,
+    pub controller: PidSetup,
 }
 
 #[function_component(AccordeonPlotControl)]
@@ -168,6 +354,121 @@ pub fn accordeon_time_signal(props: &AccordeonPlotControlProps) -> Html {
         }
     });
 
+    // the disturbances, the noise and the manual profile are optional
+    let selected_disturbance = use_state(|| None::<usize>);
+    let on_disturbance_change = {
+        let selected_disturbance = selected_disturbance.clone();
+        Callback::from(move |index: Option<usize>| selected_disturbance.set(index))
+    };
+    let disturbance = selected_disturbance.and_then(|index| props.signals.get(index).cloned());
+
+    let selected_output_disturbance = use_state(|| None::<usize>);
+    let on_output_disturbance_change = {
+        let selected_output_disturbance = selected_output_disturbance.clone();
+        Callback::from(move |index: Option<usize>| selected_output_disturbance.set(index))
+    };
+    let output_disturbance = selected_output_disturbance.and_then(|index| props.signals.get(index).cloned());
+
+    let selected_noise = use_state(|| None::<usize>);
+    let on_noise_change = {
+        let selected_noise = selected_noise.clone();
+        Callback::from(move |index: Option<usize>| selected_noise.set(index))
+    };
+    let noise = selected_noise.and_then(|index| props.signals.get(index).cloned());
+
+    // without a manual profile the loop is automatic all the time
+    let selected_manual = use_state(|| None::<usize>);
+    let on_manual_change = {
+        let selected_manual = selected_manual.clone();
+        Callback::from(move |index: Option<usize>| selected_manual.set(index))
+    };
+    let manual = selected_manual.and_then(|index| props.signals.get(index).cloned());
+
+    let is_externally_scheduled = props
+        .controller
+        .gain_schedule
+        .as_ref()
+        .is_some_and(|schedule| schedule.variable == SchedulingVariable::External);
+    let selected_scheduling = use_state(|| Some(0_usize));
+    let on_scheduling_change = {
+        let selected_scheduling = selected_scheduling.clone();
+        Callback::from(move |index: Option<usize>| selected_scheduling.set(index))
+    };
+    let scheduling = if is_externally_scheduled {
+        selected_scheduling.and_then(|index| props.signals.get(index).cloned())
+    } else {
+        None
+    };
+
+    let mode_switch = use_state(ModeSwitchSetup::default);
+    let on_mode_switch_update = {
+        let mode_switch = mode_switch.clone();
+        Callback::from(move |updated: ModeSwitchSetup| {
+            info!("Mode switch update to: {:?}", updated);
+            mode_switch.set(updated);
+        })
+    };
+
+    let feedforward = use_state(FeedforwardSetup::default);
+    let on_feedforward_update = {
+        let feedforward = feedforward.clone();
+        Callback::from(move |updated: FeedforwardSetup| {
+            info!("Feedforward update to: {:?}", updated);
+            feedforward.set(updated);
+        })
+    };
+
+    let timing = use_state(TimingSetup::default);
+    let on_timing_update = {
+        let timing = timing.clone();
+        Callback::from(move |updated: TimingSetup| {
+            info!("Timing update to: {:?}", updated);
+            timing.set(updated);
+        })
+    };
+
+    let actuator = use_state(|| None::<ActuatorSetup>);
+    let on_actuator_update = {
+        let actuator = actuator.clone();
+        Callback::from(move |updated: Option<ActuatorSetup>| {
+            info!("Actuator update to: {:?}", updated);
+            actuator.set(updated);
+        })
+    };
+
+    let sensor = use_state(|| None::<SensorSetup>);
+    let on_sensor_update = {
+        let sensor = sensor.clone();
+        Callback::from(move |updated: Option<SensorSetup>| {
+            info!("Sensor update to: {:?}", updated);
+            sensor.set(updated);
+        })
+    };
+
+    fn not_negative_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value >= 0.0,
+            Err(_) => false,
+        }
+    }
+
+    let plant_dead_time_ref = use_node_ref();
+    let plant_dead_time_handle = use_state(|| "0".to_string());
+    let plant_dead_time_valid_handle = use_state(|| true);
+    let plant_dead_time = (*plant_dead_time_handle).parse::<f64>().unwrap_or_default();
+
+    fn ratio_valid(s: String) -> bool {
+        match s.parse::<usize>() {
+            Ok(value) => value >= 1,
+            Err(_) => false,
+        }
+    }
+
+    let controller_ratio_ref = use_node_ref();
+    let controller_ratio_handle = use_state(|| "1".to_string());
+    let controller_ratio_valid_handle = use_state(|| true);
+    let controller_ratio = (*controller_ratio_handle).parse::<usize>().unwrap_or(1).max(1);
+
     let is_open_loop_checked = use_state(|| false);
     let on_open_loop_change = {
         let is_open_loop_checked = is_open_loop_checked.clone();
@@ -177,6 +478,33 @@ pub fn accordeon_time_signal(props: &AccordeonPlotControlProps) -> Html {
         })
     };
 
+    let is_compare_checked = use_state(|| false);
+    let on_compare_change = {
+        let is_compare_checked = is_compare_checked.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            is_compare_checked.set(input.checked());
+        })
+    };
+
+    let is_precision_checked = use_state(|| false);
+    let on_precision_change = {
+        let is_precision_checked = is_precision_checked.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            is_precision_checked.set(input.checked());
+        })
+    };
+
+    let is_integral_checked = use_state(|| false);
+    let on_integral_change = {
+        let is_integral_checked = is_integral_checked.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            is_integral_checked.set(input.checked());
+        })
+    };
+
     html! {
         <Accordion
             expand={expand}
@@ -216,6 +544,97 @@ pub fn accordeon_time_signal(props: &AccordeonPlotControlProps) -> Html {
                             </label>
                         </div>
                     </div>
+                    <div class="flex flex-col w-64">
+                        <label for="show_integral_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Show Integrator" } </label>
+                        <div id="show_integral_label">
+                            <label class="relative inline-flex items-center cursor-pointer">
+                                <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                                <input type="checkbox" checked={*is_integral_checked} onchange={on_integral_change} class="sr-only peer"/>
+                                <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                                <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                            </label>
+                        </div>
+                    </div>
+                    <div class="flex flex-col w-64">
+                        <label for="compare_discretizations_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Compare Discretizations" } </label>
+                        <div id="compare_discretizations_label">
+                            <label class="relative inline-flex items-center cursor-pointer">
+                                <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                                <input type="checkbox" checked={*is_compare_checked} onchange={on_compare_change} class="sr-only peer"/>
+                                <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                                <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                            </label>
+                        </div>
+                    </div>
+                    <div class="flex flex-col w-64">
+                        <label for="compare_precisions_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Compare Precisions" } </label>
+                        <div id="compare_precisions_label">
+                            <label class="relative inline-flex items-center cursor-pointer">
+                                <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"No"}</span>
+                                <input type="checkbox" checked={*is_precision_checked} onchange={on_precision_change} class="sr-only peer"/>
+                                <div class="relative w-11 h-6 bg-gray-200 rounded-full peer peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600 dark:peer-checked:bg-blue-600"></div>
+                                <span class="ms-3 p-2 text-sm font-medium text-gray-900 dark:text-gray-300"> {"Yes"}</span>
+                            </label>
+                        </div>
+                    </div>
+                </Item>
+                <Item class="flex flex-wrap max-w-full">
+                    <OptionalSignalSelect id="disturbance_label" label="Select measured Disturbance at Plant Input"
+                        signals={props.signals.clone()} selected={*selected_disturbance} on_change={on_disturbance_change} />
+                    <OptionalSignalSelect id="output_disturbance_label" label="Select Load Disturbance at Plant Output"
+                        signals={props.signals.clone()} selected={*selected_output_disturbance} on_change={on_output_disturbance_change} />
+                    <OptionalSignalSelect id="noise_label" label="Select Measurement Noise"
+                        signals={props.signals.clone()} selected={*selected_noise} on_change={on_noise_change} />
+                    if !*is_open_loop_checked {
+                        <OptionalSignalSelect id="manual_label" label="Select Manual Output Profile" none_label={Some(AttrValue::Static("None (Automatic only)"))}
+                            signals={props.signals.clone()} selected={*selected_manual} on_change={on_manual_change} />
+                        if manual.is_some() {
+                            <ModeSwitchDialog config={(*mode_switch).clone()} on_update={on_mode_switch_update} />
+                        }
+                    }
+                    if is_externally_scheduled {
+                        <OptionalSignalSelect id="scheduling_label" label="Select Scheduling Signal" none_label={None::<AttrValue>}
+                            signals={props.signals.clone()} selected={*selected_scheduling} on_change={on_scheduling_change} />
+                    }
+                    <FeedforwardDialog config={(*feedforward).clone()} on_update={on_feedforward_update} />
+                    <form class="flex flex-row m-2">
+                        <Input
+                            r#type="number"
+                            name="plant_dead_time"
+                            r#ref={plant_dead_time_ref}
+                            handle={plant_dead_time_handle}
+                            valid_handle={plant_dead_time_valid_handle}
+                            validate_function={not_negative_valid}
+
+                            label="Plant Dead Time [ms]"
+                            required={true}
+                            error_message="Must be a not-negative number"
+                            class="form-field w-48"
+                            label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                            input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                            error_class="text-red-800"
+                        />
+                        <Input
+                            r#type="number"
+                            min="1"
+                            name="controller_ratio"
+                            r#ref={controller_ratio_ref}
+                            handle={controller_ratio_handle}
+                            valid_handle={controller_ratio_valid_handle}
+                            validate_function={ratio_valid}
+
+                            label="Plant steps per controller sample"
+                            required={true}
+                            error_message="Must be a positive integer"
+                            class="form-field w-64 pl-2"
+                            label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                            input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                            error_class="text-red-800"
+                        />
+                    </form>
+                    <ActuatorDialog config={(*actuator).clone()} on_update={on_actuator_update} />
+                    <SensorDialog config={(*sensor).clone()} on_update={on_sensor_update} />
+                    <TimingDialog config={(*timing).clone()} on_update={on_timing_update} />
                 </Item>
                 {
                     if props.signals.is_empty() {
@@ -231,7 +650,25 @@ pub fn accordeon_time_signal(props: &AccordeonPlotControlProps) -> Html {
                                 if let Some(element) = props.elements.get(index) {
                                     html! {
                                         <Item>
-                                            <PlotControl range={props.range.clone()} signal={signal.clone()} element={element.clone()} controller={props.controller.clone()} open_loop={ *is_open_loop_checked }/>
+                                            <PlotControl setup={LoopSetup::default()
+                                                    .set_range(props.range.clone())
+                                                    .set_setpoint(signal.clone())
+                                                    .set_element(element.clone())
+                                                    .set_controller(props.controller.clone())
+                                                    .set_open_loop(*is_open_loop_checked)
+                                                    .set_disturbance(disturbance.clone())
+                                                    .set_output_disturbance(output_disturbance.clone())
+                                                    .set_noise(noise.clone())
+                                                    .set_feedforward((*feedforward).clone())
+                                                    .set_plant_dead_time(plant_dead_time)
+                                                    .set_controller_ratio(controller_ratio)
+                                                    .set_timing((*timing).clone())
+                                                    .set_actuator((*actuator).clone())
+                                                    .set_sensor((*sensor).clone())
+                                                    .set_manual(manual.clone())
+                                                    .set_mode_switch((*mode_switch).clone())
+                                                    .set_scheduling(scheduling.clone())}
+                                                show_integral={ *is_integral_checked } compare_discretizations={ *is_compare_checked } compare_precisions={ *is_precision_checked } />
                                         </Item>
                                     }
                                 } else {
@@ -1,16 +1,16 @@
 use accordion_rs::yew::{Accordion, Item, List};
 use accordion_rs::Size;
-use ndarray::{Array, Ix1};
 use plotly::common::AxisSide;
-use plotly::{layout::Axis, Layout, Scatter};
+use plotly::{layout::Axis, Layout};
 use yew::prelude::*;
 use yew_plotly::plotly::common::{Mode, Title};
-use yew_plotly::plotly::Plot;
-use yew_plotly::Plotly;
 
 use web_sys::HtmlSelectElement;
 
+use crate::components::sampled_plot::{trace, SampledPlot, SampledPlotly};
+use crate::components::simulation::{use_simulation, Computation, Simulation, SimulationProgress};
 use crate::plant::named_element::NamedElement;
+use crate::simulation::{JobRun, SimulationJob, SimulationResult};
 use crate::time_signal::named_time_signal::NamedTimeSignal;
 use cb_simulation_util::signal::TimeRange;
 
@@ -26,25 +26,36 @@ pub struct ElementProps {
 
 #[function_component(PlotElement)]
 pub fn plotly_time_signal(props: &ElementProps) -> Html {
-    let time: Array<f64, Ix1> = props.range.collect();
-    let signal = props.signal.signal.clone();
-    let input: Array<f64, Ix1> = time.iter().map(|v| signal.time_to_signal(*v)).collect();
-    let mut element = props.element.element.clone();
-    let output: Array<f64, Ix1> = input.iter().map(|v| element.transfer_td(*v)).collect();
+    let computation = use_memo(
+        (props.range.clone(), props.signal.clone(), props.element.clone()),
+        |(range, signal, element)| {
+            Computation::new(SimulationJob::element(range, signal, element), || {
+                JobRun::samples(range, signal.signal.clone(), Some(element.element.clone()))
+            })
+        },
+    );
+    let result = match use_simulation(computation) {
+        Simulation::Done(result) => result,
+        Simulation::Running(fraction) => return html! { <SimulationProgress fraction={fraction} /> },
+        Simulation::Failed(error) => return html! { <div class="text-red-800 dark:text-red-200">{ error }</div> },
+    };
+    let SimulationResult::Samples { time, input, output: Some(output) } = (*result).clone() else {
+        return html! {};
+    };
 
-    let mut plot = Plot::new();
-    let input_trace = Scatter::from_array(time.clone(), input)
+    let mut plot = SampledPlot::new();
+    let input_trace = trace()
         .mode(Mode::LinesMarkers)
         .show_legend(true)
         .name("Input signal");
-    let output_trace = Scatter::from_array(time.clone(), output)
+    let output_trace = trace()
         .mode(Mode::LinesMarkers)
         .show_legend(true)
         .name("Output signal")
         .y_axis("y2");
 
-    plot.add_trace(input_trace);
-    plot.add_trace(output_trace);
+    plot.add_trace(input_trace, &time, &input);
+    plot.add_trace(output_trace, &time, &output);
 
     let layout = Layout::new()
         .title("<b>Transfer function of Element in Time Domain</b>".into())
@@ -61,7 +72,7 @@ pub fn plotly_time_signal(props: &ElementProps) -> Html {
     plot.set_layout(layout);
 
     html! {
-        <Plotly plot={plot}/>
+        <SampledPlotly plot={plot}/>
     }
 }
 
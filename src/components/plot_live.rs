@@ -0,0 +1,428 @@
+use accordion_rs::yew::{Accordion, Item, List};
+use accordion_rs::Size;
+use gloo::timers::callback::Interval;
+use input_rs::yew::Input;
+use js_sys::Date;
+use log::{error, info};
+use ndarray::Array1;
+use plotly::common::{AxisSide, Line, LineShape};
+use plotly::{layout::Axis, Layout};
+use yew::prelude::*;
+use yew_plotly::plotly::common::{Mode, Title};
+use web_sys::HtmlSelectElement;
+
+use crate::components::sampled_plot::{trace, SampledPlot, SampledPlotly};
+use crate::control::pid_setup::PidSetup;
+use crate::plant::named_element::NamedElement;
+use crate::simulation::{LiveLoop, LoopSetup};
+use cb_simulation_util::signal::TimeRange;
+
+/// Wall-clock time between two updates of the strip chart [ms]
+const TICK: u32 = 50;
+
+/// Wall-clock time the loop may compute per update [ms], the rest of the tick keeps the page responsive
+const BUDGET: f64 = TICK as f64 / 2.0;
+
+/// Strip chart of the recent history: setpoint and process variable left, control variable right
+fn strip_chart(live: &LiveLoop) -> SampledPlot {
+    let history = live.history();
+    let time: Array1<f64> = history.iter().map(|sample| sample.time).collect();
+    let setpoint: Array1<f64> = history.iter().map(|sample| sample.setpoint).collect();
+    let process_variable: Array1<f64> = history.iter().map(|sample| sample.y).collect();
+    let control_variable: Array1<f64> = history.iter().map(|sample| sample.u).collect();
+
+    let mut plot = SampledPlot::new();
+    let setpoint_trace = trace()
+        .mode(Mode::Lines)
+        .show_legend(true)
+        .name("r: Setpoint");
+    let process_variable_trace = trace()
+        .mode(Mode::Lines)
+        .show_legend(true)
+        .name("y: Process Variable");
+    let control_variable_trace = trace()
+        .mode(Mode::Lines)
+        .line(Line::new().shape(LineShape::Hv))
+        .show_legend(true)
+        .name("u: Control Variable")
+        .y_axis("y2");
+    plot.add_trace(setpoint_trace, &time, &setpoint);
+    plot.add_trace(control_variable_trace, &time, &control_variable);
+    plot.add_trace(process_variable_trace, &time, &process_variable);
+
+    // the window scrolls with the simulated time
+    let end = live.time();
+    let layout = Layout::new()
+        .title("<b>Control Loop Live</b>".into())
+        .x_axis(
+            Axis::new()
+                .title("time [ms]".into()) // plotly 0.8.3 does not support From<String>
+                .range(vec![end - live.window(), end]),
+        )
+        .y_axis(Axis::new().title("Setpoint Process Variable".into()))
+        .y_axis2(
+            Axis::new()
+                .title(Title::from("Control Variable"))
+                .overlaying("y")
+                .side(AxisSide::Right),
+        );
+    plot.set_layout(layout);
+    plot
+}
+
+#[derive(Properties, PartialEq)]
+pub struct AccordeonPlotLiveProps {
+    /// Its length is the width of the strip chart
+    #[prop_or_default]
+    pub range: TimeRange,
+    pub elements: Vec<NamedElement<f64>>,
+    pub controller: PidSetup,
+}
+
+/// Control loop advancing in real time or faster, setpoint and gains can be changed while it runs
+#[function_component(AccordeonPlotLive)]
+pub fn accordeon_plot_live(props: &AccordeonPlotLiveProps) -> Html {
+    let expand = use_state(|| false);
+
+    let element_names = props
+        .elements
+        .iter()
+        .enumerate()
+        .map(|(index, element)| {
+            html! {
+                <option value={index.to_string()}
+                // if the list get changed always the first element is selected
+                selected={index == 0}>
+                    { element.name.clone() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let initial_selected_element = if !props.elements.is_empty() {
+        "0".to_string()
+    } else {
+        "".to_string()
+    };
+    let selected_element = use_state(|| initial_selected_element);
+    let selected_element_clone = selected_element.clone();
+    let on_element_change = Callback::from(move |event: Event| {
+        let target = event.target_dyn_into::<HtmlSelectElement>();
+        if let Some(select) = target {
+            selected_element_clone.set(select.value());
+        }
+    });
+    let element = props
+        .elements
+        .get(selected_element.parse::<usize>().unwrap_or(0))
+        .cloned();
+
+    fn number_valid(s: String) -> bool {
+        s.parse::<f64>().is_ok()
+    }
+
+    fn not_negative_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value >= 0.0,
+            Err(_) => false,
+        }
+    }
+
+    fn positive_valid(s: String) -> bool {
+        match s.parse::<f64>() {
+            Ok(value) => value > 0.0,
+            Err(_) => false,
+        }
+    }
+
+    let setpoint_ref = use_node_ref();
+    let setpoint_handle = use_state(|| "1".to_string());
+    let setpoint_valid_handle = use_state(|| true);
+    let setpoint = (*setpoint_handle).parse::<f64>().unwrap_or_default();
+
+    let kp_ref = use_node_ref();
+    let kp_handle = use_state(|| props.controller.core.kp.to_string());
+    let kp_valid_handle = use_state(|| true);
+
+    let ki_ref = use_node_ref();
+    let ki_handle = use_state(|| props.controller.core.get_ki().to_string());
+    let ki_valid_handle = use_state(|| true);
+
+    let kd_ref = use_node_ref();
+    let kd_handle = use_state(|| props.controller.core.get_kd().to_string());
+    let kd_valid_handle = use_state(|| true);
+
+    let core = props
+        .controller
+        .core
+        .clone()
+        .kp((*kp_handle).parse::<f64>().unwrap_or_default())
+        .ki((*ki_handle).parse::<f64>().unwrap_or_default())
+        .kd((*kd_handle).parse::<f64>().unwrap_or_default());
+
+    let speed_ref = use_node_ref();
+    let speed_handle = use_state(|| "1".to_string());
+    let speed_valid_handle = use_state(|| true);
+    let speed = (*speed_handle).parse::<f64>().unwrap_or(1.0).max(0.0);
+
+    let live = use_mut_ref(|| None::<LiveLoop>);
+    let interval = use_mut_ref(|| None::<Interval>);
+    // read by the running interval, so a new speed-up factor applies without restart
+    let speed_factor = use_mut_ref(|| 1.0);
+    let redraw = use_force_update();
+
+    {
+        let speed_factor = speed_factor.clone();
+        use_effect_with(speed, move |&speed| *speed_factor.borrow_mut() = speed);
+    }
+
+    // the running loop keeps its states, only setpoint and gains change
+    {
+        let live = live.clone();
+        use_effect_with((setpoint, core.clone()), move |(setpoint, core)| {
+            if let Some(live) = live.borrow_mut().as_mut() {
+                info!("Live loop update - setpoint: {} gains: {:?}", setpoint, core);
+                live.set_setpoint(*setpoint);
+                live.set_gains(core);
+            }
+        });
+    }
+
+    // the interval is dropped, and with it stopped, when the plot is removed
+    {
+        let interval = interval.clone();
+        use_effect_with((), move |_| {
+            move || {
+                interval.borrow_mut().take();
+            }
+        });
+    }
+
+    let setup = element.clone().map(|element| {
+        LoopSetup::default()
+            .set_range(props.range.clone())
+            .set_element(element)
+            .set_controller(props.controller.clone())
+    });
+
+    let on_start = {
+        let live = live.clone();
+        let interval = interval.clone();
+        let redraw = redraw.clone();
+        let setup = setup.clone();
+        let core = core.clone();
+        Callback::from(move |_| {
+            let Some(setup) = &setup else { return };
+            if interval.borrow().is_some() {
+                return;
+            }
+            {
+                let mut live = live.borrow_mut();
+                if live.is_none() {
+                    match LiveLoop::new(setup) {
+                        Ok(started) => *live = Some(started),
+                        Err(unsupported) => {
+                            error!("Live loop not started: {}", unsupported);
+                            return;
+                        }
+                    }
+                }
+                if let Some(live) = live.as_mut() {
+                    live.set_setpoint(setpoint);
+                    live.set_gains(&core);
+                }
+            }
+            let live = live.clone();
+            let speed_factor = speed_factor.clone();
+            let redraw_tick = redraw.clone();
+            let mut last = Date::now();
+            *interval.borrow_mut() = Some(Interval::new(TICK, move || {
+                let now = Date::now();
+                if let Some(live) = live.borrow_mut().as_mut() {
+                    live.advance((now - last) * *speed_factor.borrow(), || Date::now() - now < BUDGET);
+                }
+                last = Date::now();
+                redraw_tick.force_update();
+            }));
+            redraw.force_update();
+        })
+    };
+
+    let on_stop = {
+        let interval = interval.clone();
+        let redraw = redraw.clone();
+        Callback::from(move |_| {
+            interval.borrow_mut().take();
+            redraw.force_update();
+        })
+    };
+
+    // the next start picks up the selected element and the configured controller
+    let on_reset = {
+        let live = live.clone();
+        let interval = interval.clone();
+        Callback::from(move |_| {
+            interval.borrow_mut().take();
+            live.borrow_mut().take();
+            redraw.force_update();
+        })
+    };
+
+    let running = interval.borrow().is_some();
+    let unsupported = setup.as_ref().map(LiveLoop::unsupported).unwrap_or_default();
+    let plot = live.borrow().as_ref().map(strip_chart);
+
+    html! {
+        <Accordion
+            expand={expand}
+            expanded={html! { "Plot Control Loop Live" } }
+            collapsed={html! { "Show Control Loop Live" } }
+            expanded_class=" bg-gradient-to-r from-blue-700 to-blue-500 text-white p-2 rounded"
+            collapsed_class="bg-gradient-to-r from-green-700 to-green-500 text-white p-2 rounded"
+            class="w-full p-4 rounded border border-gray-400 dark:border-gray-600"
+            size={Size::Custom("auto")}
+        >
+            <List>
+                <Item class="flex flex-row">
+                    <div class="flex flex-col w-64">
+                        <label for="live_element_label" class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { "Select Element by Name" } </label>
+                        <select name={"live_element"} onchange={on_element_change}
+                            class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+                        id="live_element_label">
+                            { element_names }
+                        </select>
+                    </div>
+                    <div class="flex flex-row items-center p-4">
+                        if running {
+                            <button onclick={on_stop}
+                                class="btn-social bg-blue-600 hover:bg-blue-700 text-white w-12 h-12 rounded-lg text-xl leading-12"
+                                aria-label="Stop the live loop"
+                            >
+                                <span class="fa-solid fa-pause"></span>
+                            </button>
+                        } else {
+                            <button onclick={on_start}
+                                class="btn-social bg-blue-600 hover:bg-blue-700 text-white w-12 h-12 rounded-lg text-xl leading-12"
+                                aria-label="Start the live loop"
+                            >
+                                <span class="fa-solid fa-play"></span>
+                            </button>
+                        }
+                        <button onclick={on_reset}
+                            class="btn-social bg-blue-600 hover:bg-blue-700 text-white w-12 h-12 rounded-lg text-xl leading-12 ml-2"
+                            aria-label="Reset the live loop"
+                        >
+                            <span class="fa-solid fa-rotate-left"></span>
+                        </button>
+                    </div>
+                    <form class="flex flex-row">
+                        <Input
+                            r#type="number"
+                            min="0"
+                            name="live_speed"
+                            r#ref={speed_ref}
+                            handle={speed_handle}
+                            valid_handle={speed_valid_handle}
+                            validate_function={positive_valid}
+
+                            label="Speed-up factor"
+                            required={true}
+                            error_message="Must be a positive number"
+                            class="form-field w-64"
+                            label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                            input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                            error_class="text-red-800"
+                        />
+                    </form>
+                </Item>
+                <Item class="flex flex-row">
+                    <form class="flex flex-row">
+                        <Input
+                            r#type="number"
+                            name="live_setpoint"
+                            r#ref={setpoint_ref}
+                            handle={setpoint_handle}
+                            valid_handle={setpoint_valid_handle}
+                            validate_function={number_valid}
+
+                            label="Setpoint"
+                            required={true}
+                            error_message="Must be a number"
+                            class="form-field w-64"
+                            label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                            input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                            error_class="text-red-800"
+                        />
+                        <Input
+                            r#type="number"
+                            min="0"
+                            name="live_kp"
+                            r#ref={kp_ref}
+                            handle={kp_handle}
+                            valid_handle={kp_valid_handle}
+                            validate_function={not_negative_valid}
+
+                            label="Kp"
+                            required={true}
+                            error_message="Must be a non-negative number"
+                            class="form-field w-64"
+                            label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                            input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                            error_class="text-red-800"
+                        />
+                        <Input
+                            r#type="number"
+                            min="0"
+                            name="live_ki"
+                            r#ref={ki_ref}
+                            handle={ki_handle}
+                            valid_handle={ki_valid_handle}
+                            validate_function={not_negative_valid}
+
+                            label="Ki"
+                            required={true}
+                            error_message="Must be a non-negative number"
+                            class="form-field w-64"
+                            label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                            input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                            error_class="text-red-800"
+                        />
+                        <Input
+                            r#type="number"
+                            min="0"
+                            name="live_kd"
+                            r#ref={kd_ref}
+                            handle={kd_handle}
+                            valid_handle={kd_valid_handle}
+                            validate_function={not_negative_valid}
+
+                            label="Kd"
+                            required={true}
+                            error_message="Must be a non-negative number"
+                            class="form-field w-64"
+                            label_class="block text-sm mb-2 text-gray-300 dark:text-gray-700"
+                            input_class="w-full p-2 border border-gray-400 dark:border-gray-600 rounded"
+                            error_class="text-red-800"
+                        />
+                    </form>
+                </Item>
+                {
+                    match (element, plot) {
+                        (None, _) => html! { <Item>{"No elements available"}</Item> },
+                        (Some(_), None) if !unsupported.is_empty() => html! {
+                            <Item class="text-red-800 dark:text-red-200">
+                                { format!("The live loop runs the plain PID only, remove: {}", unsupported.join(", ")) }
+                            </Item>
+                        },
+                        (Some(_), None) => html! { <Item>{"Start the loop to see it live"}</Item> },
+                        (Some(_), Some(plot)) => html! {
+                            <Item>
+                                <SampledPlotly plot={plot} />
+                            </Item>
+                        },
+                    }
+                }
+            </List>
+        </Accordion>
+    }
+}
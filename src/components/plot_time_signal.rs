@@ -1,14 +1,14 @@
 use accordion_rs::yew::{Accordion, Item, List};
 use accordion_rs::Size;
-use ndarray::{Array, Ix1};
-use plotly::{layout::Axis, Layout, Scatter};
+use plotly::{layout::Axis, Layout};
 use yew::prelude::*;
 use yew_plotly::plotly::common::Mode;
-use yew_plotly::plotly::Plot;
-use yew_plotly::Plotly;
 
 use web_sys::HtmlSelectElement;
 
+use crate::components::sampled_plot::{trace, SampledPlot, SampledPlotly};
+use crate::components::simulation::{use_simulation, Computation, Simulation, SimulationProgress};
+use crate::simulation::{JobRun, SimulationJob, SimulationResult};
 use crate::time_signal::named_time_signal::NamedTimeSignal;
 use cb_simulation_util::signal::TimeRange;
 
@@ -22,19 +22,26 @@ pub struct TimeSignalProps {
 
 #[function_component(PlotTimeSignal)]
 pub fn plotly_time_signal(props: &TimeSignalProps) -> Html {
-    let time: Array<f64, Ix1> = props.range.collect();
-    let signal_struct = props.signal.signal.clone();
-    let signal: Array<f64, Ix1> = time
-        .iter()
-        .map(|v| signal_struct.time_to_signal(*v))
-        .collect();
+    let computation = use_memo((props.range.clone(), props.signal.clone()), |(range, signal)| {
+        Computation::new(SimulationJob::signal(range, signal), || {
+            JobRun::samples(range, signal.signal.clone(), None)
+        })
+    });
+    let result = match use_simulation(computation) {
+        Simulation::Done(result) => result,
+        Simulation::Running(fraction) => return html! { <SimulationProgress fraction={fraction} /> },
+        Simulation::Failed(error) => return html! { <div class="text-red-800 dark:text-red-200">{ error }</div> },
+    };
+    let SimulationResult::Samples { time, input: signal, .. } = (*result).clone() else {
+        return html! {};
+    };
 
-    let mut plot = Plot::new();
-    let trace = Scatter::from_array(time.clone(), signal)
+    let mut plot = SampledPlot::new();
+    let signal_trace = trace()
         .mode(Mode::LinesMarkers)
         .show_legend(false)
         .name("Time signal");
-    plot.add_trace(trace);
+    plot.add_trace(signal_trace, &time, &signal);
 
     let layout = Layout::new()
         .title("<b>Signal in Time Domain</b>".into())
@@ -45,7 +52,7 @@ pub fn plotly_time_signal(props: &TimeSignalProps) -> Html {
     plot.set_layout(layout);
 
     html! {
-        <Plotly plot={plot}/>
+        <SampledPlotly plot={plot}/>
     }
 }
 
@@ -0,0 +1,149 @@
+//! Plots of many samples.
+//!
+//! The samples are reduced for display, the simulation results keep all of them.
+//! The reduction is done once for the whole time range: zooming in shows the reduced
+//! samples and no further details, those are in the CSV download of the results.
+//! They are handed to Plotly as typed arrays instead of being serialized with the traces,
+//! and traces of many samples are drawn with WebGL.
+use js_sys::{Float64Array, Reflect, JSON};
+use log::error;
+use ndarray::{s, Array1};
+use plotly::{Layout, Scatter};
+use wasm_bindgen::prelude::*;
+use web_sys::Element;
+use yew::prelude::*;
+use yew_plotly::plotly::common::Mode;
+use yew_plotly::plotly::Plot;
+
+/// Traces with more samples are reduced to about this many points
+pub const DISPLAY_POINTS: usize = 4000;
+
+/// Traces with more samples are drawn with WebGL and without markers
+pub const WEBGL_THRESHOLD: usize = 2000;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Plotly.js loaded by `index.html`, draws the plot or updates it in place
+    #[wasm_bindgen(js_namespace = Plotly, js_name = react)]
+    fn plotly_react(root: &Element, data: &JsValue, layout: &JsValue, config: &JsValue) -> js_sys::Promise;
+}
+
+/// Reduces the samples to the first, the minimum, the maximum and the last one of each bucket.
+///
+/// Unlike taking every n-th sample, peaks such as an overshoot stay visible.
+pub fn min_max(time: &Array1<f64>, values: &Array1<f64>, buckets: usize) -> (Vec<f64>, Vec<f64>) {
+    let len = time.len().min(values.len());
+    if buckets == 0 || len <= 4 * buckets {
+        return (time.slice(s![..len]).to_vec(), values.slice(s![..len]).to_vec());
+    }
+    let size = len.div_ceil(buckets);
+    let mut reduced_time = Vec::with_capacity(4 * buckets);
+    let mut reduced_values = Vec::with_capacity(4 * buckets);
+    for start in (0..len).step_by(size) {
+        let end = (start + size).min(len);
+        let (mut min, mut max) = (start, start);
+        for index in start..end {
+            if values[index] < values[min] {
+                min = index;
+            }
+            if values[index] > values[max] {
+                max = index;
+            }
+        }
+        let mut indices = [start, min, max, end - 1];
+        indices.sort_unstable();
+        let mut previous = None;
+        for index in indices {
+            if previous != Some(index) {
+                reduced_time.push(time[index]);
+                reduced_values.push(values[index]);
+                previous = Some(index);
+            }
+        }
+    }
+    (reduced_time, reduced_values)
+}
+
+/// Trace without samples, styled by the caller and filled by [`SampledPlot::add_trace`]
+pub fn trace() -> Box<Scatter<f64, f64>> {
+    Scatter::new(Vec::new(), Vec::new())
+}
+
+/// Plot whose samples are kept apart from the traces
+#[derive(Clone, Default)]
+pub struct SampledPlot {
+    /// Layout and trace styles, the traces without samples
+    plot: Plot,
+    /// Reduced time and values of each trace
+    samples: Vec<(Vec<f64>, Vec<f64>)>,
+}
+
+impl SampledPlot {
+    pub fn new() -> Self {
+        SampledPlot::default()
+    }
+
+    /// Adds the trace of the samples, reduced for display and drawn with WebGL if many
+    pub fn add_trace(&mut self, trace: Box<Scatter<f64, f64>>, time: &Array1<f64>, values: &Array1<f64>) {
+        let trace = if time.len() > WEBGL_THRESHOLD {
+            trace.mode(Mode::Lines).web_gl_mode(true)
+        } else {
+            trace
+        };
+        self.plot.add_trace(trace);
+        self.samples.push(min_max(time, values, DISPLAY_POINTS / 4));
+    }
+
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.plot.set_layout(layout);
+    }
+
+    /// Data, layout and configuration for Plotly.js, the samples as `Float64Array`
+    fn to_js(&self) -> Result<(JsValue, JsValue, JsValue), JsValue> {
+        let figure = JSON::parse(&self.plot.to_json())?;
+        let data = Reflect::get(&figure, &"data".into())?;
+        for (index, (time, values)) in self.samples.iter().enumerate() {
+            let trace = Reflect::get_u32(&data, index as u32)?;
+            Reflect::set(&trace, &"x".into(), &Float64Array::from(time.as_slice()))?;
+            Reflect::set(&trace, &"y".into(), &Float64Array::from(values.as_slice()))?;
+        }
+        Ok((
+            data,
+            Reflect::get(&figure, &"layout".into())?,
+            Reflect::get(&figure, &"config".into())?,
+        ))
+    }
+}
+
+impl PartialEq for SampledPlot {
+    fn eq(&self, other: &Self) -> bool {
+        self.samples == other.samples && self.plot.to_json() == other.plot.to_json()
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SampledPlotlyProps {
+    pub plot: SampledPlot,
+}
+
+#[function_component(SampledPlotly)]
+pub fn sampled_plotly(props: &SampledPlotlyProps) -> Html {
+    let node = use_node_ref();
+    {
+        let node = node.clone();
+        use_effect_with(props.plot.clone(), move |plot| {
+            if let Some(root) = node.cast::<Element>() {
+                match plot.to_js() {
+                    Ok((data, layout, config)) => {
+                        let _ = plotly_react(&root, &data, &layout, &config);
+                    }
+                    Err(error) => error!("Plot not drawn: {:?}", error),
+                }
+            }
+        });
+    }
+
+    html! {
+        <div ref={node}></div>
+    }
+}
@@ -0,0 +1,195 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo::worker::{Spawnable, WorkerBridge};
+use log::info;
+use yew::prelude::*;
+
+use crate::simulation::worker::{SimulationWorker, WorkerInput, WorkerOutput, WORKER_PATH};
+use crate::simulation::{JobRun, ProjectError, SimulationJob, SimulationResult};
+
+/// Results kept for jobs computed before, e.g. when switching back to a signal
+const CACHE_SIZE: usize = 16;
+
+thread_local! {
+    /// Results by job, the most recently used last
+    static RESULTS: RefCell<Vec<(SimulationJob, Rc<SimulationResult>)>> = RefCell::new(Vec::new());
+}
+
+fn cached(job: &SimulationJob) -> Option<Rc<SimulationResult>> {
+    RESULTS.with(|results| {
+        let mut results = results.borrow_mut();
+        let index = results.iter().position(|(cached, _)| cached == job)?;
+        let entry = results.remove(index);
+        let result = entry.1.clone();
+        results.push(entry);
+        Some(result)
+    })
+}
+
+fn cache(job: SimulationJob, result: Rc<SimulationResult>) {
+    RESULTS.with(|results| {
+        let mut results = results.borrow_mut();
+        results.retain(|(cached, _)| *cached != job);
+        if results.len() >= CACHE_SIZE {
+            results.remove(0);
+        }
+        results.push((job, result));
+    })
+}
+
+/// What a plot computes, to be memoized on the inputs of the plot
+#[derive(Debug, Clone, PartialEq)]
+pub enum Computation {
+    /// Computed by the [`SimulationWorker`]
+    Job(SimulationJob),
+    /// Inputs the job can not describe, computed on the UI thread
+    Local(Rc<SimulationResult>),
+}
+
+impl Computation {
+    pub fn new(job: Result<SimulationJob, ProjectError>, local: impl FnOnce() -> JobRun) -> Self {
+        match job {
+            Ok(job) => Computation::Job(job),
+            Err(error) => {
+                info!("Simulation computed on the UI thread: {}", error);
+                Computation::Local(Rc::new(local().complete()))
+            }
+        }
+    }
+}
+
+/// The one simulation worker of the app, shared by the plots
+#[derive(Clone)]
+pub struct SharedWorker(Rc<WorkerBridge<SimulationWorker>>);
+
+impl PartialEq for SharedWorker {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SimulationWorkerProviderProps {
+    #[prop_or_default]
+    pub children: Html,
+}
+
+/// Spawns the simulation worker once for all plots inside, each plot connects with a bridge of its own
+#[function_component(SimulationWorkerProvider)]
+pub fn simulation_worker_provider(props: &SimulationWorkerProviderProps) -> Html {
+    let worker = use_memo((), |_| {
+        info!("Spawning the simulation worker");
+        // the outputs go to the bridges of the plots
+        SharedWorker(Rc::new(SimulationWorker::spawner().callback(|_| ()).spawn_with_loader(WORKER_PATH)))
+    });
+    html! {
+        <ContextProvider<SharedWorker> context={(*worker).clone()}>
+            { props.children.clone() }
+        </ContextProvider<SharedWorker>>
+    }
+}
+
+/// State of the computation of a plot
+#[derive(Debug, Clone, PartialEq)]
+pub enum Simulation {
+    /// Fraction of the computed time steps
+    Running(f64),
+    Done(Rc<SimulationResult>),
+    /// Why the worker could not start the job
+    Failed(String),
+}
+
+/// Computes the job in the shared worker of the [`SimulationWorkerProvider`],
+/// a changed job cancels the running one.
+///
+/// Results are cached by job, so a plot whose inputs did not change is not simulated again.
+#[hook]
+pub fn use_simulation(computation: Rc<Computation>) -> Simulation {
+    let job = match &*computation {
+        Computation::Job(job) => Some(job.clone()),
+        Computation::Local(_) => None,
+    };
+    let state = use_state(|| Simulation::Running(0.0));
+    let generation = use_mut_ref(|| 0_u64);
+    // the job whose results are in the state
+    let sent = use_mut_ref(|| None::<SimulationJob>);
+    let worker = use_context::<SharedWorker>().expect("plots are placed inside the SimulationWorkerProvider");
+
+    let bridge = {
+        let state = state.clone();
+        let generation = generation.clone();
+        let sent = sent.clone();
+        use_memo((), move |_| {
+            worker.0.fork(Some(move |output| match output {
+                // outputs of replaced runs are dropped
+                WorkerOutput::Progress { generation: g, fraction } if g == *generation.borrow() => {
+                    state.set(Simulation::Running(fraction))
+                }
+                WorkerOutput::Done { generation: g, result } if g == *generation.borrow() => match result {
+                    Ok(result) => {
+                        let result = Rc::new(result);
+                        if let Some(job) = sent.borrow().clone() {
+                            cache(job, result.clone());
+                        }
+                        state.set(Simulation::Done(result));
+                    }
+                    Err(error) => {
+                        info!("Simulation worker failed: {}", error);
+                        state.set(Simulation::Failed(error));
+                    }
+                },
+                _ => (),
+            }))
+        })
+    };
+
+    {
+        let state = state.clone();
+        let sent = sent.clone();
+        use_effect_with(job.clone(), move |job| {
+            *generation.borrow_mut() += 1;
+            if let Some(job) = job {
+                match cached(job) {
+                    Some(result) => state.set(Simulation::Done(result)),
+                    None => {
+                        state.set(Simulation::Running(0.0));
+                        bridge.send(WorkerInput::Run { generation: *generation.borrow(), job: job.clone() });
+                    }
+                }
+            }
+            *sent.borrow_mut() = job.clone();
+            move || bridge.send(WorkerInput::Cancel)
+        });
+    }
+
+    match (&*computation, job) {
+        (Computation::Local(result), _) => Simulation::Done(result.clone()),
+        // until the effect has sent a changed job, the state belongs to the previous one
+        (_, Some(job)) if sent.borrow().as_ref() != Some(&job) => {
+            cached(&job).map_or(Simulation::Running(0.0), Simulation::Done)
+        }
+        _ => (*state).clone(),
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SimulationProgressProps {
+    /// Fraction of the computed time steps
+    pub fraction: f64,
+}
+
+#[function_component(SimulationProgress)]
+pub fn simulation_progress(props: &SimulationProgressProps) -> Html {
+    let percent = (props.fraction.clamp(0.0, 1.0) * 100.0).round();
+    html! {
+        <div class="flex flex-col m-2">
+            <label class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700">
+                { format!("Simulating ... {} %", percent) }
+            </label>
+            <div class="w-full h-2.5 bg-gray-200 rounded-full dark:bg-gray-700">
+                <div class="h-2.5 bg-blue-600 rounded-full" style={format!("width: {}%", percent)}></div>
+            </div>
+        </div>
+    }
+}
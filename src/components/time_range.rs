@@ -13,7 +13,7 @@ pub struct TimeRangeDialogProps {
 
 #[function_component(TimeRangeDialog)]
 pub fn time_range_dialog(props: &TimeRangeDialogProps) -> Html {
-    let updated = props.handle.clone();
+    let current = (*props.handle).clone();
 
     fn validate_sample_interval(sample_interval: String) -> bool {
         let st: f64 = sample_interval.parse::<f64>().unwrap_or(-1.0);
@@ -31,23 +31,37 @@ pub fn time_range_dialog(props: &TimeRangeDialogProps) -> Html {
     }
 
     let sample_interval_ref = use_node_ref();
-    let sample_interval_handle = use_state(|| updated.sampling_interval.to_string());
+    let sample_interval_handle = use_state(|| current.sampling_interval.to_string());
     let sample_interval_valid_handle = use_state(|| true);
 
     let start_ref = use_node_ref();
-    let start_handle = use_state(|| updated.start.to_string());
+    let start_handle = use_state(|| current.start.to_string());
     let start_valid_handle = use_state(|| true);
 
     let end_ref = use_node_ref();
-    let end_handle = use_state(|| updated.end.to_string());
+    let end_handle = use_state(|| current.end.to_string());
     let end_valid_handle = use_state(|| true);
 
-    let updated =
-        updated.set_sampling_interval((*sample_interval_handle).parse::<f64>().unwrap_or_default());
-    let updated = updated.set_start((*start_handle).parse::<f64>().unwrap_or_default());
-    let updated = updated.set_end((*end_handle).parse::<f64>().unwrap_or_default());
-
-    props.handle.set(updated.clone());
+    let sampling_interval = (*sample_interval_handle).parse::<f64>().unwrap_or_default();
+    let start = (*start_handle).parse::<f64>().unwrap_or_default();
+    let end = (*end_handle).parse::<f64>().unwrap_or_default();
+
+    // the range is set after the render and only if an input changed it, setting it on
+    // every render re-rendered and re-simulated all plots
+    {
+        let handle = props.handle.clone();
+        use_effect_with((sampling_interval, start, end), move |&(sampling_interval, start, end)| {
+            let current = (*handle).clone();
+            if (current.sampling_interval, current.start, current.end) != (sampling_interval, start, end) {
+                handle.set(
+                    current
+                        .set_sampling_interval(sampling_interval)
+                        .set_start(start)
+                        .set_end(end),
+                );
+            }
+        });
+    }
 
     html! {
        <div>
@@ -2,10 +2,11 @@ use input_rs::yew::Input;
 use log::info;
 use yew::prelude::*;
 
+use crate::components::hooks::use_emit_changed;
 use crate::components::time_signal::BoxedTimeSignalDialogProps;
 use crate::time_signal::registry::{register_time_signal, YewTimeSignal};
 use cb_simulation_util::signal::impulse_fn::ImpulseFunction;
-use cb_simulation_util::signal::{DynTimeSignal, TimeSignal};
+use cb_simulation_util::signal::{BoxedTimeSignal, DynTimeSignal, TimeSignal};
 
 pub struct YewImpulse {
     signal: ImpulseFunction<f64>,
@@ -51,7 +52,7 @@ pub fn register() {
 pub fn impulse_function_dialog(props: &BoxedTimeSignalDialogProps) -> Html {
     // Runtime reflection (downcasting to concrete type)
     // Variable assignment must be done outside the html! macro
-    let updated = if let Some(step) = props
+    let current = if let Some(step) = props
         .time_signal
         .clone()
         .as_any()
@@ -67,19 +68,19 @@ pub fn impulse_function_dialog(props: &BoxedTimeSignalDialogProps) -> Html {
     }
 
     let out_value_ref = use_node_ref();
-    let out_value_handle = use_state(|| updated.out_value.to_string());
+    let out_value_handle = use_state(|| current.out_value.to_string());
     let out_value_valid_handle = use_state(|| true);
 
     let in_value_ref = use_node_ref();
-    let in_value_handle = use_state(|| updated.in_value.to_string());
+    let in_value_handle = use_state(|| current.in_value.to_string());
     let in_value_valid_handle = use_state(|| true);
 
     let start_ref = use_node_ref();
-    let start_handle = use_state(|| updated.start_time.to_string());
+    let start_handle = use_state(|| current.start_time.to_string());
     let start_valid_handle = use_state(|| true);
 
     let duration_ref = use_node_ref();
-    let duration_handle = use_state(|| updated.duration.to_string());
+    let duration_handle = use_state(|| current.duration.to_string());
     let duration_valid_handle = use_state(|| true);
 
     let updated = ImpulseFunction::<f64> {
@@ -89,7 +90,11 @@ pub fn impulse_function_dialog(props: &BoxedTimeSignalDialogProps) -> Html {
         duration: (*duration_handle).parse::<f64>().unwrap_or_default(),
     };
 
-    props.on_update.emit(Box::new(updated));
+    use_emit_changed(
+        updated,
+        current,
+        props.on_update.reform(|impulse: ImpulseFunction<f64>| Box::new(impulse) as BoxedTimeSignal<f64>),
+    );
 
     html! {
         <div>
@@ -1,5 +1,6 @@
 pub mod impulse_fn;
 pub mod named_time_signal_dialog;
+pub mod optional_signal_select;
 pub mod step_fn;
 pub mod time_signal;
 pub mod time_signal_select;
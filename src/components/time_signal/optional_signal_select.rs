@@ -0,0 +1,60 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::time_signal::named_time_signal::NamedTimeSignal;
+
+#[derive(Properties, PartialEq)]
+pub struct OptionalSignalSelectProps {
+    /// Id of the select, referenced by its label
+    pub id: AttrValue,
+    pub label: AttrValue,
+    pub signals: Vec<NamedTimeSignal<f64>>,
+    /// Index of the selected signal, `None` for no signal
+    pub selected: Option<usize>,
+    /// Text of the entry without signal, without it a signal must be selected
+    #[prop_or(Some(AttrValue::Static("None")))]
+    pub none_label: Option<AttrValue>,
+    pub on_change: Callback<Option<usize>>,
+}
+
+/// Select of a signal by name, optionally with an entry for no signal
+#[function_component(OptionalSignalSelect)]
+pub fn optional_signal_select(props: &OptionalSignalSelectProps) -> Html {
+    let signal_names = props
+        .signals
+        .iter()
+        .enumerate()
+        .map(|(index, signal)| {
+            html! {
+                <option value={index.to_string()} selected={props.selected == Some(index)}>
+                    { signal.name.clone() }
+                </option>
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    let on_change = {
+        let emitter = props.on_change.clone();
+        Callback::from(move |event: Event| {
+            let target = event.target_dyn_into::<HtmlSelectElement>();
+            if let Some(select) = target {
+                // "" stands for no signal
+                emitter.emit(select.value().parse::<usize>().ok());
+            }
+        })
+    };
+
+    html! {
+        <div class="flex flex-col w-64 m-2">
+            <label for={props.id.clone()} class="block mb-2 text-sm font-medium text-gray-300 dark:text-gray-700"> { props.label.clone() } </label>
+            <select name={props.id.clone()} onchange={on_change}
+                class="bg-gray-50 border border-gray-300 text-gray-900 text-sm rounded-lg focus:ring-blue-500 focus:border-blue-500 block w-full p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500"
+            id={props.id.clone()}>
+                if let Some(none_label) = &props.none_label {
+                    <option value="" selected={props.selected.is_none()}>{ none_label.clone() }</option>
+                }
+                { signal_names }
+            </select>
+        </div>
+    }
+}
@@ -2,9 +2,10 @@ use input_rs::yew::Input;
 use log::info;
 use yew::prelude::*;
 
+use crate::components::hooks::use_emit_changed;
 use crate::components::time_signal::BoxedTimeSignalDialogProps;
 use crate::time_signal::registry::{register_time_signal, YewTimeSignal};
-use cb_simulation_util::signal::{step_fn::StepFunction, DynTimeSignal, TimeSignal};
+use cb_simulation_util::signal::{step_fn::StepFunction, BoxedTimeSignal, DynTimeSignal, TimeSignal};
 
 pub struct YewStep {
     signal: StepFunction<f64>,
@@ -50,7 +51,7 @@ pub fn register() {
 pub fn step_function_dialog(props: &BoxedTimeSignalDialogProps) -> Html {
     // Runtime reflection (downcasting to concrete type)
     // Variable assignment must be done outside the html! macro
-    let updated = if let Some(step) = props
+    let current = if let Some(step) = props
         .time_signal
         .clone()
         .as_any()
@@ -66,15 +67,15 @@ pub fn step_function_dialog(props: &BoxedTimeSignalDialogProps) -> Html {
     }
 
     let pre_value_ref = use_node_ref();
-    let pre_value_handle = use_state(|| updated.pre_value.to_string());
+    let pre_value_handle = use_state(|| current.pre_value.to_string());
     let pre_value_valid_handle = use_state(|| true);
 
     let post_value_ref = use_node_ref();
-    let post_value_handle = use_state(|| updated.post_value.to_string());
+    let post_value_handle = use_state(|| current.post_value.to_string());
     let post_value_valid_handle = use_state(|| true);
 
     let step_time_ref = use_node_ref();
-    let step_time_handle = use_state(|| updated.step_time.to_string());
+    let step_time_handle = use_state(|| current.step_time.to_string());
     let step_time_valid_handle = use_state(|| true);
 
     let updated = StepFunction::<f64> {
@@ -83,7 +84,11 @@ pub fn step_function_dialog(props: &BoxedTimeSignalDialogProps) -> Html {
         step_time: (*step_time_handle).parse::<f64>().unwrap_or_default(),
     };
 
-    props.on_update.emit(Box::new(updated));
+    use_emit_changed(
+        updated,
+        current,
+        props.on_update.reform(|step: StepFunction<f64>| Box::new(step) as BoxedTimeSignal<f64>),
+    );
 
     html! {
         <div>
@@ -40,7 +40,8 @@ pub fn accordeon_time_signals(props: &AccordeonTimeSignalsProps) -> Html {
 
         Callback::from(move |_| {
             let mut signals = (*signals_handle).clone();
-            let new = (*new_handle).clone();
+            // the name is taken from its input when the signal is added, not on every render
+            let new = (*new_handle).clone().set_name((*name_handle).clone());
             info!("Add new signal: {}", new);
             signals.push(new.clone());
             let new_name = format!("{}-{}", new.signal.short_type_name(), signals.len() + 1);
@@ -125,10 +126,6 @@ pub fn accordeon_time_signals(props: &AccordeonTimeSignalsProps) -> Html {
         })
     };
 
-    let name = (*name_handle).parse::<String>().unwrap_or_default();
-    let new_value = (*new_handle).clone().set_name(name.clone());
-    new_handle.set(new_value);
-
     html! {
         <Accordion
             expand={expand}
@@ -0,0 +1,56 @@
+use std::collections::VecDeque;
+
+/// Transport delay, rounded to an integer number of samples
+#[derive(Debug, Clone)]
+pub struct DeadTime {
+    buffer: VecDeque<f64>,
+}
+
+impl DeadTime {
+    pub fn new(dead_time: f64, sampling_interval: f64) -> Self {
+        let samples = if sampling_interval > 0.0 && dead_time > 0.0 {
+            (dead_time / sampling_interval).round() as usize
+        } else {
+            0
+        };
+        DeadTime {
+            buffer: VecDeque::from(vec![0.0; samples]),
+        }
+    }
+
+    pub fn update(&mut self, input: f64) -> f64 {
+        self.buffer.push_back(input);
+        self.buffer.pop_front().unwrap_or(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Outputs for the inputs 1, 2, 3, ...
+    fn ramp(mut dead_time: DeadTime, steps: usize) -> Vec<f64> {
+        (1..=steps).map(|i| dead_time.update(i as f64)).collect()
+    }
+
+    #[test]
+    fn delays_by_whole_samples() {
+        assert_eq!(ramp(DeadTime::new(30.0, 10.0), 6), vec![0.0, 0.0, 0.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn non_integer_delays_round_to_the_nearest_sample() {
+        assert_eq!(ramp(DeadTime::new(24.0, 10.0), 4), vec![0.0, 0.0, 1.0, 2.0]);
+        assert_eq!(ramp(DeadTime::new(26.0, 10.0), 4), vec![0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(ramp(DeadTime::new(25.0, 10.0), 4), vec![0.0, 0.0, 0.0, 1.0]);
+        // less than half a sample is no delay at all
+        assert_eq!(ramp(DeadTime::new(4.0, 10.0), 3), vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn no_delay_without_dead_time_or_sampling_interval() {
+        assert_eq!(ramp(DeadTime::new(0.0, 10.0), 3), vec![1.0, 2.0, 3.0]);
+        assert_eq!(ramp(DeadTime::new(-5.0, 10.0), 3), vec![1.0, 2.0, 3.0]);
+        assert_eq!(ramp(DeadTime::new(30.0, 0.0), 3), vec![1.0, 2.0, 3.0]);
+    }
+}
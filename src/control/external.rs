@@ -0,0 +1,160 @@
+//! Software in the loop: the controller step is delegated to an external process.
+//!
+//! The process gets one line per controller sample on stdin and answers with one line
+//! on stdout, numbers are whitespace separated:
+//!
+//! ```text
+//! -> <r> <y> <t>      setpoint, measured process variable, time [ms]
+//! <- <u>              control variable
+//! ```
+//!
+//! The process is started per simulation run and ends when its stdin is closed.
+//! A process not answering within [`RESPONSE_TIMEOUT`] fails the controller.
+//!
+//! Only native builds with the `cli` feature can start processes. A project file naming
+//! a program runs it with the rights of the user, `cb-sim` therefore requires `--allow-external`.
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::control::LoopController;
+
+/// Longest wait for the answer to one sample
+pub const RESPONSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Time the process gets to end after its stdin is closed, before it is killed
+const EXIT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Command line of the external controller process
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExternalControllerSetup {
+    pub program: String,
+    pub arguments: Vec<String>,
+}
+
+impl ExternalControllerSetup {
+    pub fn set_program(self, program: String) -> Self {
+        ExternalControllerSetup { program, ..self }
+    }
+
+    pub fn set_arguments(self, arguments: Vec<String>) -> Self {
+        ExternalControllerSetup { arguments, ..self }
+    }
+
+    pub fn spawn(&self) -> io::Result<ExternalController> {
+        let mut child = Command::new(&self.program)
+            .args(&self.arguments)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or_else(|| io::Error::other("no stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| io::Error::other("no stdout"))?;
+        // the lines are read on a thread of their own, so that waiting for them can time out
+        let (sender, answers) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let failed = line.is_err();
+                if sender.send(line).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        info!("External controller started: {}", self);
+        Ok(ExternalController {
+            child,
+            stdin: Some(stdin),
+            answers,
+            time: 0.0,
+            output: 0.0,
+            failed: false,
+        })
+    }
+}
+
+impl fmt::Display for ExternalControllerSetup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        for argument in &self.arguments {
+            write!(f, " {}", argument)?;
+        }
+        Ok(())
+    }
+}
+
+/// Running external controller process as created by [`ExternalControllerSetup::spawn`]
+#[derive(Debug)]
+pub struct ExternalController {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    /// Lines of stdout, disconnected at the end of the output
+    answers: Receiver<io::Result<String>>,
+    /// Time of the next sample [ms]
+    time: f64,
+    /// Last valid output, held after a protocol error
+    output: f64,
+    failed: bool,
+}
+
+impl ExternalController {
+    fn exchange(&mut self, setpoint: f64, process_variable: f64) -> io::Result<f64> {
+        let stdin = self.stdin.as_mut().ok_or_else(|| io::Error::other("stdin closed"))?;
+        writeln!(stdin, "{} {} {}", setpoint, process_variable, self.time)?;
+        stdin.flush()?;
+        let line = match self.answers.recv_timeout(RESPONSE_TIMEOUT) {
+            Ok(line) => line?,
+            Err(RecvTimeoutError::Timeout) => {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "no answer in time"));
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "process ended"));
+            }
+        };
+        line.trim()
+            .parse::<f64>()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("'{}' is not a number", line.trim())))
+    }
+}
+
+impl LoopController for ExternalController {
+    /// Returns the answer of the process; after the first error or timeout the last output is held
+    fn update(&mut self, setpoint: f64, process_variable: f64) -> f64 {
+        if self.failed {
+            return self.output;
+        }
+        match self.exchange(setpoint, process_variable) {
+            Ok(output) => self.output = output,
+            Err(error) => {
+                info!("External controller failed at t = {}, holding the output: {}", self.time, error);
+                self.failed = true;
+            }
+        }
+        self.output
+    }
+
+    fn time(&mut self, time: f64) {
+        self.time = time;
+    }
+}
+
+impl Drop for ExternalController {
+    fn drop(&mut self) {
+        // closing stdin asks the process to end, it is killed if it does not in time
+        self.stdin.take();
+        let deadline = Instant::now() + EXIT_TIMEOUT;
+        while matches!(self.child.try_wait(), Ok(None)) {
+            if Instant::now() >= deadline {
+                let _ = self.child.kill();
+                break;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+/// Lead-lag transfer function `K (1 + T_lead s) / (1 + T_lag s)`.
+///
+/// With both time constants set to zero it is a static gain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeadLag {
+    pub gain: f64,
+    pub lead_time: f64,
+    pub lag_time: f64,
+}
+
+impl LeadLag {
+    pub fn set_gain(self, gain: f64) -> Self {
+        LeadLag { gain, ..self }
+    }
+
+    pub fn set_lead_time(self, lead_time: f64) -> Self {
+        LeadLag { lead_time, ..self }
+    }
+
+    pub fn set_lag_time(self, lag_time: f64) -> Self {
+        LeadLag { lag_time, ..self }
+    }
+
+    /// Discretizes the transfer function via Tustin (bilinear) transformation.
+    ///
+    /// Without lag Tustin would place the pole at z = -1 and the lead would ring forever,
+    /// therefore a pure lead is discretized by the backward difference: one kick per step.
+    pub fn filter(&self, sampling_interval: f64) -> LeadLagFilter {
+        if self.lag_time <= 0.0 {
+            let lead = self.lead_time / sampling_interval;
+            return LeadLagFilter {
+                gain: self.gain,
+                b0: self.gain * (1.0 + lead),
+                b1: -self.gain * lead,
+                a1: 0.0,
+                previous: None,
+            };
+        }
+        let alpha = 2.0 / sampling_interval;
+        let a0 = 1.0 + self.lag_time * alpha;
+        LeadLagFilter {
+            gain: self.gain,
+            b0: self.gain * (1.0 + self.lead_time * alpha) / a0,
+            b1: self.gain * (1.0 - self.lead_time * alpha) / a0,
+            a1: (1.0 - self.lag_time * alpha) / a0,
+            previous: None,
+        }
+    }
+}
+
+impl Default for LeadLag {
+    fn default() -> Self {
+        LeadLag {
+            gain: 1.0,
+            lead_time: 0.0,
+            lag_time: 0.0,
+        }
+    }
+}
+
+/// Stateful, discrete lead-lag filter as created by [`LeadLag::filter`]
+#[derive(Debug, Clone)]
+pub struct LeadLagFilter {
+    gain: f64,
+    b0: f64,
+    b1: f64,
+    a1: f64,
+    /// previous input and output
+    previous: Option<(f64, f64)>,
+}
+
+impl LeadLagFilter {
+    pub fn update(&mut self, input: f64) -> f64 {
+        // start in steady state to avoid a kick by a non-zero initial input
+        let (x1, y1) = self.previous.unwrap_or((input, self.gain * input));
+        let output = self.b0 * input + self.b1 * x1 - self.a1 * y1;
+        self.previous = Some((input, output));
+        output
+    }
+}
+
+/// Optional feedforward paths added to the controller output before the plant
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedforwardSetup {
+    /// Filter from the setpoint
+    pub setpoint: Option<LeadLag>,
+    /// Filter from the measured disturbance
+    pub disturbance: Option<LeadLag>,
+}
+
+impl FeedforwardSetup {
+    pub fn set_setpoint(self, setpoint: Option<LeadLag>) -> Self {
+        FeedforwardSetup { setpoint, ..self }
+    }
+
+    pub fn set_disturbance(self, disturbance: Option<LeadLag>) -> Self {
+        FeedforwardSetup { disturbance, ..self }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.setpoint.is_some() || self.disturbance.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Output for a unit step after one sample at zero
+    fn step_response(lead_lag: LeadLag, samples: usize) -> Vec<f64> {
+        let mut filter = lead_lag.filter(10.0);
+        filter.update(0.0);
+        (0..samples).map(|_| filter.update(1.0)).collect()
+    }
+
+    #[test]
+    fn static_gain() {
+        let response = step_response(LeadLag::default().set_gain(2.5), 5);
+        assert_eq!(response, vec![2.5; 5]);
+    }
+
+    #[test]
+    fn lead_only_kicks_once() {
+        let response = step_response(LeadLag::default().set_gain(2.0).set_lead_time(30.0), 20);
+        // K (1 + T_lead / dt)
+        assert_eq!(response[0], 8.0);
+        assert!(response[1..].iter().all(|y| *y == 2.0), "{:?}", response);
+    }
+
+    #[test]
+    fn lead_lag_jumps_and_settles_at_the_gain() {
+        let response = step_response(LeadLag::default().set_gain(2.0).set_lead_time(30.0).set_lag_time(10.0), 200);
+        // K (1 + 2 T_lead / dt) / (1 + 2 T_lag / dt)
+        assert!((response[0] - 2.0 * 7.0 / 3.0).abs() < 1e-12);
+        // the lead exceeds the lag, the output decays monotonically towards the gain
+        assert!(response.windows(2).all(|w| w[1] <= w[0]));
+        assert!((response[199] - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn starts_in_steady_state() {
+        let mut filter = LeadLag::default().set_gain(2.0).set_lead_time(30.0).set_lag_time(10.0).filter(10.0);
+        assert_eq!(filter.update(1.5), 3.0);
+        assert_eq!(filter.update(1.5), 3.0);
+    }
+}
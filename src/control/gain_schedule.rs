@@ -0,0 +1,244 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::control::pid_law::PidLaw;
+use crate::control::LoopController;
+
+/// Signal that selects the PID parameter set
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum SchedulingVariable {
+    #[default]
+    Setpoint,
+    ProcessVariable,
+    /// Time signal given by the simulation, see [`LoopController::schedule`]
+    External,
+}
+
+impl SchedulingVariable {
+    pub const ALL: [SchedulingVariable; 3] = [
+        SchedulingVariable::Setpoint,
+        SchedulingVariable::ProcessVariable,
+        SchedulingVariable::External,
+    ];
+}
+
+impl fmt::Display for SchedulingVariable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchedulingVariable::Setpoint => write!(f, "Setpoint"),
+            SchedulingVariable::ProcessVariable => write!(f, "Process Variable"),
+            SchedulingVariable::External => write!(f, "External Signal"),
+        }
+    }
+}
+
+/// PID parameter set valid at one value of the scheduling variable
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GainScheduleRow {
+    pub at: f64,
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+}
+
+impl GainScheduleRow {
+    pub fn new(at: f64, kp: f64, ki: f64, kd: f64) -> Self {
+        GainScheduleRow { at, kp, ki, kd }
+    }
+}
+
+/// Table of PID parameter sets, indexed by the scheduling variable
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GainScheduleSetup {
+    pub variable: SchedulingVariable,
+    /// Linear interpolation between the rows, otherwise the gains switch at each row
+    pub interpolate: bool,
+    pub rows: Vec<GainScheduleRow>,
+}
+
+impl GainScheduleSetup {
+    pub fn set_variable(self, variable: SchedulingVariable) -> Self {
+        GainScheduleSetup { variable, ..self }
+    }
+
+    pub fn set_interpolate(self, interpolate: bool) -> Self {
+        GainScheduleSetup { interpolate, ..self }
+    }
+
+    pub fn set_rows(self, rows: Vec<GainScheduleRow>) -> Self {
+        GainScheduleSetup { rows, ..self }
+    }
+
+    /// Gains kp, ki and kd at the value of the scheduling variable.
+    ///
+    /// Outside the table the first resp. last row is used, `None` for an empty table.
+    pub fn gains_at(&self, value: f64) -> Option<(f64, f64, f64)> {
+        gains_at(&sorted(&self.rows), self.interpolate, value)
+    }
+}
+
+/// The rows in the order of the scheduling variable, the table keeps the order of input
+fn sorted(rows: &[GainScheduleRow]) -> Vec<GainScheduleRow> {
+    let mut rows = rows.to_vec();
+    rows.sort_by(|a, b| a.at.total_cmp(&b.at));
+    rows
+}
+
+/// Gains at the value of the scheduling variable from rows sorted by [`sorted`]
+fn gains_at(rows: &[GainScheduleRow], interpolate: bool, value: f64) -> Option<(f64, f64, f64)> {
+    let first = rows.first()?;
+    let below = rows.iter().rev().find(|r| r.at <= value).unwrap_or(first);
+    let above = rows.iter().find(|r| r.at > value);
+    match above {
+        Some(above) if interpolate && value >= below.at => {
+            let ratio = (value - below.at) / (above.at - below.at);
+            let lerp = |a: f64, b: f64| a + (b - a) * ratio;
+            Some((lerp(below.kp, above.kp), lerp(below.ki, above.ki), lerp(below.kd, above.kd)))
+        }
+        _ => Some((below.kp, below.ki, below.kd)),
+    }
+}
+
+/// PID law whose gains follow the [`GainScheduleSetup`] at every update
+#[derive(Debug, Clone)]
+pub struct GainScheduledPid {
+    law: PidLaw,
+    variable: SchedulingVariable,
+    interpolate: bool,
+    /// Sorted once, the gains are looked up at every update
+    rows: Vec<GainScheduleRow>,
+    external: f64,
+}
+
+impl GainScheduledPid {
+    pub fn new(law: PidLaw, schedule: &GainScheduleSetup) -> Self {
+        GainScheduledPid {
+            law,
+            variable: schedule.variable,
+            interpolate: schedule.interpolate,
+            rows: sorted(&schedule.rows),
+            external: 0.0,
+        }
+    }
+
+    fn apply_gains(&mut self, setpoint: f64, process_variable: f64) {
+        let value = match self.variable {
+            SchedulingVariable::Setpoint => setpoint,
+            SchedulingVariable::ProcessVariable => process_variable,
+            SchedulingVariable::External => self.external,
+        };
+        if let Some((kp, ki, kd)) = gains_at(&self.rows, self.interpolate, value) {
+            self.law.set_gains(kp, ki, kd);
+        }
+    }
+}
+
+impl LoopController for GainScheduledPid {
+    fn update(&mut self, setpoint: f64, process_variable: f64) -> f64 {
+        self.apply_gains(setpoint, process_variable);
+        self.law.update(setpoint, process_variable)
+    }
+
+    fn integral(&self) -> Option<f64> {
+        self.law.integral()
+    }
+
+    fn track(&mut self, setpoint: f64, process_variable: f64, output: f64) {
+        self.apply_gains(setpoint, process_variable);
+        self.law.track(setpoint, process_variable, output);
+    }
+
+    fn schedule(&mut self, scheduling_variable: f64) {
+        self.external = scheduling_variable;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::pid_setup::PidSetup;
+    use cb_controller::pid::PidCoreBuilder;
+
+    /// Rows deliberately not in the order of the scheduling variable
+    fn rows() -> Vec<GainScheduleRow> {
+        vec![
+            GainScheduleRow::new(10.0, 3.0, 0.3, 30.0),
+            GainScheduleRow::new(0.0, 1.0, 0.1, 10.0),
+            GainScheduleRow::new(5.0, 2.0, 0.2, 20.0),
+        ]
+    }
+
+    fn assert_gains(actual: Option<(f64, f64, f64)>, expected: (f64, f64, f64)) {
+        let actual = actual.unwrap();
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-12
+                && (actual.1 - expected.1).abs() < 1e-12
+                && (actual.2 - expected.2).abs() < 1e-12,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn interpolates_between_breakpoints() {
+        let schedule = GainScheduleSetup::default().set_rows(rows()).set_interpolate(true);
+        assert_gains(schedule.gains_at(2.5), (1.5, 0.15, 15.0));
+        assert_gains(schedule.gains_at(8.0), (2.6, 0.26, 26.0));
+        assert_gains(schedule.gains_at(5.0), (2.0, 0.2, 20.0));
+    }
+
+    #[test]
+    fn switches_at_breakpoints_without_interpolation() {
+        let schedule = GainScheduleSetup::default().set_rows(rows());
+        assert_gains(schedule.gains_at(4.999), (1.0, 0.1, 10.0));
+        assert_gains(schedule.gains_at(5.0), (2.0, 0.2, 20.0));
+        assert_gains(schedule.gains_at(9.0), (2.0, 0.2, 20.0));
+    }
+
+    #[test]
+    fn clamps_beyond_the_ends() {
+        for interpolate in [false, true] {
+            let schedule = GainScheduleSetup::default().set_rows(rows()).set_interpolate(interpolate);
+            assert_gains(schedule.gains_at(-100.0), (1.0, 0.1, 10.0));
+            assert_gains(schedule.gains_at(10.0), (3.0, 0.3, 30.0));
+            assert_gains(schedule.gains_at(100.0), (3.0, 0.3, 30.0));
+        }
+        assert_eq!(GainScheduleSetup::default().gains_at(1.0), None);
+    }
+
+    #[test]
+    fn table_keeps_its_order_and_the_controller_sorts_it_once() {
+        let schedule = GainScheduleSetup::default().set_rows(rows()).set_interpolate(true);
+        let pid = GainScheduledPid::new(PidLaw::new(&PidSetup::default().set_sampling_interval(1.0)), &schedule);
+        assert_eq!(schedule.rows, rows());
+        assert!(pid.rows.windows(2).all(|pair| pair[0].at <= pair[1].at));
+        assert_eq!(pid.rows.len(), 3);
+    }
+
+    /// Proportional-only output `kp e` of the first update at the scheduling value
+    fn first_output(variable: SchedulingVariable, rows: Vec<GainScheduleRow>, external: f64) -> f64 {
+        let rows = rows.into_iter().map(|r| GainScheduleRow { ki: 0.0, kd: 0.0, ..r }).collect();
+        let schedule = GainScheduleSetup::default().set_variable(variable).set_rows(rows).set_interpolate(true);
+        let setup = PidSetup::default()
+            .set_core(PidCoreBuilder::default().kp(100.0).ki(0.0).kd(0.0))
+            .set_sampling_interval(1.0);
+        let mut pid = GainScheduledPid::new(PidLaw::new(&setup), &schedule);
+        pid.schedule(external);
+        pid.update(8.5, 7.5)
+    }
+
+    #[test]
+    fn controller_follows_the_scheduling_variable() {
+        let mut sorted_rows = rows();
+        sorted_rows.sort_by(|a, b| a.at.total_cmp(&b.at));
+        for rows in [rows(), sorted_rows] {
+            // error 1, so the output is the scheduled kp
+            assert!((first_output(SchedulingVariable::Setpoint, rows.clone(), 0.0) - 2.7).abs() < 1e-12);
+            assert!((first_output(SchedulingVariable::ProcessVariable, rows.clone(), 0.0) - 2.5).abs() < 1e-12);
+            assert!((first_output(SchedulingVariable::External, rows.clone(), -3.0) - 1.0).abs() < 1e-12);
+        }
+    }
+}
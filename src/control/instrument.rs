@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+
+use crate::control::dead_time::DeadTime;
+use crate::control::feedforward::{LeadLag, LeadLagFilter};
+
+/// Rounds to one of `2^bits` levels spanning the range, values outside are clipped.
+///
+/// Zero bits stand for an ideal converter.
+pub fn quantize(value: f64, bits: u32, minimum: f64, maximum: f64) -> f64 {
+    if bits == 0 || maximum <= minimum {
+        return value;
+    }
+    let steps = (2.0_f64.powi(bits.min(52) as i32) - 1.0).max(1.0);
+    let resolution = (maximum - minimum) / steps;
+    let clipped = value.max(minimum).min(maximum);
+    minimum + ((clipped - minimum) / resolution).round() * resolution
+}
+
+/// Actuator between controller and plant input
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActuatorSetup {
+    /// Saturation, also the full scale of the DAC
+    pub minimum: f64,
+    pub maximum: f64,
+    /// Maximum change per ms, zero for no rate limit
+    pub rate_limit: f64,
+    /// Resolution of the DAC, zero for no quantization
+    pub bits: u32,
+}
+
+impl ActuatorSetup {
+    pub fn set_minimum(self, minimum: f64) -> Self {
+        ActuatorSetup { minimum, ..self }
+    }
+
+    pub fn set_maximum(self, maximum: f64) -> Self {
+        ActuatorSetup { maximum, ..self }
+    }
+
+    pub fn set_rate_limit(self, rate_limit: f64) -> Self {
+        ActuatorSetup { rate_limit, ..self }
+    }
+
+    pub fn set_bits(self, bits: u32) -> Self {
+        ActuatorSetup { bits, ..self }
+    }
+
+    pub fn actuator(&self, sampling_interval: f64) -> Actuator {
+        Actuator { setup: self.clone(), sampling_interval, previous: 0.0 }
+    }
+}
+
+impl Default for ActuatorSetup {
+    fn default() -> Self {
+        ActuatorSetup {
+            minimum: -10.0,
+            maximum: 10.0,
+            rate_limit: 0.0,
+            bits: 0,
+        }
+    }
+}
+
+/// Stateful actuator as created by [`ActuatorSetup::actuator`]
+#[derive(Debug, Clone)]
+pub struct Actuator {
+    setup: ActuatorSetup,
+    sampling_interval: f64,
+    previous: f64,
+}
+
+impl Actuator {
+    /// Returns the value actually applied to the plant for the commanded control variable
+    pub fn update(&mut self, command: f64) -> f64 {
+        let setup = &self.setup;
+        let mut output = command;
+        if setup.rate_limit > 0.0 {
+            let step = setup.rate_limit * self.sampling_interval;
+            output = output.clamp(self.previous - step, self.previous + step);
+        }
+        output = output.max(setup.minimum).min(setup.maximum);
+        output = quantize(output, setup.bits, setup.minimum, setup.maximum);
+        self.previous = output;
+        output
+    }
+}
+
+/// Sensor between plant output and controller
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensorSetup {
+    /// First-order lag [ms], zero for an ideal sensor
+    pub lag_time: f64,
+    /// Transport delay of the measurement [ms]
+    pub dead_time: f64,
+    /// Constant measurement error
+    pub offset: f64,
+    /// Resolution of the ADC, zero for no quantization
+    pub bits: u32,
+    /// Full scale of the ADC
+    pub minimum: f64,
+    pub maximum: f64,
+}
+
+impl SensorSetup {
+    pub fn set_lag_time(self, lag_time: f64) -> Self {
+        SensorSetup { lag_time, ..self }
+    }
+
+    pub fn set_dead_time(self, dead_time: f64) -> Self {
+        SensorSetup { dead_time, ..self }
+    }
+
+    pub fn set_offset(self, offset: f64) -> Self {
+        SensorSetup { offset, ..self }
+    }
+
+    pub fn set_bits(self, bits: u32) -> Self {
+        SensorSetup { bits, ..self }
+    }
+
+    pub fn set_minimum(self, minimum: f64) -> Self {
+        SensorSetup { minimum, ..self }
+    }
+
+    pub fn set_maximum(self, maximum: f64) -> Self {
+        SensorSetup { maximum, ..self }
+    }
+
+    pub fn sensor(&self, sampling_interval: f64) -> Sensor {
+        Sensor {
+            setup: self.clone(),
+            lag: LeadLag::default().set_lag_time(self.lag_time).filter(sampling_interval),
+            dead_time: DeadTime::new(self.dead_time, sampling_interval),
+        }
+    }
+}
+
+impl Default for SensorSetup {
+    fn default() -> Self {
+        SensorSetup {
+            lag_time: 0.0,
+            dead_time: 0.0,
+            offset: 0.0,
+            bits: 0,
+            minimum: -10.0,
+            maximum: 10.0,
+        }
+    }
+}
+
+/// Stateful sensor as created by [`SensorSetup::sensor`]
+#[derive(Debug, Clone)]
+pub struct Sensor {
+    setup: SensorSetup,
+    lag: LeadLagFilter,
+    dead_time: DeadTime,
+}
+
+impl Sensor {
+    /// Returns the measurement of the true process variable
+    pub fn update(&mut self, process_variable: f64) -> f64 {
+        let lagged = self.lag.update(process_variable);
+        let delayed = self.dead_time.update(lagged);
+        quantize(delayed + self.setup.offset, self.setup.bits, self.setup.minimum, self.setup.maximum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantize_rounds_to_the_nearest_level_and_clips() {
+        // 3 bits over 0..7 are the integers
+        assert_eq!(quantize(3.4, 3, 0.0, 7.0), 3.0);
+        assert_eq!(quantize(3.6, 3, 0.0, 7.0), 4.0);
+        assert_eq!(quantize(-2.0, 3, 0.0, 7.0), 0.0);
+        assert_eq!(quantize(9.0, 3, 0.0, 7.0), 7.0);
+        // ideal converter and empty range
+        assert_eq!(quantize(3.4, 0, 0.0, 7.0), 3.4);
+        assert_eq!(quantize(3.4, 3, 1.0, 1.0), 3.4);
+    }
+
+    #[test]
+    fn actuator_saturates() {
+        let mut actuator = ActuatorSetup::default().set_minimum(-1.0).set_maximum(2.0).actuator(10.0);
+        let applied: Vec<f64> = [5.0, 1.5, -3.0].iter().map(|u| actuator.update(*u)).collect();
+        assert_eq!(applied, vec![2.0, 1.5, -1.0]);
+    }
+
+    #[test]
+    fn actuator_rate_limit_ramps_per_sampling_interval() {
+        // 0.02 per ms at 10 ms are 0.2 per sample
+        let mut actuator = ActuatorSetup::default().set_rate_limit(0.02).actuator(10.0);
+        let applied: Vec<f64> = [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, -1.0].iter().map(|u| actuator.update(*u)).collect();
+        let expected = [0.2, 0.4, 0.6, 0.8, 1.0, 1.0, 0.8];
+        for (a, e) in applied.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-12, "{:?}", applied);
+        }
+    }
+
+    #[test]
+    fn actuator_rate_limit_starts_from_the_saturated_output() {
+        let mut actuator = ActuatorSetup::default().set_maximum(1.0).set_rate_limit(0.5).actuator(10.0);
+        assert_eq!(actuator.update(100.0), 1.0);
+        // falls from the applied 1, not from the commanded 100
+        assert_eq!(actuator.update(-100.0), -4.0);
+    }
+
+    #[test]
+    fn actuator_dac_resolution() {
+        // 2 bits over -10..10 have the levels -10, -3.33, 3.33 and 10
+        let mut actuator = ActuatorSetup::default().set_bits(2).actuator(10.0);
+        assert!((actuator.update(1.0) - 10.0 / 3.0).abs() < 1e-12);
+        assert!((actuator.update(-1.0) + 10.0 / 3.0).abs() < 1e-12);
+        assert_eq!(actuator.update(8.0), 10.0);
+    }
+
+    #[test]
+    fn sensor_offset_and_adc_resolution() {
+        // 4 bits over 0..15 are the integers
+        let mut sensor = SensorSetup::default().set_offset(0.3).set_bits(4).set_minimum(0.0).set_maximum(15.0).sensor(1.0);
+        let measured: Vec<f64> = [1.0, 1.1, 1.3, 20.0].iter().map(|y| sensor.update(*y)).collect();
+        assert_eq!(measured, vec![1.0, 1.0, 2.0, 15.0]);
+    }
+
+    #[test]
+    fn sensor_delays_and_lags() {
+        let mut delayed = SensorSetup::default().set_dead_time(20.0).sensor(10.0);
+        let measured: Vec<f64> = (1..=4).map(|y| delayed.update(y as f64)).collect();
+        assert_eq!(measured, vec![0.0, 0.0, 1.0, 2.0]);
+
+        // the lag starts in steady state at zero, then sees a unit step
+        let mut lagged = SensorSetup::default().set_lag_time(100.0).sensor(10.0);
+        assert_eq!(lagged.update(0.0), 0.0);
+        let measured: Vec<f64> = (0..200).map(|_| lagged.update(1.0)).collect();
+        assert!(measured[0] > 0.0 && measured[0] < 0.1, "{}", measured[0]);
+        assert!(measured.windows(2).all(|pair| pair[1] >= pair[0]));
+        assert!((measured[199] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ideal_sensor_passes_the_process_variable() {
+        let mut sensor = SensorSetup::default().sensor(10.0);
+        assert_eq!(sensor.update(1.234), 1.234);
+    }
+}
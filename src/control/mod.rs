@@ -0,0 +1,62 @@
+pub mod dead_time;
+#[cfg(feature = "cli")]
+pub mod external;
+pub mod feedforward;
+pub mod gain_schedule;
+pub mod instrument;
+pub mod mode_switch;
+pub mod mpc;
+pub mod pid_design;
+pub mod pid_form;
+pub mod pid_law;
+pub mod pid_setup;
+pub mod precision;
+pub mod qp;
+pub mod smith_predictor;
+pub mod state_feedback;
+pub mod state_space;
+pub mod timing;
+
+use cb_controller::pid::PidController;
+use cb_simulation_util::plant::BoxedTransferTimeDomain;
+
+/// Common interface of the controllers that can be simulated in a control loop
+pub trait LoopController: core::fmt::Debug {
+    /// Returns the control variable for the setpoint and the measured process variable
+    fn update(&mut self, setpoint: f64, process_variable: f64) -> f64;
+
+    /// State of the integrator, if the controller exposes it
+    fn integral(&self) -> Option<f64> {
+        None
+    }
+
+    /// Follows an output set from outside, e.g. in manual mode, so that the
+    /// next [`LoopController::update`] continues bumpless from there.
+    ///
+    /// Controllers without access to their internal states ignore it.
+    fn track(&mut self, _setpoint: f64, _process_variable: f64, _output: f64) {}
+
+    /// External scheduling variable, set before [`LoopController::update`];
+    /// ignored by controllers without gain schedule
+    fn schedule(&mut self, _scheduling_variable: f64) {}
+
+    /// Simulation time [ms], set before [`LoopController::update`];
+    /// ignored by controllers that do not depend on it
+    fn time(&mut self, _time: f64) {}
+}
+
+impl LoopController for PidController<f64> {
+    fn update(&mut self, setpoint: f64, process_variable: f64) -> f64 {
+        PidController::update(self, setpoint, process_variable)
+    }
+}
+
+/// Applies the input held over `steps` steps to a model discretized at the plant sampling
+/// interval, returns the output after the last step
+pub fn transfer_held(model: &mut BoxedTransferTimeDomain<f64>, input: f64, steps: usize) -> f64 {
+    let mut output = model.transfer_td(input);
+    for _ in 1..steps {
+        output = model.transfer_td(input);
+    }
+    output
+}
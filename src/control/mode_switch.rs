@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+
+use crate::control::LoopController;
+
+/// Operating mode of a controller
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlMode {
+    /// The output is set by the operator
+    Manual,
+    /// The output is calculated by the controller
+    Automatic,
+}
+
+/// Scheduled switch between manual and automatic mode during a simulation run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModeSwitchSetup {
+    /// Time of the switch [ms]
+    pub switch_time: f64,
+    /// Manual before the switch and automatic after it, otherwise the other way round
+    pub manual_first: bool,
+    /// Manual to automatic: the controller tracks the manual output;
+    /// automatic to manual: the manual output starts at the last controller output
+    pub bumpless: bool,
+}
+
+impl ModeSwitchSetup {
+    pub fn set_switch_time(self, switch_time: f64) -> Self {
+        ModeSwitchSetup { switch_time, ..self }
+    }
+
+    pub fn set_manual_first(self, manual_first: bool) -> Self {
+        ModeSwitchSetup { manual_first, ..self }
+    }
+
+    pub fn set_bumpless(self, bumpless: bool) -> Self {
+        ModeSwitchSetup { bumpless, ..self }
+    }
+
+    pub fn mode(&self, time: f64) -> ControlMode {
+        if (time < self.switch_time) == self.manual_first {
+            ControlMode::Manual
+        } else {
+            ControlMode::Automatic
+        }
+    }
+}
+
+impl Default for ModeSwitchSetup {
+    fn default() -> Self {
+        ModeSwitchSetup {
+            switch_time: 0.0,
+            manual_first: true,
+            bumpless: true,
+        }
+    }
+}
+
+/// Manual/automatic station in front of a controller
+#[derive(Debug, Clone)]
+pub struct ManualAutoStation {
+    setup: ModeSwitchSetup,
+    previous_output: f64,
+    /// Added to the manual profile, set at the first manual sample
+    manual_offset: Option<f64>,
+}
+
+impl ManualAutoStation {
+    pub fn new(setup: &ModeSwitchSetup) -> Self {
+        ManualAutoStation {
+            setup: setup.clone(),
+            previous_output: 0.0,
+            manual_offset: None,
+        }
+    }
+
+    /// Returns the manual output or the controller output plus feedforward, depending on the mode at `time`.
+    ///
+    /// The feedforward is added in automatic mode only, so the controller tracks the manual
+    /// output less the feedforward.
+    pub fn update(
+        &mut self,
+        controller: &mut dyn LoopController,
+        time: f64,
+        manual_output: f64,
+        feedforward: f64,
+        setpoint: f64,
+        process_variable: f64,
+    ) -> f64 {
+        let output = match self.setup.mode(time) {
+            ControlMode::Manual => {
+                let switched_from_automatic = self.setup.bumpless && !self.setup.manual_first;
+                let previous_output = self.previous_output;
+                let offset = *self.manual_offset
+                    .get_or_insert(if switched_from_automatic { previous_output - manual_output } else { 0.0 });
+                let output = manual_output + offset;
+                if self.setup.bumpless {
+                    controller.track(setpoint, process_variable, output - feedforward);
+                }
+                output
+            }
+            ControlMode::Automatic => controller.update(setpoint, process_variable) + feedforward,
+        };
+        self.previous_output = output;
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Controller answering a fixed output, records what it was told to track
+    #[derive(Debug, Default)]
+    struct Recorder {
+        output: f64,
+        tracked: Vec<f64>,
+    }
+
+    impl LoopController for Recorder {
+        fn update(&mut self, _setpoint: f64, _process_variable: f64) -> f64 {
+            self.output
+        }
+
+        fn track(&mut self, _setpoint: f64, _process_variable: f64, output: f64) {
+            self.tracked.push(output);
+        }
+    }
+
+    /// Outputs for the times 0 to 9 with the manual profile, switching at time 5
+    fn run(setup: ModeSwitchSetup, controller: &mut Recorder, manual_output: impl Fn(f64) -> f64) -> Vec<f64> {
+        let mut station = ManualAutoStation::new(&setup.set_switch_time(5.0));
+        (0..10)
+            .map(|t| {
+                let time = t as f64;
+                station.update(controller, time, manual_output(time), 0.5, 1.0, 0.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mode_changes_at_the_switch_time() {
+        let manual_first = ModeSwitchSetup::default().set_switch_time(5.0);
+        assert_eq!(manual_first.mode(4.9), ControlMode::Manual);
+        assert_eq!(manual_first.mode(5.0), ControlMode::Automatic);
+        let automatic_first = manual_first.set_manual_first(false);
+        assert_eq!(automatic_first.mode(4.9), ControlMode::Automatic);
+        assert_eq!(automatic_first.mode(5.0), ControlMode::Manual);
+    }
+
+    #[test]
+    fn bumpless_manual_mode_is_tracked_less_the_feedforward() {
+        let mut controller = Recorder { output: 3.0, ..Recorder::default() };
+        let u = run(ModeSwitchSetup::default(), &mut controller, |_| 2.0);
+        assert_eq!(u, vec![2.0, 2.0, 2.0, 2.0, 2.0, 3.5, 3.5, 3.5, 3.5, 3.5]);
+        assert_eq!(controller.tracked, vec![1.5; 5]);
+    }
+
+    #[test]
+    fn manual_mode_is_not_tracked_without_bumpless_transfer() {
+        let mut controller = Recorder { output: 3.0, ..Recorder::default() };
+        run(ModeSwitchSetup::default().set_bumpless(false), &mut controller, |_| 2.0);
+        assert!(controller.tracked.is_empty());
+    }
+
+    #[test]
+    fn bumpless_switch_to_manual_starts_at_the_last_output() {
+        let setup = ModeSwitchSetup::default().set_manual_first(false);
+        let mut controller = Recorder { output: 3.0, ..Recorder::default() };
+        // the manual profile is shifted to start at the last automatic output 3.5
+        let u = run(setup.clone(), &mut controller, |t| t / 10.0);
+        assert_eq!(&u[..5], &[3.5; 5]);
+        for (t, u) in u.iter().enumerate().skip(5) {
+            assert!((u - (3.5 + (t as f64 - 5.0) / 10.0)).abs() < 1e-12, "time {}: {}", t, u);
+        }
+
+        let mut controller = Recorder { output: 3.0, ..Recorder::default() };
+        let u = run(setup.set_bumpless(false), &mut controller, |t| t / 10.0);
+        assert_eq!(u[5], 0.5);
+    }
+}
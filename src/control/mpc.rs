@@ -0,0 +1,294 @@
+use std::fmt;
+
+use cb_simulation_util::plant::BoxedTransferTimeDomain;
+use ndarray::{Array1, Array2};
+
+use crate::control::qp::QuadraticProgram;
+use crate::control::{transfer_held, LoopController};
+use crate::plant::named_element::NamedElement;
+
+/// Linear model predictive controller with an element as internal model
+#[derive(Debug, Clone, PartialEq)]
+pub struct MpcSetup {
+    pub model: NamedElement<f64>,
+    /// Number of predicted samples
+    pub prediction_horizon: usize,
+    /// Number of future input moves, the input is held afterwards
+    pub control_horizon: usize,
+    /// Weight of the squared tracking error
+    pub output_weight: f64,
+    /// Weight of the squared input moves
+    pub rate_weight: f64,
+    /// Minimum and maximum of the control variable
+    pub input_limit: Option<(f64, f64)>,
+    /// Maximum change of the control variable per sample
+    pub rate_limit: Option<f64>,
+}
+
+impl MpcSetup {
+    pub fn set_model(self, model: NamedElement<f64>) -> Self {
+        MpcSetup { model, ..self }
+    }
+
+    pub fn set_prediction_horizon(self, prediction_horizon: usize) -> Self {
+        MpcSetup { prediction_horizon, ..self }
+    }
+
+    pub fn set_control_horizon(self, control_horizon: usize) -> Self {
+        MpcSetup { control_horizon, ..self }
+    }
+
+    pub fn set_output_weight(self, output_weight: f64) -> Self {
+        MpcSetup { output_weight, ..self }
+    }
+
+    pub fn set_rate_weight(self, rate_weight: f64) -> Self {
+        MpcSetup { rate_weight, ..self }
+    }
+
+    pub fn set_input_limit(self, input_limit: Option<(f64, f64)>) -> Self {
+        MpcSetup { input_limit, ..self }
+    }
+
+    pub fn set_rate_limit(self, rate_limit: Option<f64>) -> Self {
+        MpcSetup { rate_limit, ..self }
+    }
+}
+
+impl Default for MpcSetup {
+    fn default() -> Self {
+        MpcSetup {
+            model: NamedElement::<f64>::default(),
+            prediction_horizon: 20,
+            control_horizon: 5,
+            output_weight: 1.0,
+            rate_weight: 0.1,
+            input_limit: None,
+            rate_limit: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MpcError {
+    /// Horizons have to be positive and the control horizon not longer than the prediction horizon
+    InvalidHorizon,
+    /// The weights lead to a singular Hessian, e.g. a model without response and no rate weight
+    SingularHessian,
+}
+
+impl fmt::Display for MpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MpcError::InvalidHorizon => write!(f, "Control horizon must be between 1 and the prediction horizon"),
+            MpcError::SingularHessian => write!(f, "Optimization problem is singular, increase the rate weight"),
+        }
+    }
+}
+
+/// Dynamic matrix controller: the prediction is the free response of the internal model
+/// plus the step response to the future input moves, corrected by the current model error.
+///
+/// The input moves are optimized per sample by a [`QuadraticProgram`] and the first one is applied.
+#[derive(Debug)]
+pub struct Mpc {
+    model: BoxedTransferTimeDomain<f64>,
+    /// Plant steps of the model per controller sample
+    model_steps: usize,
+    /// Effect of the input moves on the predicted outputs
+    dynamic_matrix: Array2<f64>,
+    problem: QuadraticProgram,
+    output_weight: f64,
+    input_limit: Option<(f64, f64)>,
+    rate_limit: Option<f64>,
+    model_output: f64,
+    previous_output: f64,
+}
+
+impl Mpc {
+    /// The model is discretized at the plant sampling interval and stepped `model_steps` times
+    /// per controller sample, the horizons count controller samples
+    pub fn new(setup: &MpcSetup, model_steps: usize) -> Result<Self, MpcError> {
+        let model_steps = model_steps.max(1);
+        let prediction_horizon = setup.prediction_horizon;
+        let control_horizon = setup.control_horizon;
+        if control_horizon == 0 || control_horizon > prediction_horizon {
+            return Err(MpcError::InvalidHorizon);
+        }
+
+        // the element has not been simulated yet, so its clone starts at rest
+        let mut step_model = setup.model.element.clone();
+        let step_response: Vec<f64> =
+            (0..prediction_horizon).map(|_| transfer_held(&mut step_model, 1.0, model_steps)).collect();
+        let dynamic_matrix = Array2::from_shape_fn((prediction_horizon, control_horizon), |(j, m)| {
+            if m <= j { step_response[j - m] } else { 0.0 }
+        });
+
+        let hessian = dynamic_matrix.t().dot(&dynamic_matrix) * setup.output_weight
+            + Array2::<f64>::eye(control_horizon) * setup.rate_weight;
+        let problem = QuadraticProgram::new(&hessian, constraint_matrix(setup))
+            .ok_or(MpcError::SingularHessian)?;
+
+        Ok(Mpc {
+            model: setup.model.element.clone(),
+            model_steps,
+            dynamic_matrix,
+            problem,
+            output_weight: setup.output_weight,
+            input_limit: setup.input_limit,
+            rate_limit: setup.rate_limit,
+            model_output: 0.0,
+            previous_output: 0.0,
+        })
+    }
+
+    /// Applies the control variable to the internal model
+    fn advance(&mut self, output: f64) {
+        self.model_output = transfer_held(&mut self.model, output, self.model_steps);
+        self.previous_output = output;
+    }
+
+    /// Right-hand side of the constraints in the order of [`constraint_matrix`]
+    fn constraint_bounds(&self) -> Array1<f64> {
+        let control_horizon = self.dynamic_matrix.ncols();
+        let mut bounds = Vec::new();
+        if let Some(rate_limit) = self.rate_limit {
+            bounds.extend(std::iter::repeat(rate_limit).take(2 * control_horizon));
+        }
+        if let Some((minimum, maximum)) = self.input_limit {
+            bounds.extend(std::iter::repeat(maximum - self.previous_output).take(control_horizon));
+            bounds.extend(std::iter::repeat(self.previous_output - minimum).take(control_horizon));
+        }
+        Array1::from_vec(bounds)
+    }
+
+    /// Optimal input moves over the control horizon
+    fn plan(&self, setpoint: f64, process_variable: f64) -> Array1<f64> {
+        // output disturbance assumed constant over the horizon, removes the steady-state offset
+        let model_error = process_variable - self.model_output;
+        let mut free_model = self.model.clone();
+        let error = Array1::from_iter(
+            (0..self.dynamic_matrix.nrows())
+                .map(|_| setpoint - transfer_held(&mut free_model, self.previous_output, self.model_steps) - model_error),
+        );
+        let gradient = -self.dynamic_matrix.t().dot(&error) * self.output_weight;
+        self.problem.solve(&gradient, &self.constraint_bounds())
+    }
+}
+
+impl LoopController for Mpc {
+    fn update(&mut self, setpoint: f64, process_variable: f64) -> f64 {
+        let moves = self.plan(setpoint, process_variable);
+
+        let mut output = self.previous_output + moves[0];
+        if let Some(rate_limit) = self.rate_limit {
+            output = output.clamp(self.previous_output - rate_limit, self.previous_output + rate_limit);
+        }
+        if let Some((minimum, maximum)) = self.input_limit {
+            output = output.max(minimum).min(maximum);
+        }
+        self.advance(output);
+        output
+    }
+
+    fn track(&mut self, _setpoint: f64, _process_variable: f64, output: f64) {
+        self.advance(output);
+    }
+}
+
+/// `M Δu ≤ γ` for the rate limit `±Δu ≤ rate` and the input limit `min ≤ u_prev + Σ Δu ≤ max`
+fn constraint_matrix(setup: &MpcSetup) -> Array2<f64> {
+    let n = setup.control_horizon;
+    let cumulative = Array2::from_shape_fn((n, n), |(i, j)| if j <= i { 1.0 } else { 0.0 });
+    let mut blocks = Vec::new();
+    if setup.rate_limit.is_some() {
+        blocks.push(Array2::<f64>::eye(n));
+        blocks.push(-Array2::<f64>::eye(n));
+    }
+    if setup.input_limit.is_some() {
+        blocks.push(cumulative.clone());
+        blocks.push(-cumulative);
+    }
+    let mut constraints = Array2::<f64>::zeros((blocks.len() * n, n));
+    for (index, block) in blocks.iter().enumerate() {
+        constraints.slice_mut(ndarray::s![index * n..(index + 1) * n, ..]).assign(block);
+    }
+    constraints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cb_simulation_util::plant::pt1::PT1;
+
+    fn setup() -> MpcSetup {
+        let pt1 = PT1::<f64>::default().set_sample_time_or_default(1.0).set_t1_time_or_default(10.0).set_kp(2.0);
+        MpcSetup::default()
+            .set_model(NamedElement::default().set_element(Box::new(pt1)))
+            .set_rate_weight(0.01)
+    }
+
+    /// Step of the setpoint onto the model itself, returns control and process variable and the moves
+    /// planned in each sample
+    fn simulate(setup: &MpcSetup, setpoint: f64, steps: usize) -> (Vec<f64>, Vec<f64>, Vec<Array1<f64>>) {
+        let mut mpc = Mpc::new(setup, 1).unwrap();
+        let mut plant = setup.model.element.clone();
+        let (mut u, mut y, mut moves) = (Vec::new(), Vec::new(), Vec::new());
+        let mut measured = 0.0;
+        for _ in 0..steps {
+            moves.push(mpc.plan(setpoint, measured));
+            let output = mpc.update(setpoint, measured);
+            measured = transfer_held(&mut plant, output, 1);
+            u.push(output);
+            y.push(measured);
+        }
+        (u, y, moves)
+    }
+
+    #[test]
+    fn unconstrained_mpc_tracks_without_offset() {
+        let (_, y, _) = simulate(&setup(), 1.0, 200);
+        assert!((y[199] - 1.0).abs() < 1e-6, "{}", y[199]);
+    }
+
+    #[test]
+    fn planned_inputs_respect_the_input_limit() {
+        let setup = setup().set_input_limit(Some((-0.5, 0.8)));
+        let (u, y, moves) = simulate(&setup, 1.0, 200);
+        let mut previous = 0.0;
+        for (planned, output) in moves.iter().zip(&u) {
+            // every future input of the plan, not only the applied one
+            let mut input = previous;
+            for m in planned {
+                input += m;
+                assert!((-0.5 - 1e-6..=0.8 + 1e-6).contains(&input), "{} in {}", input, planned);
+            }
+            previous = *output;
+        }
+        // the setpoint needs u = 0.5, so the limit only acts on the way
+        assert!(u.iter().any(|u| (u - 0.8).abs() < 1e-9));
+        assert!((y[199] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn planned_moves_respect_the_rate_limit() {
+        let setup = setup().set_rate_limit(Some(0.05));
+        let (u, y, moves) = simulate(&setup, 1.0, 300);
+        for planned in &moves {
+            assert!(planned.iter().all(|m| m.abs() <= 0.05 + 1e-6), "{}", planned);
+        }
+        let mut previous = 0.0;
+        for output in &u {
+            assert!((output - previous).abs() <= 0.05 + 1e-12);
+            previous = *output;
+        }
+        assert!((u[0] - 0.05).abs() < 1e-6);
+        assert!((y[299] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn horizons_are_checked() {
+        assert_eq!(Mpc::new(&setup().set_control_horizon(0), 1).err(), Some(MpcError::InvalidHorizon));
+        assert_eq!(Mpc::new(&setup().set_control_horizon(21), 1).err(), Some(MpcError::InvalidHorizon));
+    }
+}
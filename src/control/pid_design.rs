@@ -0,0 +1,311 @@
+use std::fmt;
+
+use cb_simulation_util::plant::{pt1::PT1, pt2::PT2};
+
+use crate::plant::named_element::NamedElement;
+
+/// Linear plants the design assistant can handle analytically
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlantModel {
+    /// `kp / (t1 s + 1)`
+    Pt1 { kp: f64, t1: f64 },
+    /// `kp omega² / (s² + 2 damping omega s + omega²)`
+    Pt2 { kp: f64, omega: f64, damping: f64 },
+}
+
+impl PlantModel {
+    /// Returns `None` for elements without analytical model, e.g. dead time
+    pub fn from_element(element: &NamedElement<f64>) -> Option<Self> {
+        let any = element.element.as_any();
+        if let Some(pt1) = any.downcast_ref::<PT1<f64>>() {
+            Some(PlantModel::Pt1 { kp: pt1.kp, t1: pt1.t1_time })
+        } else {
+            any.downcast_ref::<PT2<f64>>()
+                .map(|pt2| PlantModel::Pt2 { kp: pt2.kp, omega: pt2.omega, damping: pt2.damping })
+        }
+    }
+
+    /// Numerator constant and denominator coefficients in ascending powers of s
+    fn transfer_function(&self) -> (f64, Vec<f64>) {
+        match *self {
+            PlantModel::Pt1 { kp, t1 } => (kp, vec![1.0, t1]),
+            PlantModel::Pt2 { kp, omega, damping } => {
+                (kp * omega * omega, vec![omega * omega, 2.0 * damping * omega, 1.0])
+            }
+        }
+    }
+
+    /// Magnitude and phase [rad] at the angular frequency
+    fn frequency_response(&self, frequency: f64) -> (f64, f64) {
+        let (numerator, denominator) = self.transfer_function();
+        let (re, im) = evaluate(&denominator, 0.0, frequency);
+        (numerator.abs() / re.hypot(im), -im.atan2(re))
+    }
+}
+
+/// Desired closed-loop characteristics, frequencies in rad per time unit of the simulation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DesignGoal {
+    /// Dominant pole pair `s² + 2 damping omega s + omega²`,
+    /// a PT2 plant gets a third pole at `-omega`
+    PolePlacement { omega: f64, damping: f64 },
+    /// Crossover frequency of the open loop and phase margin [°],
+    /// the PID gets a double zero, i.e. reset time = 4 hold time
+    LoopShaping { crossover: f64, phase_margin: f64, with_derivative: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DesignError {
+    /// The desired dynamic is slower than the plant, the controller gain would be negative
+    NegativeGain,
+    /// The phase at crossover can not be reached with the controller structure
+    PhaseNotReachable,
+}
+
+impl fmt::Display for DesignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DesignError::NegativeGain => write!(f, "Gains get negative, choose faster closed-loop dynamics"),
+            DesignError::PhaseNotReachable => write!(f, "Phase margin not reachable with this controller at this crossover frequency"),
+        }
+    }
+}
+
+/// Closed-loop pole `re + j im`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pole {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl fmt::Display for Pole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.im.abs() < 1e-12 {
+            write!(f, "{:.6}", self.re)
+        } else {
+            write!(f, "{:.6} {} {:.6}j", self.re, if self.im < 0.0 { "-" } else { "+" }, self.im.abs())
+        }
+    }
+}
+
+/// Parallel PID gains and the resulting closed-loop poles
+#[derive(Debug, Clone, PartialEq)]
+pub struct PidDesign {
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+    pub poles: Vec<Pole>,
+}
+
+/// Computes the PID gains analytically for the plant and the goal
+pub fn design(plant: &PlantModel, goal: &DesignGoal) -> Result<PidDesign, DesignError> {
+    let (kp, ki, kd) = match (*plant, *goal) {
+        // PI: t1 s² + (1 + k kp) s + k ki
+        (PlantModel::Pt1 { kp: k, t1 }, DesignGoal::PolePlacement { omega, damping }) => {
+            ((2.0 * damping * omega * t1 - 1.0) / k, omega * omega * t1 / k, 0.0)
+        }
+        // PID: s³ + (2 dp wp + k wp² kd) s² + (wp² + k wp² kp) s + k wp² ki
+        (PlantModel::Pt2 { kp: k, omega: wp, damping: dp }, DesignGoal::PolePlacement { omega, damping }) => {
+            let gain = k * wp * wp;
+            (
+                (omega * omega * (1.0 + 2.0 * damping) - wp * wp) / gain,
+                omega * omega * omega / gain,
+                (omega * (2.0 * damping + 1.0) - 2.0 * dp * wp) / gain,
+            )
+        }
+        (_, DesignGoal::LoopShaping { crossover, phase_margin, with_derivative }) => {
+            // the controller has to deliver C(j wc) = m e^(j theta)
+            let (magnitude, phase) = plant.frequency_response(crossover);
+            let m = 1.0 / magnitude;
+            let theta = (phase_margin - 180.0).to_radians() - phase;
+            let theta = (theta + std::f64::consts::PI).rem_euclid(2.0 * std::f64::consts::PI) - std::f64::consts::PI;
+            let kp = m * theta.cos();
+            if kp <= 0.0 {
+                return Err(DesignError::PhaseNotReachable);
+            }
+            if with_derivative {
+                // kp (td wc - 1 / (4 td wc)) = m sin(theta)
+                let hold_time = (theta.tan() + 1.0 / theta.cos()) / 2.0 / crossover;
+                (kp, kp / (4.0 * hold_time), kp * hold_time)
+            } else {
+                // kp (-1 / (ti wc)) = m sin(theta)
+                if theta >= 0.0 {
+                    return Err(DesignError::PhaseNotReachable);
+                }
+                (kp, -kp * crossover * theta.tan(), 0.0)
+            }
+        }
+    };
+    if kp < 0.0 || ki < 0.0 || kd < 0.0 {
+        return Err(DesignError::NegativeGain);
+    }
+    Ok(PidDesign { kp, ki, kd, poles: closed_loop_poles(plant, kp, ki, kd) })
+}
+
+/// Roots of `s a(s) + b (kd s² + kp s + ki)` for the plant `b / a(s)`
+fn closed_loop_poles(plant: &PlantModel, kp: f64, ki: f64, kd: f64) -> Vec<Pole> {
+    let (numerator, denominator) = plant.transfer_function();
+    let mut characteristic = vec![0.0; denominator.len() + 1];
+    for (power, a) in denominator.iter().enumerate() {
+        characteristic[power + 1] += a;
+    }
+    for (power, c) in [ki, kp, kd].iter().enumerate() {
+        characteristic[power] += numerator * c;
+    }
+    roots(&characteristic)
+}
+
+/// Polynomial with ascending coefficients at `re + j im`
+fn evaluate(coefficients: &[f64], re: f64, im: f64) -> (f64, f64) {
+    coefficients.iter().rev().fold((0.0, 0.0), |(acc_re, acc_im), c| {
+        (acc_re * re - acc_im * im + c, acc_re * im + acc_im * re)
+    })
+}
+
+/// Roots of polynomials up to degree three, coefficients ascending
+fn roots(coefficients: &[f64]) -> Vec<Pole> {
+    let mut coefficients = coefficients.to_vec();
+    while coefficients.len() > 1 && coefficients.last() == Some(&0.0) {
+        coefficients.pop();
+    }
+    match coefficients.len() {
+        2 => vec![Pole { re: -coefficients[0] / coefficients[1], im: 0.0 }],
+        3 => {
+            let (c, b, a) = (coefficients[0], coefficients[1], coefficients[2]);
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant >= 0.0 {
+                let root = discriminant.sqrt();
+                vec![
+                    Pole { re: (-b + root) / (2.0 * a), im: 0.0 },
+                    Pole { re: (-b - root) / (2.0 * a), im: 0.0 },
+                ]
+            } else {
+                let root = (-discriminant).sqrt();
+                vec![
+                    Pole { re: -b / (2.0 * a), im: root / (2.0 * a) },
+                    Pole { re: -b / (2.0 * a), im: -root / (2.0 * a) },
+                ]
+            }
+        }
+        4 => {
+            // a cubic has a real root within the Cauchy bound, found by bisection
+            let leading = coefficients[3];
+            let monic: Vec<f64> = coefficients.iter().map(|c| c / leading).collect();
+            let bound = 1.0 + monic[..3].iter().fold(0.0_f64, |m, c| m.max(c.abs()));
+            let value = |s: f64| evaluate(&monic, s, 0.0).0;
+            let (mut low, mut high) = (-bound, bound);
+            for _ in 0..200 {
+                let middle = (low + high) / 2.0;
+                if value(middle) < 0.0 {
+                    low = middle;
+                } else {
+                    high = middle;
+                }
+            }
+            let real = (low + high) / 2.0;
+            // deflate to the quadratic s² + (a2 + real) s + (a1 + real (a2 + real))
+            let b1 = monic[2] + real;
+            let b0 = monic[1] + real * b1;
+            let mut poles = vec![Pole { re: real, im: 0.0 }];
+            poles.extend(roots(&[b0, b1, 1.0]));
+            poles
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    fn assert_poles(poles: &[Pole], expected: &[(f64, f64)]) {
+        assert_eq!(poles.len(), expected.len());
+        for (re, im) in expected {
+            assert!(
+                poles.iter().any(|p| (p.re - re).abs() < 1e-6 && (p.im - im).abs() < 1e-6),
+                "{:?} misses {} + {}j",
+                poles,
+                re,
+                im
+            );
+        }
+    }
+
+    /// Open loop `C(jw) P(jw)` as magnitude and phase [°]
+    fn open_loop(plant: &PlantModel, pid: &PidDesign, frequency: f64) -> (f64, f64) {
+        let (magnitude, phase) = plant.frequency_response(frequency);
+        let (re, im) = (pid.kp, pid.kd * frequency - pid.ki / frequency);
+        (magnitude * re.hypot(im), (phase + im.atan2(re)).to_degrees())
+    }
+
+    #[test]
+    fn pole_placement_of_a_pi_for_a_pt1() {
+        // Åström & Murray, Feedback Systems, PI for a first order system:
+        // kp = (2 damping omega t1 - 1) / k, ki = omega² t1 / k
+        let plant = PlantModel::Pt1 { kp: 1.0, t1: 10.0 };
+        let pid = design(&plant, &DesignGoal::PolePlacement { omega: 0.5, damping: 0.7 }).unwrap();
+        assert_close(pid.kp, 6.0);
+        assert_close(pid.ki, 2.5);
+        assert_close(pid.kd, 0.0);
+        // s² + 0.7 s + 0.25
+        let damped = 0.5 * (1.0_f64 - 0.49).sqrt();
+        assert_poles(&pid.poles, &[(-0.35, damped), (-0.35, -damped)]);
+    }
+
+    #[test]
+    fn pole_placement_of_a_pid_for_a_pt2() {
+        // (s + 2)(s² + 2 s + 4) = s³ + 4 s² + 8 s + 8 for the plant 1 / (s² + s + 1)
+        let plant = PlantModel::Pt2 { kp: 1.0, omega: 1.0, damping: 0.5 };
+        let pid = design(&plant, &DesignGoal::PolePlacement { omega: 2.0, damping: 0.5 }).unwrap();
+        assert_close(pid.kp, 7.0);
+        assert_close(pid.ki, 8.0);
+        assert_close(pid.kd, 3.0);
+        assert_poles(&pid.poles, &[(-2.0, 0.0), (-1.0, 3.0_f64.sqrt()), (-1.0, -3.0_f64.sqrt())]);
+    }
+
+    #[test]
+    fn pole_placement_slower_than_the_plant_is_rejected() {
+        let plant = PlantModel::Pt1 { kp: 1.0, t1: 10.0 };
+        let goal = DesignGoal::PolePlacement { omega: 0.01, damping: 0.7 };
+        assert_eq!(design(&plant, &goal), Err(DesignError::NegativeGain));
+    }
+
+    #[test]
+    fn loop_shaping_of_a_pi_for_a_pt1() {
+        // 1 / (s + 1) at w = 1 has |P| = 1/√2 and -45°, 60° margin need the PI phase -75°:
+        // kp = √2 cos 75° = (√3 - 1) / 2, ki = kp tan 75° = (√3 + 1) / 2
+        let plant = PlantModel::Pt1 { kp: 1.0, t1: 1.0 };
+        let goal = DesignGoal::LoopShaping { crossover: 1.0, phase_margin: 60.0, with_derivative: false };
+        let pid = design(&plant, &goal).unwrap();
+        assert_close(pid.kp, (3.0_f64.sqrt() - 1.0) / 2.0);
+        assert_close(pid.ki, (3.0_f64.sqrt() + 1.0) / 2.0);
+        assert_close(pid.kd, 0.0);
+        let (magnitude, phase) = open_loop(&plant, &pid, 1.0);
+        assert_close(magnitude, 1.0);
+        assert_close(phase, -120.0);
+    }
+
+    #[test]
+    fn loop_shaping_of_a_pid_for_a_pt2() {
+        let plant = PlantModel::Pt2 { kp: 2.0, omega: 0.5, damping: 0.4 };
+        let goal = DesignGoal::LoopShaping { crossover: 1.0, phase_margin: 45.0, with_derivative: true };
+        let pid = design(&plant, &goal).unwrap();
+        let (magnitude, phase) = open_loop(&plant, &pid, 1.0);
+        assert_close(magnitude, 1.0);
+        assert_close(phase, -135.0);
+        // double zero: reset time 4 times the hold time, i.e. kp² = 4 ki kd
+        assert_close(pid.kp * pid.kp, 4.0 * pid.ki * pid.kd);
+    }
+
+    #[test]
+    fn loop_shaping_with_a_pi_needing_phase_lead_is_rejected() {
+        // the PT2 lags by almost 180° at w = 5, a PI can only add lag
+        let plant = PlantModel::Pt2 { kp: 1.0, omega: 0.5, damping: 0.4 };
+        let goal = DesignGoal::LoopShaping { crossover: 5.0, phase_margin: 60.0, with_derivative: false };
+        assert_eq!(design(&plant, &goal), Err(DesignError::PhaseNotReachable));
+    }
+}
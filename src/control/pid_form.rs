@@ -0,0 +1,211 @@
+use std::fmt;
+
+/// Vendors parameterize the same PID in different forms
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum PidForm {
+    /// `u = kp e + ki ∫e dt + kd de/dt`, the form of `PidCoreBuilder`
+    #[default]
+    Parallel,
+    /// ISA standard form `u = k (e + 1/ti ∫e dt + td de/dt)`
+    Ideal,
+    /// Interacting form `u = k (1 + 1/(ti s)) (1 + td s)`
+    Series,
+}
+
+impl PidForm {
+    pub const ALL: [PidForm; 3] = [PidForm::Parallel, PidForm::Ideal, PidForm::Series];
+
+    /// Names of the three parameters in this form
+    pub fn parameter_names(&self) -> [&'static str; 3] {
+        match self {
+            PidForm::Parallel => ["Kp", "Ki", "Kd"],
+            PidForm::Ideal => ["K", "Ti", "Td"],
+            PidForm::Series => ["K'", "Ti'", "Td'"],
+        }
+    }
+}
+
+impl fmt::Display for PidForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PidForm::Parallel => write!(f, "Parallel"),
+            PidForm::Ideal => write!(f, "Ideal (ISA)"),
+            PidForm::Series => write!(f, "Series (interacting)"),
+        }
+    }
+}
+
+/// Why parameters cannot be converted exactly into another form
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConversionError {
+    /// Integral or derivative action without proportional gain, the ideal and the series form
+    /// scale all terms by the gain
+    ZeroGain,
+    /// The series form needs real zeros, i.e. an integral time of at least four times the derivative time
+    ComplexZeros,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::ZeroGain => write!(f, "Not representable: integral or derivative action without gain"),
+            ConversionError::ComplexZeros => write!(
+                f,
+                "Not representable: complex zeros, the integral time is less than 4 times the derivative time"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// PID parameters in one of the forms of [`PidForm`].
+///
+/// An infinite integral time stands for a controller without integral action.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PidParameters {
+    pub form: PidForm,
+    /// `kp` resp. `k`
+    pub gain: f64,
+    /// `ki` for the parallel form, otherwise the integral (reset) time
+    pub integral: f64,
+    /// `kd` for the parallel form, otherwise the derivative (hold) time
+    pub derivative: f64,
+}
+
+impl PidParameters {
+    pub fn new(form: PidForm, gain: f64, integral: f64, derivative: f64) -> Self {
+        PidParameters { form, gain, integral, derivative }
+    }
+
+    pub fn parallel(kp: f64, ki: f64, kd: f64) -> Self {
+        PidParameters::new(PidForm::Parallel, kp, ki, kd)
+    }
+
+    pub fn values(&self) -> [f64; 3] {
+        [self.gain, self.integral, self.derivative]
+    }
+
+    /// Converts exactly into the given form, the own form is returned unchanged.
+    ///
+    /// Fails if the controller cannot be represented in the requested form, e.g. the series
+    /// form of a controller with complex zeros.
+    pub fn convert(&self, form: PidForm) -> Result<PidParameters, ConversionError> {
+        if form == self.form {
+            return Ok(*self);
+        }
+        let ideal = self.to_ideal()?;
+        match form {
+            PidForm::Parallel => Ok(ideal.ideal_to_parallel()),
+            PidForm::Ideal => Ok(ideal),
+            PidForm::Series => ideal.ideal_to_series(),
+        }
+    }
+
+    fn to_ideal(self) -> Result<PidParameters, ConversionError> {
+        match self.form {
+            PidForm::Parallel => {
+                let (kp, ki, kd) = (self.gain, self.integral, self.derivative);
+                if kp == 0.0 && (ki != 0.0 || kd != 0.0) {
+                    return Err(ConversionError::ZeroGain);
+                }
+                let ti = if ki > 0.0 { kp / ki } else { f64::INFINITY };
+                let td = if kp != 0.0 { kd / kp } else { 0.0 };
+                Ok(PidParameters::new(PidForm::Ideal, kp, ti, td))
+            }
+            PidForm::Ideal => Ok(self),
+            PidForm::Series => {
+                let (k, ti, td) = (self.gain, self.integral, self.derivative);
+                if ti.is_infinite() {
+                    Ok(PidParameters::new(PidForm::Ideal, k, ti, td))
+                } else {
+                    Ok(PidParameters::new(PidForm::Ideal, k * (1.0 + td / ti), ti + td, ti * td / (ti + td)))
+                }
+            }
+        }
+    }
+
+    fn ideal_to_parallel(self) -> PidParameters {
+        let ki = if self.integral.is_finite() && self.integral > 0.0 { self.gain / self.integral } else { 0.0 };
+        PidParameters::parallel(self.gain, ki, self.gain * self.derivative)
+    }
+
+    fn ideal_to_series(self) -> Result<PidParameters, ConversionError> {
+        let (k, ti, td) = (self.gain, self.integral, self.derivative);
+        if ti.is_infinite() {
+            return Ok(PidParameters::new(PidForm::Series, k, ti, td));
+        }
+        let discriminant = 1.0 - 4.0 * td / ti;
+        if discriminant < 0.0 {
+            return Err(ConversionError::ComplexZeros);
+        }
+        let root = discriminant.sqrt();
+        Ok(PidParameters::new(
+            PidForm::Series,
+            k / 2.0 * (1.0 + root),
+            ti / 2.0 * (1.0 + root),
+            ti / 2.0 * (1.0 - root),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: PidParameters, expected: PidParameters) {
+        assert_eq!(actual.form, expected.form);
+        for (a, e) in actual.values().iter().zip(expected.values()) {
+            assert!(a == &e || (a - e).abs() <= 1e-12 * e.abs(), "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    /// Converts into every other form and back
+    fn assert_round_trips(parameters: PidParameters) {
+        for form in PidForm::ALL {
+            let converted = parameters.convert(form).unwrap();
+            assert_close(converted.convert(parameters.form).unwrap(), parameters);
+        }
+    }
+
+    #[test]
+    fn parallel_to_ideal_and_series() {
+        let parallel = PidParameters::parallel(2.0, 0.1, 5.0);
+        assert_close(parallel.convert(PidForm::Ideal).unwrap(), PidParameters::new(PidForm::Ideal, 2.0, 20.0, 2.5));
+        // ti = 20, td = 2.5: root = sqrt(1 - 0.5)
+        let root = 0.5_f64.sqrt();
+        let series = PidParameters::new(PidForm::Series, 1.0 + root, 10.0 * (1.0 + root), 10.0 * (1.0 - root));
+        assert_close(parallel.convert(PidForm::Series).unwrap(), series);
+    }
+
+    #[test]
+    fn round_trips_of_all_forms() {
+        assert_round_trips(PidParameters::parallel(2.0, 0.1, 5.0));
+        assert_round_trips(PidParameters::new(PidForm::Ideal, 1.5, 40.0, 4.0));
+        assert_round_trips(PidParameters::new(PidForm::Series, 1.2, 30.0, 3.0));
+    }
+
+    #[test]
+    fn round_trips_without_integral_action() {
+        assert_round_trips(PidParameters::parallel(2.0, 0.0, 5.0));
+        assert_round_trips(PidParameters::new(PidForm::Ideal, 1.5, f64::INFINITY, 4.0));
+        assert_round_trips(PidParameters::new(PidForm::Series, 1.2, f64::INFINITY, 3.0));
+    }
+
+    #[test]
+    fn zero_gain_is_rejected_unless_the_controller_is_off() {
+        for parameters in [PidParameters::parallel(0.0, 0.1, 0.0), PidParameters::parallel(0.0, 0.0, 5.0)] {
+            assert_eq!(parameters.convert(PidForm::Ideal), Err(ConversionError::ZeroGain));
+            assert_eq!(parameters.convert(PidForm::Series), Err(ConversionError::ZeroGain));
+            assert_eq!(parameters.convert(PidForm::Parallel), Ok(parameters));
+        }
+        assert_round_trips(PidParameters::parallel(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn complex_zeros_have_no_series_form() {
+        let ideal = PidParameters::new(PidForm::Ideal, 1.0, 10.0, 5.0);
+        assert_eq!(ideal.convert(PidForm::Series), Err(ConversionError::ComplexZeros));
+        assert_round_trips(PidParameters::new(PidForm::Ideal, 1.0, 20.0, 5.0));
+    }
+}
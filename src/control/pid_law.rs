@@ -0,0 +1,433 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::control::pid_setup::PidSetup;
+use crate::control::precision::Precision;
+use crate::control::LoopController;
+
+/// Source of the derivative term
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum DerivativeMode {
+    /// Derivative of the weighted error `c r - y`
+    #[default]
+    OnError,
+    /// Derivative of the negated process variable, avoids the derivative kick on setpoint steps
+    OnMeasurement,
+}
+
+/// Strategy against integrator windup while the output limit is active
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum AntiWindup {
+    #[default]
+    None,
+    /// Conditional integration: the integral is frozen while the error drives the output further into saturation
+    Clamping,
+    /// The integral is corrected by the saturation excess, weighted by `sampling interval / tracking time`
+    BackCalculation { tracking_time: f64 },
+}
+
+/// Discretization of the integral and the (filtered) derivative term
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Discretization {
+    /// `s = (z - 1) / dt`, the integral uses the previous error;
+    /// an unfiltered derivative falls back to the backward difference
+    ForwardEuler,
+    /// `s = (z - 1) / (z dt)`, the integral uses the recent error
+    #[default]
+    BackwardEuler,
+    /// Bilinear transform `s = 2 (z - 1) / (dt (z + 1))`, the integral uses the trapezoidal rule;
+    /// an unfiltered derivative falls back to the backward difference, its pole would be at z = -1
+    Tustin,
+}
+
+impl Discretization {
+    pub const ALL: [Discretization; 3] = [
+        Discretization::ForwardEuler,
+        Discretization::BackwardEuler,
+        Discretization::Tustin,
+    ];
+}
+
+impl fmt::Display for Discretization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Discretization::ForwardEuler => write!(f, "Forward Euler"),
+            Discretization::BackwardEuler => write!(f, "Backward Euler"),
+            Discretization::Tustin => write!(f, "Tustin"),
+        }
+    }
+}
+
+/// PID structure options beyond the gains of the `PidCoreBuilder`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PidOptions {
+    /// Setpoint weight b of the proportional term
+    pub setpoint_weight_p: f64,
+    /// Setpoint weight c of the derivative term, ignored for derivative on measurement
+    pub setpoint_weight_d: f64,
+    /// Derivative filter N, the filter time constant is `hold time / N`
+    pub derivative_filter: Option<f64>,
+    pub derivative: DerivativeMode,
+    pub anti_windup: AntiWindup,
+    pub discretization: Discretization,
+    /// Number format of the controller arithmetic on the target
+    pub precision: Precision,
+    /// Value represented by the largest fixed-point number, ignored for floating point
+    pub full_scale: f64,
+}
+
+impl PidOptions {
+    pub fn set_setpoint_weight_p(self, setpoint_weight_p: f64) -> Self {
+        PidOptions { setpoint_weight_p, ..self }
+    }
+
+    pub fn set_setpoint_weight_d(self, setpoint_weight_d: f64) -> Self {
+        PidOptions { setpoint_weight_d, ..self }
+    }
+
+    pub fn set_derivative_filter(self, derivative_filter: Option<f64>) -> Self {
+        PidOptions { derivative_filter, ..self }
+    }
+
+    pub fn set_derivative(self, derivative: DerivativeMode) -> Self {
+        PidOptions { derivative, ..self }
+    }
+
+    pub fn set_anti_windup(self, anti_windup: AntiWindup) -> Self {
+        PidOptions { anti_windup, ..self }
+    }
+
+    pub fn set_discretization(self, discretization: Discretization) -> Self {
+        PidOptions { discretization, ..self }
+    }
+
+    pub fn set_precision(self, precision: Precision) -> Self {
+        PidOptions { precision, ..self }
+    }
+
+    pub fn set_full_scale(self, full_scale: f64) -> Self {
+        PidOptions { full_scale, ..self }
+    }
+
+    /// True if the options describe the textbook PID, which `cb_controller` implements
+    ///
+    /// `cb_controller` supports clamping as anti-windup strategy, too.
+    /// The full scale only matters for fixed point, which `cb_controller` does not support.
+    pub fn is_standard(&self) -> bool {
+        let anti_windup = match self.anti_windup {
+            AntiWindup::Clamping => AntiWindup::None,
+            other => other,
+        };
+        let default = PidOptions::default();
+        PidOptions { anti_windup, full_scale: default.full_scale, ..self.clone() } == default
+    }
+}
+
+impl Default for PidOptions {
+    fn default() -> Self {
+        PidOptions {
+            setpoint_weight_p: 1.0,
+            setpoint_weight_d: 1.0,
+            derivative_filter: None,
+            derivative: DerivativeMode::OnError,
+            anti_windup: AntiWindup::None,
+            discretization: Discretization::BackwardEuler,
+            precision: Precision::F64,
+            full_scale: 10.0,
+        }
+    }
+}
+
+/// Two-degree-of-freedom PID in parallel form
+///
+/// `u = kp (b r - y) + ki ∫(r - y) dt + kd d/dt (c r - y)`
+///
+/// Gains, output limit, setpoint range and dead band are taken over from the [`PidSetup`],
+/// within the dead band the integral is frozen.
+/// Gains, signals and states are rounded to the [`Precision`] of the options.
+#[derive(Debug, Clone)]
+pub struct PidLaw {
+    kp: f64,
+    ki: f64,
+    kd: f64,
+    dt: f64,
+    options: PidOptions,
+    /// minimum and maximum
+    output_limit: Option<(f64, f64)>,
+    /// minimum, maximum and output if the setpoint is out of range
+    setpoint_range: Option<(f64, f64, f64)>,
+    dead_band: Option<f64>,
+    integral: f64,
+    derivative: f64,
+    previous_error: Option<f64>,
+    previous_derivative_input: Option<f64>,
+}
+
+impl PidLaw {
+    pub fn new(setup: &PidSetup) -> Self {
+        PidLaw {
+            kp: setup.options.precision.coefficient(setup.core.kp),
+            ki: setup.options.precision.coefficient(setup.core.get_ki()),
+            kd: setup.options.precision.coefficient(setup.core.get_kd()),
+            dt: setup.sampling_interval(),
+            options: setup.options.clone(),
+            output_limit: setup
+                .output_limit
+                .as_ref()
+                .map(|l| (l.minimum(), l.maximum())),
+            setpoint_range: setup
+                .setpoint_range
+                .as_ref()
+                .map(|r| (r.minimum(), r.maximum(), r.off_band_output)),
+            dead_band: setup.dead_band,
+            integral: 0.0,
+            derivative: 0.0,
+            previous_error: None,
+            previous_derivative_input: None,
+        }
+    }
+
+    /// Changes the gains while running, the accumulated integral is kept
+    pub fn set_gains(&mut self, kp: f64, ki: f64, kd: f64) {
+        self.kp = self.options.precision.coefficient(kp);
+        self.ki = self.options.precision.coefficient(ki);
+        self.kd = self.options.precision.coefficient(kd);
+    }
+
+    /// Rounds an intermediate value to the precision of the target
+    fn round(&self, value: f64) -> f64 {
+        self.options.precision.signal(value, self.options.full_scale)
+    }
+}
+
+impl LoopController for PidLaw {
+    fn update(&mut self, setpoint: f64, process_variable: f64) -> f64 {
+        if let Some((minimum, maximum, off_band_output)) = self.setpoint_range {
+            if setpoint < minimum || setpoint > maximum {
+                return off_band_output;
+            }
+        }
+
+        let setpoint = self.round(setpoint);
+        let process_variable = self.round(process_variable);
+        let error = self.round(setpoint - process_variable);
+        let proportional = self.round(self.kp * (self.options.setpoint_weight_p * setpoint - process_variable));
+
+        let previous_error = self.previous_error.replace(error);
+        let integral_increment = match self.dead_band {
+            Some(tolerance) if error.abs() <= tolerance => 0.0,
+            _ => match self.options.discretization {
+                Discretization::ForwardEuler => self.ki * previous_error.unwrap_or_default() * self.dt,
+                Discretization::BackwardEuler => self.ki * error * self.dt,
+                Discretization::Tustin => self.ki * (error + previous_error.unwrap_or(error)) * self.dt / 2.0,
+            },
+        };
+        let integral_increment = self.round(integral_increment);
+        self.integral = self.round(self.integral + integral_increment);
+
+        let derivative_input = match self.options.derivative {
+            DerivativeMode::OnError => self.options.setpoint_weight_d * setpoint - process_variable,
+            DerivativeMode::OnMeasurement => -process_variable,
+        };
+        let delta = derivative_input - self.previous_derivative_input.unwrap_or(derivative_input);
+        self.previous_derivative_input = Some(derivative_input);
+        // derivative kd s / (tf s + 1), tf = 0 without filter
+        let filter_time = match self.options.derivative_filter {
+            Some(n) if n > 0.0 && self.kp > 0.0 => self.kd / self.kp / n,
+            _ => 0.0,
+        };
+        self.derivative = match self.options.discretization {
+            Discretization::ForwardEuler if filter_time > 0.0 => {
+                (1.0 - self.dt / filter_time) * self.derivative + self.kd * delta / filter_time
+            }
+            Discretization::Tustin if filter_time > 0.0 => {
+                ((2.0 * filter_time - self.dt) * self.derivative + 2.0 * self.kd * delta)
+                    / (2.0 * filter_time + self.dt)
+            }
+            _ => (filter_time * self.derivative + self.kd * delta) / (filter_time + self.dt),
+        };
+        self.derivative = self.round(self.derivative);
+
+        let output = self.round(proportional + self.integral + self.derivative);
+        match self.output_limit {
+            Some((minimum, maximum)) => {
+                let limited = output.max(minimum).min(maximum);
+                match self.options.anti_windup {
+                    AntiWindup::None => {}
+                    AntiWindup::Clamping => {
+                        if (output > maximum && error > 0.0) || (output < minimum && error < 0.0) {
+                            self.integral = self.round(self.integral - integral_increment);
+                        }
+                    }
+                    AntiWindup::BackCalculation { tracking_time } => {
+                        if tracking_time > 0.0 {
+                            self.integral = self.round(self.integral + (limited - output) * self.dt / tracking_time);
+                        }
+                    }
+                }
+                limited
+            }
+            None => output,
+        }
+    }
+
+    fn integral(&self) -> Option<f64> {
+        Some(self.integral)
+    }
+
+    /// Initializes the integral so that the PID would return `output` right now
+    fn track(&mut self, setpoint: f64, process_variable: f64, output: f64) {
+        let proportional = self.kp * (self.options.setpoint_weight_p * setpoint - process_variable);
+        self.integral = self.round(output - proportional);
+        self.derivative = 0.0;
+        self.previous_error = Some(setpoint - process_variable);
+        self.previous_derivative_input = Some(match self.options.derivative {
+            DerivativeMode::OnError => self.options.setpoint_weight_d * setpoint - process_variable,
+            DerivativeMode::OnMeasurement => -process_variable,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cb_controller::pid::{PidCoreBuilder, PidOutputLimit};
+
+    fn law(kp: f64, ki: f64, options: PidOptions) -> PidLaw {
+        let setup = PidSetup::default()
+            .set_core(PidCoreBuilder::default().kp(kp).ki(ki).kd(0.0))
+            .set_options(options)
+            .set_sampling_interval(10.0);
+        PidLaw::new(&setup)
+    }
+
+    /// Outputs for a constant setpoint of one and a decaying process variable
+    fn response(mut pid: PidLaw) -> Vec<f64> {
+        (0..20).map(|i| pid.update(1.0, 1.0 - 0.9_f64.powi(i))).collect()
+    }
+
+    #[test]
+    fn default_options_match_the_cb_controller_pid() {
+        let core = PidCoreBuilder::default().kp(2.0).ki(0.01).kd(5.0);
+        let limited = PidSetup::default()
+            .set_core(core.clone())
+            .set_output_limit(Some(PidOutputLimit::default().range(-1.5, 1.5)))
+            .set_dead_band(Some(0.05));
+        let clamped = limited.clone().set_options(PidOptions::default().set_anti_windup(AntiWindup::Clamping));
+        for setup in [PidSetup::default().set_core(core), limited, clamped] {
+            let setup = setup.set_sampling_interval(10.0);
+            assert!(setup.options.is_standard());
+            let mut firmware = setup.build();
+            let mut law = PidLaw::new(&setup);
+            for i in 0..50 {
+                let (setpoint, process_variable) = (if i < 25 { 1.0 } else { -1.0 }, 1.0 - 0.9_f64.powi(i));
+                let expected = firmware.update(setpoint, process_variable);
+                let actual = law.update(setpoint, process_variable);
+                assert!((expected - actual).abs() < 1e-9, "step {}: {} != {}", i, expected, actual);
+            }
+        }
+    }
+
+    #[test]
+    fn full_scale_and_clamping_keep_the_options_standard() {
+        assert!(PidOptions::default().set_full_scale(1.0).is_standard());
+        assert!(PidOptions::default().set_anti_windup(AntiWindup::Clamping).is_standard());
+        assert!(!PidOptions::default().set_discretization(Discretization::Tustin).is_standard());
+        assert!(!PidOptions::default().set_precision(Precision::Q15).is_standard());
+    }
+
+    #[test]
+    fn discretizations_agree_for_a_proportional_controller() {
+        let responses: Vec<_> = Discretization::ALL
+            .iter()
+            .map(|d| response(law(2.0, 0.0, PidOptions::default().set_discretization(*d))))
+            .collect();
+        assert_eq!(responses[0], responses[1]);
+        assert_eq!(responses[1], responses[2]);
+    }
+
+    #[test]
+    fn discretizations_differ_in_the_integral() {
+        let first = |d: Discretization| law(0.0, 0.01, PidOptions::default().set_discretization(d)).update(1.0, 0.0);
+        assert_eq!(first(Discretization::ForwardEuler), 0.0);
+        assert_eq!(first(Discretization::BackwardEuler), 0.1);
+        assert_eq!(first(Discretization::Tustin), 0.1);
+        let mut forward = law(0.0, 0.01, PidOptions::default().set_discretization(Discretization::ForwardEuler));
+        forward.update(1.0, 0.0);
+        assert_eq!(forward.update(1.0, 0.0), 0.1);
+    }
+
+    #[test]
+    fn unfiltered_derivative_decays_for_every_discretization() {
+        for discretization in Discretization::ALL {
+            let setup = PidSetup::default()
+                .set_core(PidCoreBuilder::default().kp(1.0).ki(0.0).kd(10.0))
+                .set_options(PidOptions::default().set_discretization(discretization))
+                .set_sampling_interval(10.0);
+            let mut pid = PidLaw::new(&setup);
+            pid.update(0.0, 0.0);
+            // the step of the measurement kicks the derivative once
+            assert_eq!(pid.update(0.0, 1.0), -1.0 - 1.0);
+            for _ in 0..10 {
+                assert_eq!(pid.update(0.0, 1.0), -1.0, "{}", discretization);
+            }
+        }
+    }
+
+    #[test]
+    fn full_scale_only_matters_for_fixed_point() {
+        let with_full_scale = |precision: Precision, full_scale: f64| {
+            response(law(2.0, 0.01, PidOptions::default().set_precision(precision).set_full_scale(full_scale)))
+        };
+        for precision in [Precision::F64, Precision::F32] {
+            assert_eq!(with_full_scale(precision, 10.0), with_full_scale(precision, 1.0));
+        }
+        // the initial output 2 saturates just below the full scale of 1
+        let saturated = with_full_scale(Precision::Q15, 1.0);
+        assert!(saturated[0] < 1.0 && saturated[0] > 0.99);
+        assert_ne!(with_full_scale(Precision::Q15, 10.0), saturated);
+    }
+
+    /// PI limited to ±1, saturated by a setpoint of 5 for 20 samples, then at rest at zero error;
+    /// returns the integral at the end of the saturation and the outputs at rest
+    fn windup(anti_windup: AntiWindup) -> (f64, Vec<f64>) {
+        let setup = PidSetup::default()
+            .set_core(PidCoreBuilder::default().kp(1.0).ki(0.01).kd(0.0))
+            .set_output_limit(Some(PidOutputLimit::default().range(-1.0, 1.0)))
+            .set_options(PidOptions::default().set_anti_windup(anti_windup))
+            .set_sampling_interval(10.0);
+        let mut pid = PidLaw::new(&setup);
+        for _ in 0..20 {
+            assert_eq!(pid.update(5.0, 0.0), 1.0);
+        }
+        let integral = pid.integral().unwrap();
+        (integral, (0..5).map(|_| pid.update(0.0, 0.0)).collect())
+    }
+
+    #[test]
+    fn integral_winds_up_without_anti_windup() {
+        let (integral, at_rest) = windup(AntiWindup::None);
+        // 20 samples of 0.01 * 5 * 10
+        assert!((integral - 10.0).abs() < 1e-9);
+        assert_eq!(at_rest, vec![1.0; 5]);
+    }
+
+    #[test]
+    fn clamping_freezes_the_integral_in_saturation() {
+        let (integral, at_rest) = windup(AntiWindup::Clamping);
+        assert_eq!(integral, 0.0);
+        assert_eq!(at_rest, vec![0.0; 5]);
+    }
+
+    #[test]
+    fn back_calculation_bounds_the_integral() {
+        // approaches the equilibrium of ki e dt = (output - limit) dt / tracking time, 0.5 = (4 + I) / 10
+        let (integral, at_rest) = windup(AntiWindup::BackCalculation { tracking_time: 100.0 });
+        assert!(integral > 0.0 && integral < 1.0, "{}", integral);
+        assert!(at_rest[0] < 1.0);
+        // without excess the integral is kept
+        assert!(at_rest.windows(2).all(|pair| (pair[0] - pair[1]).abs() < 1e-12));
+    }
+}
@@ -0,0 +1,183 @@
+use cb_controller::pid::{PidController, PidCoreBuilder, PidOutputLimit, PidSetpointRange};
+use log::info;
+
+use crate::control::gain_schedule::{GainScheduleSetup, GainScheduledPid};
+use crate::control::mpc::{Mpc, MpcSetup};
+use crate::control::pid_law::{AntiWindup, PidLaw, PidOptions};
+use crate::control::smith_predictor::{SmithPredictor, SmithPredictorSetup};
+use crate::control::state_feedback::{StateFeedback, StateFeedbackSetup};
+use crate::control::LoopController;
+
+/// Complete PID configuration as entered in the controller accordion.
+///
+/// The controller carries state (integral, previous error), therefore every
+/// simulation run builds a fresh controller from the setup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PidSetup {
+    pub core: PidCoreBuilder<f64>,
+    pub output_limit: Option<PidOutputLimit<f64>>,
+    pub setpoint_range: Option<PidSetpointRange<f64>>,
+    pub dead_band: Option<f64>,
+    /// Setpoint weighting, derivative, anti-windup and number format options
+    pub options: PidOptions,
+    /// Wraps the PID into a Smith predictor for dead-time processes
+    pub smith_predictor: Option<SmithPredictorSetup>,
+    /// Replaces the gains of the core by a table over the operating range
+    pub gain_schedule: Option<GainScheduleSetup>,
+    /// Replaces the PID by state feedback with observer
+    pub state_feedback: Option<StateFeedbackSetup>,
+    /// Replaces the PID by a model predictive controller, takes precedence over state feedback
+    pub mpc: Option<MpcSetup>,
+    /// Plant steps per controller sample, the models of Smith predictor and MPC are
+    /// discretized at the plant sampling interval and stepped as often per sample
+    pub model_steps: usize,
+}
+
+impl PidSetup {
+    pub fn set_core(self, core: PidCoreBuilder<f64>) -> Self {
+        PidSetup { core, ..self }
+    }
+
+    pub fn set_output_limit(self, output_limit: Option<PidOutputLimit<f64>>) -> Self {
+        PidSetup { output_limit, ..self }
+    }
+
+    pub fn set_setpoint_range(self, setpoint_range: Option<PidSetpointRange<f64>>) -> Self {
+        PidSetup { setpoint_range, ..self }
+    }
+
+    pub fn set_dead_band(self, dead_band: Option<f64>) -> Self {
+        PidSetup { dead_band, ..self }
+    }
+
+    pub fn set_options(self, options: PidOptions) -> Self {
+        PidSetup { options, ..self }
+    }
+
+    pub fn set_smith_predictor(self, smith_predictor: Option<SmithPredictorSetup>) -> Self {
+        PidSetup { smith_predictor, ..self }
+    }
+
+    pub fn set_gain_schedule(self, gain_schedule: Option<GainScheduleSetup>) -> Self {
+        PidSetup { gain_schedule, ..self }
+    }
+
+    pub fn set_state_feedback(self, state_feedback: Option<StateFeedbackSetup>) -> Self {
+        PidSetup { state_feedback, ..self }
+    }
+
+    pub fn set_mpc(self, mpc: Option<MpcSetup>) -> Self {
+        PidSetup { mpc, ..self }
+    }
+
+    pub fn set_sampling_interval(self, sampling_interval: f64) -> Self {
+        let mut core = self.core;
+        core.dt = sampling_interval as f32;
+        PidSetup { core, ..self }
+    }
+
+    pub fn set_model_steps(self, model_steps: usize) -> Self {
+        PidSetup { model_steps, ..self }
+    }
+
+    pub fn sampling_interval(&self) -> f64 {
+        self.core.dt as f64
+    }
+
+    pub fn build(&self) -> PidController<f64> {
+        let mut pid = self.core.clone().build();
+        match self.output_limit.clone() {
+            // the anti-windup strategy is part of the options
+            Some(c) => pid.set_output_limit(c.anti_windup(self.options.anti_windup == AntiWindup::Clamping)),
+            None => pid.reset_output_limit(),
+        }
+        match self.setpoint_range.clone() {
+            Some(c) => pid.set_setpoint_range(c),
+            None => pid.reset_setpoint_range(),
+        }
+        match self.dead_band {
+            Some(c) => pid.set_dead_band_tolerance(c),
+            None => pid.reset_dead_band_tolerance(),
+        }
+        pid
+    }
+
+    /// Builds the controller structure including the optional wrappers around the PID
+    ///
+    /// The `cb_controller` PID is used unless options require the simulator's own PID law.
+    pub fn controller(&self) -> Box<dyn LoopController> {
+        match self.replacement_controller() {
+            Some(replacement) => replacement,
+            None if self.options.is_standard() && self.gain_schedule.is_none() => self.wrap(Box::new(self.build())),
+            None => self.law_controller(),
+        }
+    }
+
+    /// Like [`PidSetup::controller`], but always with the simulator's PID law,
+    /// which follows an output set from outside as `cb_controller` cannot
+    pub fn tracking_controller(&self) -> Box<dyn LoopController> {
+        self.replacement_controller().unwrap_or_else(|| self.law_controller())
+    }
+
+    fn law_controller(&self) -> Box<dyn LoopController> {
+        let law = PidLaw::new(self);
+        match &self.gain_schedule {
+            Some(schedule) => self.wrap(Box::new(GainScheduledPid::new(law, schedule))),
+            None => self.wrap(Box::new(law)),
+        }
+    }
+
+    /// MPC or state feedback if configured, a failing design falls back to the PID
+    fn replacement_controller(&self) -> Option<Box<dyn LoopController>> {
+        self.mpc_controller().or_else(|| self.state_feedback_controller())
+    }
+
+    fn mpc_controller(&self) -> Option<Box<dyn LoopController>> {
+        let setup = self.mpc.as_ref()?;
+        match Mpc::new(setup, self.model_steps) {
+            Ok(mpc) => Some(Box::new(mpc)),
+            Err(error) => {
+                info!("MPC setup failed, using the PID: {}", error);
+                None
+            }
+        }
+    }
+
+    fn state_feedback_controller(&self) -> Option<Box<dyn LoopController>> {
+        let setup = self.state_feedback.as_ref()?;
+        match setup.design(self.sampling_interval()) {
+            Ok(gains) => {
+                let output_limit = self.output_limit.as_ref().map(|l| (l.minimum(), l.maximum()));
+                Some(self.wrap(Box::new(StateFeedback::new(gains, output_limit))))
+            }
+            Err(error) => {
+                info!("State feedback design failed, using the PID: {}", error);
+                None
+            }
+        }
+    }
+
+    fn wrap(&self, pid: Box<dyn LoopController>) -> Box<dyn LoopController> {
+        match &self.smith_predictor {
+            Some(smith) => Box::new(SmithPredictor::new(pid, smith, self.sampling_interval(), self.model_steps)),
+            None => pid,
+        }
+    }
+}
+
+impl Default for PidSetup {
+    fn default() -> Self {
+        PidSetup {
+            core: PidCoreBuilder::<f64>::default(),
+            output_limit: None,
+            setpoint_range: None,
+            dead_band: None,
+            options: PidOptions::default(),
+            smith_predictor: None,
+            gain_schedule: None,
+            state_feedback: None,
+            mpc: None,
+            model_steps: 1,
+        }
+    }
+}
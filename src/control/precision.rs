@@ -0,0 +1,71 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Number format the controller is computed in on the target
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Precision {
+    /// Reference, as simulated by default
+    #[default]
+    F64,
+    F32,
+    /// 16 bit signed fraction of the full scale, saturating
+    Q15,
+    /// 32 bit signed fraction of the full scale, saturating
+    Q31,
+}
+
+impl Precision {
+    pub const ALL: [Precision; 4] = [Precision::F64, Precision::F32, Precision::Q15, Precision::Q31];
+
+    fn fractional_bits(&self) -> Option<i32> {
+        match self {
+            Precision::Q15 => Some(15),
+            Precision::Q31 => Some(31),
+            _ => None,
+        }
+    }
+
+    /// Rounds a signal or state to the format.
+    ///
+    /// Fixed-point values are fractions of `full_scale` and saturate at `±full_scale`,
+    /// which makes overflow visible instead of wrapping around.
+    pub fn signal(&self, value: f64, full_scale: f64) -> f64 {
+        match (self, self.fractional_bits()) {
+            (Precision::F32, _) => value as f32 as f64,
+            (_, Some(bits)) if full_scale > 0.0 => {
+                let one = 2.0_f64.powi(bits);
+                let raw = (value / full_scale * one).round().clamp(-one, one - 1.0);
+                raw / one * full_scale
+            }
+            _ => value,
+        }
+    }
+
+    /// Rounds a coefficient like a gain to the format.
+    ///
+    /// Fixed-point coefficients keep their own binary exponent, as firmware multiplies
+    /// with a normalized coefficient and shifts the product.
+    pub fn coefficient(&self, value: f64) -> f64 {
+        match (self, self.fractional_bits()) {
+            (Precision::F32, _) => value as f32 as f64,
+            (_, Some(bits)) if value != 0.0 && value.is_finite() => {
+                let exponent = value.abs().log2().ceil() as i32;
+                let resolution = 2.0_f64.powi(exponent - bits);
+                (value / resolution).round() * resolution
+            }
+            _ => value,
+        }
+    }
+}
+
+impl fmt::Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Precision::F64 => write!(f, "f64"),
+            Precision::F32 => write!(f, "f32"),
+            Precision::Q15 => write!(f, "Q15"),
+            Precision::Q31 => write!(f, "Q31"),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use ndarray::{Array1, Array2};
+
+use crate::control::state_space::inverse;
+
+/// Quadratic program `min ½ xᵀ H x + fᵀ x` subject to `M x ≤ γ`
+/// with fixed Hessian and constraint matrix, solved by Hildreth's dual coordinate ascent
+/// and finished by an exact solve on its active set.
+///
+/// Small and dependency free, good enough for the few decision variables of a control horizon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuadraticProgram {
+    hessian_inverse: Array2<f64>,
+    constraints: Array2<f64>,
+    /// `M H⁻¹ Mᵀ`
+    dual: Array2<f64>,
+    iterations: usize,
+}
+
+impl QuadraticProgram {
+    /// Returns `None` if the Hessian is singular
+    pub fn new(hessian: &Array2<f64>, constraints: Array2<f64>) -> Option<Self> {
+        let hessian_inverse = inverse(hessian)?;
+        let dual = constraints.dot(&hessian_inverse).dot(&constraints.t());
+        Some(QuadraticProgram { hessian_inverse, constraints, dual, iterations: 200 })
+    }
+
+    /// Optimum for the linear term `f` and the constraint bounds `γ`, refined on the active set
+    /// found by the dual iteration; the last iterate if that set does not lead to the optimum
+    pub fn solve(&self, gradient: &Array1<f64>, bounds: &Array1<f64>) -> Array1<f64> {
+        let unconstrained = -self.hessian_inverse.dot(gradient);
+        if self.constraints.nrows() == 0 {
+            return unconstrained;
+        }
+        let slack = bounds - &self.constraints.dot(&unconstrained);
+        if slack.iter().all(|s| *s >= 0.0) {
+            return unconstrained;
+        }
+
+        let mut multipliers = Array1::<f64>::zeros(slack.len());
+        for _ in 0..self.iterations {
+            let mut change = 0.0;
+            for i in 0..multipliers.len() {
+                let coupling = self.dual.row(i).dot(&multipliers) - self.dual[[i, i]] * multipliers[i];
+                let next = (-(slack[i] + coupling) / self.dual[[i, i]]).max(0.0);
+                change += (next - multipliers[i]).powi(2);
+                multipliers[i] = next;
+            }
+            if change < 1e-20 {
+                break;
+            }
+        }
+        self.solve_active(&unconstrained, &slack, &multipliers, bounds)
+            .unwrap_or_else(|| &unconstrained - &self.hessian_inverse.dot(&self.constraints.t().dot(&multipliers)))
+    }
+
+    /// Optimum with the constraints of the active set held as equalities, starting from the
+    /// constraints with positive multiplier; `None` if the set does not settle or is degenerate.
+    ///
+    /// Hildreth's iteration converges slowly for strongly coupled constraints, e.g. the rate limits
+    /// of consecutive moves, its multipliers are only a good first guess of the active set.
+    fn solve_active(
+        &self,
+        unconstrained: &Array1<f64>,
+        slack: &Array1<f64>,
+        multipliers: &Array1<f64>,
+        bounds: &Array1<f64>,
+    ) -> Option<Array1<f64>> {
+        let tolerance = 1e-9 * bounds.iter().fold(1.0_f64, |m, b| m.max(b.abs()));
+        let mut active: Vec<usize> = (0..multipliers.len()).filter(|i| multipliers[*i] > 0.0).collect();
+        for _ in 0..2 * multipliers.len() {
+            // M_A x = γ_A with x = x_u - H⁻¹ M_Aᵀ λ_A
+            let dual = Array2::from_shape_fn((active.len(), active.len()), |(i, j)| self.dual[[active[i], active[j]]]);
+            let active_multipliers = -inverse(&dual)?.dot(&Array1::from_iter(active.iter().map(|i| slack[*i])));
+            if let Some(dropped) = (0..active.len())
+                .filter(|i| active_multipliers[*i] < 0.0)
+                .min_by(|i, j| active_multipliers[*i].total_cmp(&active_multipliers[*j]))
+            {
+                active.remove(dropped);
+                continue;
+            }
+            let mut all = Array1::<f64>::zeros(multipliers.len());
+            for (i, m) in active.iter().zip(&active_multipliers) {
+                all[*i] = *m;
+            }
+            let x = unconstrained - &self.hessian_inverse.dot(&self.constraints.t().dot(&all));
+            let violation = &self.constraints.dot(&x) - bounds;
+            match (0..violation.len())
+                .filter(|i| violation[*i] > tolerance && !active.contains(i))
+                .max_by(|i, j| violation[*i].total_cmp(&violation[*j]))
+            {
+                Some(violated) => active.push(violated),
+                None => return Some(x),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &Array1<f64>, expected: &[f64]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn without_constraints_the_optimum_is_unconstrained() {
+        // ½ (x1² + x2²) - 2 x1 - 2 x2 is least at (2, 2)
+        let problem = QuadraticProgram::new(&Array2::eye(2), Array2::zeros((0, 2))).unwrap();
+        assert_close(&problem.solve(&Array1::from_vec(vec![-2.0, -2.0]), &Array1::zeros(0)), &[2.0, 2.0]);
+    }
+
+    #[test]
+    fn inactive_constraints_keep_the_unconstrained_optimum() {
+        let hessian = Array2::from_shape_vec((2, 2), vec![2.0, 1.0, 1.0, 2.0]).unwrap();
+        let constraints = Array2::from_shape_vec((2, 2), vec![1.0, 0.0, 0.0, 1.0]).unwrap();
+        let problem = QuadraticProgram::new(&hessian, constraints).unwrap();
+        // H x = -f at (1, -1)
+        let gradient = Array1::from_vec(vec![-1.0, 1.0]);
+        assert_close(&problem.solve(&gradient, &Array1::from_vec(vec![5.0, 5.0])), &[1.0, -1.0]);
+    }
+
+    #[test]
+    fn one_active_constraint_projects_onto_it() {
+        // x1 + x2 ≤ 1 cuts off (2, 2), the optimum lies on the line
+        let constraints = Array2::from_shape_vec((2, 2), vec![1.0, 1.0, 0.0, 1.0]).unwrap();
+        let problem = QuadraticProgram::new(&Array2::eye(2), constraints).unwrap();
+        let x = problem.solve(&Array1::from_vec(vec![-2.0, -2.0]), &Array1::from_vec(vec![1.0, 10.0]));
+        assert_close(&x, &[0.5, 0.5]);
+    }
+
+    #[test]
+    fn two_active_constraints_meet_in_a_vertex() {
+        // x1 ≤ 0.2 and x1 + x2 ≤ 1 with multipliers 0.6 and 1.2
+        let constraints = Array2::from_shape_vec((2, 2), vec![1.0, 0.0, 1.0, 1.0]).unwrap();
+        let problem = QuadraticProgram::new(&Array2::eye(2), constraints).unwrap();
+        let x = problem.solve(&Array1::from_vec(vec![-2.0, -2.0]), &Array1::from_vec(vec![0.2, 1.0]));
+        assert_close(&x, &[0.2, 0.8]);
+    }
+
+    #[test]
+    fn singular_hessian_has_no_problem() {
+        let hessian = Array2::from_shape_vec((2, 2), vec![1.0, 1.0, 1.0, 1.0]).unwrap();
+        assert_eq!(QuadraticProgram::new(&hessian, Array2::zeros((0, 2))), None);
+    }
+}
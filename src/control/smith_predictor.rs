@@ -0,0 +1,174 @@
+use cb_simulation_util::plant::BoxedTransferTimeDomain;
+
+use crate::control::dead_time::DeadTime;
+use crate::control::{transfer_held, LoopController};
+use crate::plant::named_element::NamedElement;
+
+/// Internal model of a Smith predictor, the model may deliberately differ from the simulated plant
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmithPredictorSetup {
+    pub model: NamedElement<f64>,
+    /// Dead time of the model [ms]
+    pub dead_time: f64,
+}
+
+impl SmithPredictorSetup {
+    pub fn set_model(self, model: NamedElement<f64>) -> Self {
+        SmithPredictorSetup { model, ..self }
+    }
+
+    pub fn set_dead_time(self, dead_time: f64) -> Self {
+        SmithPredictorSetup { dead_time, ..self }
+    }
+}
+
+impl Default for SmithPredictorSetup {
+    fn default() -> Self {
+        SmithPredictorSetup {
+            model: NamedElement::<f64>::default(),
+            dead_time: 0.0,
+        }
+    }
+}
+
+/// PID controller that gets fed back the process variable corrected by the model prediction.
+///
+/// The controller sees `y + y_model - y_model_delayed`, which removes the dead time
+/// from the loop as long as the model matches the plant.
+#[derive(Debug)]
+pub struct SmithPredictor {
+    pid: Box<dyn LoopController>,
+    model: BoxedTransferTimeDomain<f64>,
+    /// Plant steps of the model per controller sample
+    model_steps: usize,
+    model_dead_time: DeadTime,
+    model_output: f64,
+    delayed_model_output: f64,
+}
+
+impl SmithPredictor {
+    /// The model is discretized at the plant sampling interval and stepped `model_steps` times
+    /// per controller sample of `sampling_interval`
+    pub fn new(
+        pid: Box<dyn LoopController>,
+        setup: &SmithPredictorSetup,
+        sampling_interval: f64,
+        model_steps: usize,
+    ) -> Self {
+        SmithPredictor {
+            pid,
+            model: setup.model.element.clone(),
+            model_steps: model_steps.max(1),
+            model_dead_time: DeadTime::new(setup.dead_time, sampling_interval),
+            model_output: 0.0,
+            delayed_model_output: 0.0,
+        }
+    }
+}
+
+impl LoopController for SmithPredictor {
+    fn update(&mut self, setpoint: f64, process_variable: f64) -> f64 {
+        let feedback = process_variable + self.model_output - self.delayed_model_output;
+        let control_variable = self.pid.update(setpoint, feedback);
+        self.model_output = transfer_held(&mut self.model, control_variable, self.model_steps);
+        self.delayed_model_output = self.model_dead_time.update(self.model_output);
+        control_variable
+    }
+
+    fn integral(&self) -> Option<f64> {
+        self.pid.integral()
+    }
+
+    fn track(&mut self, setpoint: f64, process_variable: f64, output: f64) {
+        let feedback = process_variable + self.model_output - self.delayed_model_output;
+        self.pid.track(setpoint, feedback, output);
+        self.model_output = transfer_held(&mut self.model, output, self.model_steps);
+        self.delayed_model_output = self.model_dead_time.update(self.model_output);
+    }
+
+    fn schedule(&mut self, scheduling_variable: f64) {
+        self.pid.schedule(scheduling_variable);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::pid_law::PidLaw;
+    use crate::control::pid_setup::PidSetup;
+    use cb_controller::pid::PidCoreBuilder;
+    use cb_simulation_util::plant::pt1::PT1;
+
+    const SAMPLING_INTERVAL: f64 = 1.0;
+    const DEAD_TIME: f64 = 10.0;
+
+    fn pt1(kp: f64) -> NamedElement<f64> {
+        let pt1 = PT1::<f64>::default()
+            .set_sample_time_or_default(SAMPLING_INTERVAL)
+            .set_t1_time_or_default(20.0)
+            .set_kp(kp);
+        NamedElement::default().set_element(Box::new(pt1))
+    }
+
+    fn pi() -> Box<dyn LoopController> {
+        let setup = PidSetup::default()
+            .set_core(PidCoreBuilder::default().kp(1.5).ki(0.075).kd(0.0))
+            .set_sampling_interval(SAMPLING_INTERVAL);
+        Box::new(PidLaw::new(&setup))
+    }
+
+    fn smith(model_kp: f64, model_dead_time: f64) -> Box<dyn LoopController> {
+        let setup = SmithPredictorSetup::default().set_model(pt1(model_kp)).set_dead_time(model_dead_time);
+        Box::new(SmithPredictor::new(pi(), &setup, SAMPLING_INTERVAL, 1))
+    }
+
+    /// Unit setpoint onto a PT1 with gain one and the dead time, returns the control variables
+    /// and the process variables
+    fn simulate(mut controller: Box<dyn LoopController>, dead_time: f64) -> (Vec<f64>, Vec<f64>) {
+        let mut plant = pt1(1.0).element;
+        let mut delay = DeadTime::new(dead_time, SAMPLING_INTERVAL);
+        let mut y = 0.0;
+        (0..1000)
+            .map(|_| {
+                let u = controller.update(1.0, y);
+                y = delay.update(plant.transfer_td(u));
+                (u, y)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn matching_model_removes_the_dead_time_from_the_loop() {
+        let (u_smith, y_smith) = simulate(smith(1.0, DEAD_TIME), DEAD_TIME);
+        let (u_plain, y_plain) = simulate(pi(), 0.0);
+        for i in 0..u_plain.len() {
+            assert!((u_smith[i] - u_plain[i]).abs() < 1e-9, "step {}", i);
+        }
+        // the response is the one without dead time, only delayed
+        let delay = (DEAD_TIME / SAMPLING_INTERVAL) as usize;
+        for i in 0..y_plain.len() - delay {
+            assert!((y_smith[i + delay] - y_plain[i]).abs() < 1e-9, "step {}", i);
+        }
+    }
+
+    #[test]
+    fn model_mismatch_still_settles_at_the_setpoint() {
+        let (u_perfect, _) = simulate(smith(1.0, DEAD_TIME), DEAD_TIME);
+        for (model_kp, model_dead_time) in [(1.5, DEAD_TIME), (0.7, DEAD_TIME), (1.0, DEAD_TIME - 3.0), (1.0, DEAD_TIME + 3.0)] {
+            let (u, y) = simulate(smith(model_kp, model_dead_time), DEAD_TIME);
+            let mismatch = format!("model gain {} dead time {}", model_kp, model_dead_time);
+            // the correction y_model - y_model_delayed vanishes in steady state, the integral removes the offset
+            assert!((y[999] - 1.0).abs() < 1e-3, "{}: {}", mismatch, y[999]);
+            // but the transient differs from the one of the matching model
+            assert!(u.iter().zip(&u_perfect).any(|(a, b)| (a - b).abs() > 1e-3), "{}", mismatch);
+        }
+    }
+
+    #[test]
+    fn without_smith_predictor_the_dead_time_causes_overshoot() {
+        let overshoot = |y: &[f64]| y.iter().fold(0.0_f64, |m, v| m.max(*v)) - 1.0;
+        let (_, y_plain) = simulate(pi(), DEAD_TIME);
+        let (_, y_smith) = simulate(smith(1.0, DEAD_TIME), DEAD_TIME);
+        assert!(overshoot(&y_plain) > overshoot(&y_smith) + 0.2, "{} {}", overshoot(&y_plain), overshoot(&y_smith));
+    }
+}
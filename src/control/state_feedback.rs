@@ -0,0 +1,448 @@
+use std::fmt;
+
+use ndarray::{Array1, Array2};
+use serde::{Deserialize, Serialize};
+
+use crate::control::pid_design::PlantModel;
+use crate::control::state_space::{inverse, StateSpaceModel};
+use crate::control::LoopController;
+use crate::plant::named_element::NamedElement;
+
+/// How the state-feedback gain K is computed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GainDesign {
+    /// Dominant pole pair `s² + 2 damping omega s + omega²`, a first order model gets the pole `-omega`
+    PolePlacement { omega: f64, damping: f64 },
+    /// Discrete LQR, the derivative weight is ignored for first order models
+    Lqr { output_weight: f64, derivative_weight: f64, input_weight: f64 },
+}
+
+/// How the observer gain L is computed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ObserverDesign {
+    /// Luenberger observer with poles `speed_factor` times faster than the state feedback
+    Luenberger { speed_factor: f64 },
+    /// Steady-state Kalman filter for the noise variances
+    Kalman { process_noise: f64, measurement_noise: f64 },
+}
+
+/// State feedback with observer, designed for a PT1 or PT2 model of the plant
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateFeedbackSetup {
+    pub model: NamedElement<f64>,
+    pub gain_design: GainDesign,
+    pub observer: ObserverDesign,
+}
+
+impl StateFeedbackSetup {
+    pub fn set_model(self, model: NamedElement<f64>) -> Self {
+        StateFeedbackSetup { model, ..self }
+    }
+
+    pub fn set_gain_design(self, gain_design: GainDesign) -> Self {
+        StateFeedbackSetup { gain_design, ..self }
+    }
+
+    pub fn set_observer(self, observer: ObserverDesign) -> Self {
+        StateFeedbackSetup { observer, ..self }
+    }
+
+    /// Discretizes the model and computes feedback, observer and prefilter gains
+    pub fn design(&self, sampling_interval: f64) -> Result<StateFeedbackGains, StateFeedbackError> {
+        let plant = PlantModel::from_element(&self.model).ok_or(StateFeedbackError::ModelNotSupported)?;
+        let model = StateSpaceModel::from_plant(&plant).discretize(sampling_interval);
+        let n = model.order();
+
+        let k = match self.gain_design {
+            GainDesign::PolePlacement { omega, damping } => {
+                let polynomial = desired_polynomial(n, omega, damping, sampling_interval);
+                ackermann(&model.a, &model.b, &polynomial).ok_or(StateFeedbackError::NotControllable)?
+            }
+            GainDesign::Lqr { output_weight, derivative_weight, input_weight } => {
+                let weights = [output_weight, derivative_weight];
+                let q = Array2::from_diag(&Array1::from_iter((0..n).map(|i| weights[i])));
+                riccati_gain(&model.a, &model.b, &q, input_weight).ok_or(StateFeedbackError::NotConverged)?
+            }
+        };
+
+        let a_transposed = model.a.t().to_owned();
+        let l = match self.observer {
+            ObserverDesign::Luenberger { speed_factor } => {
+                let (omega, damping) = match self.gain_design {
+                    GainDesign::PolePlacement { omega, damping } => (omega, damping),
+                    // the observer has to be faster than the dominant closed-loop pole
+                    GainDesign::Lqr { .. } => (dominant_frequency(&model, &k, sampling_interval), 1.0),
+                };
+                let polynomial = desired_polynomial(n, omega * speed_factor, damping, sampling_interval);
+                ackermann(&a_transposed, &model.c, &polynomial).ok_or(StateFeedbackError::NotObservable)?
+            }
+            ObserverDesign::Kalman { process_noise, measurement_noise } => {
+                let q = Array2::<f64>::eye(n) * process_noise;
+                // dual of the LQR, L = A P c / (c' P c + r)
+                riccati_gain(&a_transposed, &model.c, &q, measurement_noise)
+                    .ok_or(StateFeedbackError::NotConverged)?
+            }
+        };
+
+        // unit static gain from setpoint to output: N = 1 / (c (I - A + b k)^-1 b)
+        let mut closed_loop = Array2::<f64>::eye(n) - &model.a;
+        for i in 0..n {
+            for j in 0..n {
+                closed_loop[[i, j]] += model.b[i] * k[j];
+            }
+        }
+        let static_gain = model.c.dot(&inverse(&closed_loop).ok_or(StateFeedbackError::NotControllable)?.dot(&model.b));
+        if static_gain.abs() < 1e-15 {
+            return Err(StateFeedbackError::NotControllable);
+        }
+
+        Ok(StateFeedbackGains { model, k, l, prefilter: 1.0 / static_gain })
+    }
+}
+
+impl Default for StateFeedbackSetup {
+    fn default() -> Self {
+        StateFeedbackSetup {
+            model: NamedElement::<f64>::default(),
+            gain_design: GainDesign::PolePlacement { omega: 0.01, damping: 0.7 },
+            observer: ObserverDesign::Luenberger { speed_factor: 3.0 },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StateFeedbackError {
+    /// Only PT1 and PT2 elements have a state-space model
+    ModelNotSupported,
+    NotControllable,
+    NotObservable,
+    /// The Riccati iteration of LQR or Kalman filter did not converge
+    NotConverged,
+}
+
+impl fmt::Display for StateFeedbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateFeedbackError::ModelNotSupported => write!(f, "Select a PT1 or PT2 element as model"),
+            StateFeedbackError::NotControllable => write!(f, "Model is not controllable"),
+            StateFeedbackError::NotObservable => write!(f, "Model is not observable"),
+            StateFeedbackError::NotConverged => write!(f, "Riccati equation did not converge, check the weights"),
+        }
+    }
+}
+
+/// Discrete model and gains of `u = -k x + prefilter r`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateFeedbackGains {
+    pub model: StateSpaceModel,
+    pub k: Array1<f64>,
+    pub l: Array1<f64>,
+    pub prefilter: f64,
+}
+
+/// State feedback on the estimate of a predicting observer
+#[derive(Debug, Clone)]
+pub struct StateFeedback {
+    gains: StateFeedbackGains,
+    estimate: Array1<f64>,
+    /// minimum and maximum
+    output_limit: Option<(f64, f64)>,
+}
+
+impl StateFeedback {
+    pub fn new(gains: StateFeedbackGains, output_limit: Option<(f64, f64)>) -> Self {
+        let estimate = Array1::zeros(gains.model.order());
+        StateFeedback { gains, estimate, output_limit }
+    }
+
+    fn observe(&mut self, process_variable: f64, output: f64) {
+        let model = &self.gains.model;
+        let innovation = process_variable - model.c.dot(&self.estimate);
+        self.estimate = model.a.dot(&self.estimate) + &model.b * output + &self.gains.l * innovation;
+    }
+
+    pub fn estimate(&self) -> &Array1<f64> {
+        &self.estimate
+    }
+}
+
+impl LoopController for StateFeedback {
+    fn update(&mut self, setpoint: f64, process_variable: f64) -> f64 {
+        let output = -self.gains.k.dot(&self.estimate) + self.gains.prefilter * setpoint;
+        let output = match self.output_limit {
+            Some((minimum, maximum)) => output.max(minimum).min(maximum),
+            None => output,
+        };
+        self.observe(process_variable, output);
+        output
+    }
+
+    fn track(&mut self, _setpoint: f64, process_variable: f64, output: f64) {
+        self.observe(process_variable, output);
+    }
+}
+
+/// Monic discrete characteristic polynomial (ascending) for continuous poles mapped by `z = e^(s dt)`
+fn desired_polynomial(order: usize, omega: f64, damping: f64, sampling_interval: f64) -> Vec<f64> {
+    let pole = |s: f64| (s * sampling_interval).exp();
+    match order {
+        1 => vec![-pole(-omega), 1.0],
+        _ => {
+            let mut polynomial = if damping < 1.0 {
+                // z² - 2 e^(σ dt) cos(ωd dt) z + e^(2 σ dt)
+                let sigma = -damping * omega;
+                let omega_d = omega * (1.0 - damping * damping).sqrt();
+                let radius = pole(sigma);
+                vec![radius * radius, -2.0 * radius * (omega_d * sampling_interval).cos(), 1.0]
+            } else {
+                let root = (damping * damping - 1.0).sqrt();
+                let (z1, z2) = (pole(-omega * (damping - root)), pole(-omega * (damping + root)));
+                vec![z1 * z2, -(z1 + z2), 1.0]
+            };
+            // further poles at -omega
+            for _ in 2..order {
+                let z = pole(-omega);
+                let mut shifted = vec![0.0];
+                shifted.extend(polynomial.iter());
+                for (i, c) in polynomial.iter().enumerate() {
+                    shifted[i] -= z * c;
+                }
+                polynomial = shifted;
+            }
+            polynomial
+        }
+    }
+}
+
+/// Ackermann's formula `k = [0 .. 0 1] Wc^-1 φ(A)`, `None` if not controllable
+fn ackermann(a: &Array2<f64>, b: &Array1<f64>, polynomial: &[f64]) -> Option<Array1<f64>> {
+    let n = b.len();
+    let mut controllability = Array2::<f64>::zeros((n, n));
+    let mut column = b.clone();
+    for j in 0..n {
+        controllability.column_mut(j).assign(&column);
+        column = a.dot(&column);
+    }
+    let mut phi = Array2::<f64>::zeros((n, n));
+    let mut power = Array2::<f64>::eye(n);
+    for c in polynomial {
+        phi = phi + &power * *c;
+        power = power.dot(a);
+    }
+    let last_row = inverse(&controllability)?.row(n - 1).to_owned();
+    Some(last_row.dot(&phi))
+}
+
+/// Gain of the discrete algebraic Riccati equation by fixed-point iteration
+fn riccati_gain(a: &Array2<f64>, b: &Array1<f64>, q: &Array2<f64>, r: f64) -> Option<Array1<f64>> {
+    let mut p = q.clone();
+    for _ in 0..10000 {
+        let pb = p.dot(b);
+        let s = r + b.dot(&pb);
+        if s <= 0.0 {
+            return None;
+        }
+        let atpb = a.t().dot(&pb);
+        let mut next = q + &a.t().dot(&p).dot(a);
+        for i in 0..b.len() {
+            for j in 0..b.len() {
+                next[[i, j]] -= atpb[i] * atpb[j] / s;
+            }
+        }
+        let change = (&next - &p).iter().fold(0.0_f64, |m, v| m.max(v.abs()));
+        let scale = next.iter().fold(1.0_f64, |m, v| m.max(v.abs()));
+        p = next;
+        if change < 1e-12 * scale {
+            let pb = p.dot(b);
+            return Some(a.t().dot(&pb) / (r + b.dot(&pb)));
+        }
+    }
+    None
+}
+
+/// Slowest continuous closed-loop frequency of `A - b k`, estimated from the eigenvalue radius
+fn dominant_frequency(model: &StateSpaceModel, k: &Array1<f64>, sampling_interval: f64) -> f64 {
+    let n = model.order();
+    let mut closed_loop = model.a.clone();
+    for i in 0..n {
+        for j in 0..n {
+            closed_loop[[i, j]] -= model.b[i] * k[j];
+        }
+    }
+    // determinant = product of the eigenvalues, the geometric mean radius maps back by ln
+    let determinant = match n {
+        1 => closed_loop[[0, 0]],
+        _ => closed_loop[[0, 0]] * closed_loop[[1, 1]] - closed_loop[[0, 1]] * closed_loop[[1, 0]],
+    };
+    let radius = determinant.abs().powf(1.0 / n as f64).clamp(1e-12, 1.0 - 1e-12);
+    -radius.ln() / sampling_interval
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cb_simulation_util::plant::pt2::PT2;
+
+    const SAMPLING_INTERVAL: f64 = 1.0;
+
+    fn pt2_model() -> StateSpaceModel {
+        StateSpaceModel::from_plant(&PlantModel::Pt2 { kp: 2.0, omega: 0.05, damping: 0.3 }).discretize(SAMPLING_INTERVAL)
+    }
+
+    /// `A - b k'`, the error dynamics of an observer as `A' - c l'`
+    fn closed_loop(a: &Array2<f64>, b: &Array1<f64>, k: &Array1<f64>) -> Array2<f64> {
+        let mut closed_loop = a.clone();
+        for i in 0..b.len() {
+            for j in 0..b.len() {
+                closed_loop[[i, j]] -= b[i] * k[j];
+            }
+        }
+        closed_loop
+    }
+
+    /// Ascending coefficients of `z² - trace z + determinant`
+    fn characteristic_polynomial(m: &Array2<f64>) -> [f64; 3] {
+        let determinant = m[[0, 0]] * m[[1, 1]] - m[[0, 1]] * m[[1, 0]];
+        [determinant, -(m[[0, 0]] + m[[1, 1]]), 1.0]
+    }
+
+    /// Largest eigenvalue magnitude of a 2x2 matrix
+    fn spectral_radius(m: &Array2<f64>) -> f64 {
+        let [determinant, minus_trace, _] = characteristic_polynomial(m);
+        let discriminant = minus_trace * minus_trace / 4.0 - determinant;
+        if discriminant < 0.0 {
+            determinant.sqrt()
+        } else {
+            (-minus_trace / 2.0).abs() + discriminant.sqrt()
+        }
+    }
+
+    #[test]
+    fn ackermann_places_the_closed_loop_eigenvalues() {
+        let model = pt2_model();
+        let (omega, damping) = (0.1, 0.7);
+        let polynomial = desired_polynomial(2, omega, damping, SAMPLING_INTERVAL);
+        let k = ackermann(&model.a, &model.b, &polynomial).unwrap();
+        let placed = characteristic_polynomial(&closed_loop(&model.a, &model.b, &k));
+        for (placed, desired) in placed.iter().zip(&polynomial) {
+            assert!((placed - desired).abs() < 1e-9, "{:?} != {:?}", placed, polynomial);
+        }
+        // complex pair e^((-damping omega ± j omega_d) dt)
+        let radius = (-damping * omega * SAMPLING_INTERVAL).exp();
+        assert!((placed[0] - radius * radius).abs() < 1e-9);
+        assert!((spectral_radius(&closed_loop(&model.a, &model.b, &k)) - radius).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ackermann_places_the_pole_of_a_first_order_model() {
+        let model = StateSpaceModel::from_plant(&PlantModel::Pt1 { kp: 0.5, t1: 20.0 }).discretize(SAMPLING_INTERVAL);
+        let k = ackermann(&model.a, &model.b, &desired_polynomial(1, 0.2, 1.0, SAMPLING_INTERVAL)).unwrap();
+        let pole = model.a[[0, 0]] - model.b[0] * k[0];
+        assert!((pole - (-0.2 * SAMPLING_INTERVAL).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ackermann_rejects_an_uncontrollable_model() {
+        let a = Array2::from_shape_vec((2, 2), vec![0.9, 0.0, 0.0, 0.8]).unwrap();
+        let b = Array1::from_vec(vec![1.0, 0.0]);
+        assert_eq!(ackermann(&a, &b, &[0.25, -1.0, 1.0]), None);
+    }
+
+    #[test]
+    fn riccati_gain_of_a_scalar_integrator() {
+        // a = b = q = r = 1: P² - P - 1 = 0, P is the golden ratio and k = P / (1 + P)
+        let one = Array2::from_elem((1, 1), 1.0);
+        let k = riccati_gain(&one, &Array1::from_elem(1, 1.0), &one, 1.0).unwrap();
+        let p = (1.0 + 5.0_f64.sqrt()) / 2.0;
+        assert!((k[0] - p / (1.0 + p)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lqr_stabilizes_and_a_costly_input_lowers_the_gain() {
+        let model = pt2_model();
+        let q = Array2::from_diag(&Array1::from_vec(vec![1.0, 10.0]));
+        let cheap = riccati_gain(&model.a, &model.b, &q, 0.1).unwrap();
+        let costly = riccati_gain(&model.a, &model.b, &q, 10.0).unwrap();
+        assert!(spectral_radius(&closed_loop(&model.a, &model.b, &cheap)) < 1.0);
+        assert!(spectral_radius(&closed_loop(&model.a, &model.b, &costly)) < 1.0);
+        assert!(costly[0].abs() < cheap[0].abs());
+        // more feedback moves the poles towards the origin
+        assert!(
+            spectral_radius(&closed_loop(&model.a, &model.b, &cheap))
+                < spectral_radius(&closed_loop(&model.a, &model.b, &costly))
+        );
+    }
+
+    #[test]
+    fn kalman_gain_is_the_dual_of_the_lqr() {
+        // random walk observed with noise: the steady-state predictor gain is again P / (1 + P)
+        let one = Array2::from_elem((1, 1), 1.0);
+        let l = riccati_gain(&one.t().to_owned(), &Array1::from_elem(1, 1.0), &one, 1.0).unwrap();
+        let p = (1.0 + 5.0_f64.sqrt()) / 2.0;
+        assert!((l[0] - p / (1.0 + p)).abs() < 1e-9);
+
+        let model = pt2_model();
+        let l = riccati_gain(&model.a.t().to_owned(), &model.c, &Array2::<f64>::eye(2), 0.01).unwrap();
+        // error dynamics A - l c' share the eigenvalues with A' - c l'
+        assert!(spectral_radius(&closed_loop(&model.a.t().to_owned(), &model.c, &l)) < 1.0);
+        let more_noise = riccati_gain(&model.a.t().to_owned(), &model.c, &Array2::<f64>::eye(2), 100.0).unwrap();
+        assert!(more_noise[0].abs() < l[0].abs());
+    }
+
+    fn pt2_setup() -> StateFeedbackSetup {
+        let pt2 = PT2::<f64>::default()
+            .set_sample_time_or_default(SAMPLING_INTERVAL)
+            .set_t1_time_or_default(20.0)
+            .set_damping_or_default(0.3)
+            .set_kp(2.0);
+        StateFeedbackSetup::default()
+            .set_model(NamedElement::default().set_element(Box::new(pt2)))
+            .set_gain_design(GainDesign::PolePlacement { omega: 0.1, damping: 0.7 })
+    }
+
+    /// Closes the loop around the discrete design model starting in `initial`, returns the outputs
+    fn simulate(gains: &StateFeedbackGains, initial: Array1<f64>, setpoint: f64, steps: usize) -> Vec<f64> {
+        let model = gains.model.clone();
+        let mut controller = StateFeedback::new(gains.clone(), None);
+        let mut state = initial;
+        (0..steps)
+            .map(|_| {
+                let y = model.c.dot(&state);
+                let u = controller.update(setpoint, y);
+                state = model.a.dot(&state) + &model.b * u;
+                y
+            })
+            .collect()
+    }
+
+    #[test]
+    fn prefilter_gives_unit_static_gain() {
+        for observer in [
+            ObserverDesign::Luenberger { speed_factor: 3.0 },
+            ObserverDesign::Kalman { process_noise: 1.0, measurement_noise: 0.01 },
+        ] {
+            let gains = pt2_setup().set_observer(observer).design(SAMPLING_INTERVAL).unwrap();
+            let model = &gains.model;
+            let closed_loop = Array2::<f64>::eye(2) - closed_loop(&model.a, &model.b, &gains.k);
+            let static_gain = model.c.dot(&inverse(&closed_loop).unwrap().dot(&model.b)) * gains.prefilter;
+            assert!((static_gain - 1.0).abs() < 1e-9, "{:?}", observer);
+
+            let y = simulate(&gains, Array1::zeros(2), 1.0, 500);
+            assert!((y[499] - 1.0).abs() < 1e-6, "{:?}: {}", observer, y[499]);
+        }
+    }
+
+    #[test]
+    fn observer_converges_from_a_wrong_initial_estimate() {
+        let gains = pt2_setup().design(SAMPLING_INTERVAL).unwrap();
+        // the plant starts displaced, the observer at zero
+        let y = simulate(&gains, Array1::from_vec(vec![1.0, 0.0]), 0.0, 500);
+        assert!(y[499].abs() < 1e-6, "{}", y[499]);
+    }
+
+    #[test]
+    fn design_rejects_other_elements() {
+        let setup = StateFeedbackSetup::default();
+        assert_eq!(setup.design(SAMPLING_INTERVAL), Err(StateFeedbackError::ModelNotSupported));
+    }
+}
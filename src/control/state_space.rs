@@ -0,0 +1,134 @@
+use ndarray::{Array1, Array2};
+
+use crate::control::pid_design::PlantModel;
+
+/// Single-input single-output plant `x' = A x + B u`, `y = C x`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateSpaceModel {
+    pub a: Array2<f64>,
+    pub b: Array1<f64>,
+    pub c: Array1<f64>,
+}
+
+impl StateSpaceModel {
+    /// Controllable realization with the output as first state and its derivative as second
+    pub fn from_plant(plant: &PlantModel) -> Self {
+        match *plant {
+            PlantModel::Pt1 { kp, t1 } => StateSpaceModel {
+                a: Array2::from_elem((1, 1), -1.0 / t1),
+                b: Array1::from_elem(1, kp / t1),
+                c: Array1::from_elem(1, 1.0),
+            },
+            PlantModel::Pt2 { kp, omega, damping } => StateSpaceModel {
+                a: Array2::from_shape_vec((2, 2), vec![0.0, 1.0, -omega * omega, -2.0 * damping * omega])
+                    .expect("2x2 matrix"),
+                b: Array1::from_vec(vec![0.0, kp * omega * omega]),
+                c: Array1::from_vec(vec![1.0, 0.0]),
+            },
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.b.len()
+    }
+
+    /// Zero-order hold discretization, the input is held for the sampling interval
+    pub fn discretize(&self, sampling_interval: f64) -> StateSpaceModel {
+        // exp([[A, B], [0, 0]] dt) = [[Ad, Bd], [0, 1]]
+        let n = self.order();
+        let mut augmented = Array2::<f64>::zeros((n + 1, n + 1));
+        for i in 0..n {
+            for j in 0..n {
+                augmented[[i, j]] = self.a[[i, j]] * sampling_interval;
+            }
+            augmented[[i, n]] = self.b[i] * sampling_interval;
+        }
+        let exponential = expm(&augmented);
+        StateSpaceModel {
+            a: exponential.slice(ndarray::s![..n, ..n]).to_owned(),
+            b: exponential.slice(ndarray::s![..n, n]).to_owned(),
+            c: self.c.clone(),
+        }
+    }
+}
+
+/// Matrix exponential by scaling and squaring of the Taylor series
+pub fn expm(m: &Array2<f64>) -> Array2<f64> {
+    let norm = m.iter().fold(0.0_f64, |n, v| n.max(v.abs())) * m.nrows() as f64;
+    let squarings = if norm > 0.5 { (norm / 0.5).log2().ceil() as i32 } else { 0 };
+    let scaled = m / 2.0_f64.powi(squarings);
+    let mut result = Array2::<f64>::eye(m.nrows());
+    let mut term = Array2::<f64>::eye(m.nrows());
+    for k in 1..20 {
+        term = term.dot(&scaled) / k as f64;
+        result += &term;
+    }
+    for _ in 0..squarings {
+        result = result.dot(&result);
+    }
+    result
+}
+
+/// Inverse by Gauss-Jordan elimination with partial pivoting, `None` if singular
+pub fn inverse(m: &Array2<f64>) -> Option<Array2<f64>> {
+    let n = m.nrows();
+    let mut left = m.clone();
+    let mut right = Array2::<f64>::eye(n);
+    for column in 0..n {
+        let pivot = (column..n).max_by(|i, j| left[[*i, column]].abs().total_cmp(&left[[*j, column]].abs()))?;
+        if left[[pivot, column]].abs() < 1e-15 {
+            return None;
+        }
+        for k in 0..n {
+            left.swap([column, k], [pivot, k]);
+            right.swap([column, k], [pivot, k]);
+        }
+        let factor = left[[column, column]];
+        for k in 0..n {
+            left[[column, k]] /= factor;
+            right[[column, k]] /= factor;
+        }
+        for row in 0..n {
+            if row != column {
+                let factor = left[[row, column]];
+                for k in 0..n {
+                    left[[row, k]] -= factor * left[[column, k]];
+                    right[[row, k]] -= factor * right[[column, k]];
+                }
+            }
+        }
+    }
+    Some(right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_order_hold_of_a_pt1_is_exact() {
+        let (kp, t1, dt) = (2.0, 50.0, 10.0);
+        let model = StateSpaceModel::from_plant(&PlantModel::Pt1 { kp, t1 }).discretize(dt);
+        let pole = (-dt / t1).exp();
+        assert!((model.a[[0, 0]] - pole).abs() < 1e-12);
+        assert!((model.b[0] - kp * (1.0 - pole)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn zero_order_hold_keeps_the_static_gain() {
+        let model = StateSpaceModel::from_plant(&PlantModel::Pt2 { kp: 3.0, omega: 0.2, damping: 0.4 }).discretize(1.0);
+        // steady state x = (I - A)^-1 b u
+        let steady_state = inverse(&(Array2::<f64>::eye(2) - &model.a)).unwrap().dot(&model.b);
+        assert!((model.c.dot(&steady_state) - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn inverse_of_a_matrix_needing_a_pivot() {
+        let m = Array2::from_shape_vec((2, 2), vec![0.0, 2.0, 4.0, 1.0]).unwrap();
+        let product = m.dot(&inverse(&m).unwrap());
+        for ((i, j), value) in product.indexed_iter() {
+            assert!((value - if i == j { 1.0 } else { 0.0 }).abs() < 1e-12);
+        }
+        assert_eq!(inverse(&Array2::from_shape_vec((2, 2), vec![1.0, 2.0, 2.0, 4.0]).unwrap()), None);
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// Deviation of the controller sampling instants from the nominal period
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Jitter {
+    #[default]
+    None,
+    /// Uniformly distributed within `±bound` [ms]
+    Bounded { bound: f64 },
+    /// Normally distributed with the standard deviation [ms]
+    Random { standard_deviation: f64 },
+}
+
+impl Jitter {
+    pub const NAMES: [&'static str; 3] = ["None", "Bounded", "Random"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Jitter::None => Self::NAMES[0],
+            Jitter::Bounded { .. } => Self::NAMES[1],
+            Jitter::Random { .. } => Self::NAMES[2],
+        }
+    }
+
+    /// Bound or standard deviation, zero without jitter
+    pub fn amount(&self) -> f64 {
+        match *self {
+            Jitter::None => 0.0,
+            Jitter::Bounded { bound } => bound,
+            Jitter::Random { standard_deviation } => standard_deviation,
+        }
+    }
+
+    /// Jitter of the named kind, unknown names fall back to none
+    pub fn from_name(name: &str, amount: f64) -> Self {
+        match name {
+            "Bounded" => Jitter::Bounded { bound: amount },
+            "Random" => Jitter::Random { standard_deviation: amount },
+            _ => Jitter::None,
+        }
+    }
+}
+
+/// Execution timing of the controller on a real target
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimingSetup {
+    pub jitter: Jitter,
+    /// Time between reading the measurement and writing the output [ms]
+    pub computation_delay: f64,
+    /// Seed of the jitter, the same seed reproduces the same sampling instants
+    pub seed: u64,
+}
+
+impl TimingSetup {
+    pub fn set_jitter(self, jitter: Jitter) -> Self {
+        TimingSetup { jitter, ..self }
+    }
+
+    pub fn set_computation_delay(self, computation_delay: f64) -> Self {
+        TimingSetup { computation_delay, ..self }
+    }
+
+    pub fn set_seed(self, seed: u64) -> Self {
+        TimingSetup { seed, ..self }
+    }
+
+    pub fn is_ideal(&self) -> bool {
+        self.jitter.amount() <= 0.0 && self.computation_delay <= 0.0
+    }
+
+    /// Marks the plant steps at which the controller samples.
+    ///
+    /// The nominal instants are every `controller_ratio`-th step, each one is shifted by its own
+    /// jitter. Shifts of a fraction of a plant step are dithered to whole steps, so that their
+    /// mean is kept. A sample stays behind its predecessor and ahead of the nominal instant
+    /// after the next one, the order is only kept locally and the mean period is unchanged.
+    pub fn sample_steps(&self, controller_ratio: usize, sampling_interval: f64, steps: usize) -> Vec<bool> {
+        let mut samples = vec![false; steps];
+        let mut random = SplitMix64::new(self.seed);
+        let ratio = controller_ratio.max(1);
+        let mut previous: Option<usize> = None;
+        for nominal in (0..steps).step_by(ratio) {
+            let offset = match self.jitter {
+                Jitter::None => 0.0,
+                Jitter::Bounded { bound } => (2.0 * random.next_f64() - 1.0) * bound,
+                Jitter::Random { standard_deviation } => random.next_gaussian() * standard_deviation,
+            };
+            let shift = if sampling_interval > 0.0 { offset / sampling_interval } else { 0.0 };
+            let whole = shift.floor();
+            let shift = if random.next_f64() < shift - whole { whole + 1.0 } else { whole };
+            let earliest = previous.map_or(0, |p| p + 1);
+            let latest = (nominal + 2 * ratio - 1).min(steps - 1);
+            if earliest > latest {
+                continue;
+            }
+            let step = (nominal as f64 + shift).clamp(earliest as f64, latest as f64) as usize;
+            samples[step] = true;
+            previous = Some(step);
+        }
+        samples
+    }
+
+    /// Output buffer delaying the controller outputs by the computation delay
+    pub fn output_delay(&self, sampling_interval: f64) -> OutputDelay {
+        let steps = if sampling_interval > 0.0 && self.computation_delay > 0.0 {
+            (self.computation_delay / sampling_interval).round() as usize
+        } else {
+            0
+        };
+        OutputDelay { steps, pending: VecDeque::new() }
+    }
+}
+
+/// Controller outputs waiting for the end of their computation
+#[derive(Debug, Clone)]
+pub struct OutputDelay {
+    steps: usize,
+    /// plant step at which the output gets effective, output
+    pending: VecDeque<(usize, f64)>,
+}
+
+impl OutputDelay {
+    /// Output computed at the plant step, effective at the next step plus the delay
+    pub fn push(&mut self, step: usize, output: f64) {
+        self.pending.push_back((step + 1 + self.steps, output));
+    }
+
+    /// The latest output effective at the plant step, `held` if none is due
+    pub fn release(&mut self, step: usize, held: f64) -> f64 {
+        let mut output = held;
+        while let Some((due, value)) = self.pending.front() {
+            if *due > step {
+                break;
+            }
+            output = *value;
+            self.pending.pop_front();
+        }
+        output
+    }
+}
+
+/// Small, seedable generator, identical results on every platform
+#[derive(Debug, Clone)]
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal by Box-Muller
+    fn next_gaussian(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEPS: usize = 100_000;
+
+    /// Plant steps of the controller samples
+    fn sampled(timing: &TimingSetup, controller_ratio: usize) -> Vec<usize> {
+        let samples = timing.sample_steps(controller_ratio, 1.0, STEPS);
+        (0..STEPS).filter(|step| samples[*step]).collect()
+    }
+
+    #[test]
+    fn without_jitter_every_ratio_th_step() {
+        let steps = sampled(&TimingSetup::default(), 4);
+        assert_eq!(steps.len(), STEPS / 4);
+        assert!(steps.iter().enumerate().all(|(k, step)| *step == 4 * k));
+    }
+
+    #[test]
+    fn jitter_keeps_the_mean_sample_spacing() {
+        for jitter in [
+            Jitter::Bounded { bound: 0.3 },
+            Jitter::Bounded { bound: 3.0 },
+            Jitter::Random { standard_deviation: 0.4 },
+            Jitter::Random { standard_deviation: 4.0 },
+        ] {
+            let steps = sampled(&TimingSetup::default().set_jitter(jitter).set_seed(7), 5);
+            let spacing = (steps[steps.len() - 1] - steps[0]) as f64 / (steps.len() - 1) as f64;
+            assert!((spacing - 5.0).abs() < 0.01, "{:?}: {}", jitter, spacing);
+            assert!(steps.len() + 2 >= STEPS / 5, "{:?}: {} samples", jitter, steps.len());
+            // jitter below half a plant step still shifts samples
+            assert!(steps.iter().any(|step| step % 5 != 0), "{:?}", jitter);
+        }
+    }
+
+    #[test]
+    fn jitter_is_reproducible_by_seed() {
+        let timing = TimingSetup::default().set_jitter(Jitter::Random { standard_deviation: 2.0 });
+        assert_eq!(sampled(&timing.clone().set_seed(1), 3), sampled(&timing.clone().set_seed(1), 3));
+        assert_ne!(sampled(&timing.clone().set_seed(1), 3), sampled(&timing.set_seed(2), 3));
+    }
+}
@@ -1,13 +1,22 @@
+#[cfg(feature = "web")]
 pub mod app;
+#[cfg(feature = "web")]
 pub mod components;
+pub mod control;
+#[cfg(feature = "web")]
 mod pages;
 pub mod plant;
+#[cfg(feature = "web")]
 mod router;
+pub mod simulation;
 pub mod time_signal;
 
+#[cfg(feature = "web")]
 use plant::register_all::register_build_in_elements;
+#[cfg(feature = "web")]
 use time_signal::register_build_in_time_signals;
 
+#[cfg(feature = "web")]
 pub fn register_build_in() {
     register_build_in_elements();
     register_build_in_time_signals();
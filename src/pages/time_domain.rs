@@ -4,18 +4,19 @@ use log::info;
 
 use crate::components::control::controller::AccordeonController;
 use crate::components::plant::element::AccordeonElements;
+use crate::components::plot_cascade::AccordeonPlotCascade;
 use crate::components::plot_element::AccordeonPlotElement;
 use crate::components::plot_control::AccordeonPlotControl;
+use crate::components::plot_live::AccordeonPlotLive;
 use crate::components::plot_time_signal::AccordeonPlotTimeSignal;
 use crate::components::time_range::AccordeonTimeRange;
 use crate::components::time_signal::time_signal::AccordeonTimeSignals;
 
+use crate::control::pid_setup::PidSetup;
 use crate::plant::named_element::NamedElement;
 use crate::time_signal::named_time_signal::NamedTimeSignal;
 use cb_simulation_util::signal::TimeRange;
 
-use cb_controller::pid::{PidController, PidCoreBuilder};
-
 #[function_component(TimeDomain)]
 pub fn time_domain() -> Html {
     let time_range_handle = use_state(|| TimeRange::default());
@@ -27,17 +28,27 @@ pub fn time_domain() -> Html {
     let elements_handle = use_state(|| Vec::<NamedElement<f64>>::new());
     let elements = (*elements_handle).clone();
 
-    let controller_handle = use_state(|| PidCoreBuilder::<f64>::default()
-        .sampling_interval(time_range.sampling_interval.clone() as f32)
-        .build());
+    let controller_handle = use_state(|| PidSetup::default()
+        .set_sampling_interval(time_range.sampling_interval.clone()));
 
     let on_controller_update  = {
         let controller_handle = controller_handle.clone();
-        Callback::from(move |updated: PidController<f64>| {
+        Callback::from(move |updated: PidSetup| {
             info!("Time domain - new Controller: {:?}", updated);
             controller_handle.set(updated)
         })
     };
+
+    let inner_controller_handle = use_state(|| PidSetup::default()
+        .set_sampling_interval(time_range.sampling_interval.clone()));
+
+    let on_inner_controller_update  = {
+        let inner_controller_handle = inner_controller_handle.clone();
+        Callback::from(move |updated: PidSetup| {
+            info!("Time domain - new inner Controller: {:?}", updated);
+            inner_controller_handle.set(updated)
+        })
+    };
     html! {
         <>
             <AccordeonTimeRange handle={time_range_handle}/>
@@ -48,8 +59,13 @@ pub fn time_domain() -> Html {
             <AccordeonElements elements={elements_handle} sample_time={time_range.sampling_interval.clone()} />
             <AccordeonPlotElement range={time_range.clone()} signals={signals.clone()} elements={elements.clone()} />
 
-            <AccordeonController update={on_controller_update.clone()} sampling_interval={time_range.sampling_interval.clone()} />
+            <AccordeonController update={on_controller_update.clone()} sampling_interval={time_range.sampling_interval.clone()} elements={elements.clone()} />
             <AccordeonPlotControl range={time_range.clone()} signals={signals.clone()} elements={elements.clone()} controller={(*controller_handle).clone()} />
+            <AccordeonPlotLive range={time_range.clone()} elements={elements.clone()} controller={(*controller_handle).clone()} />
+
+            <AccordeonController name="Inner Loop PID Controller" update={on_inner_controller_update.clone()} sampling_interval={time_range.sampling_interval.clone()} elements={elements.clone()} />
+            <AccordeonPlotCascade range={time_range.clone()} signals={signals.clone()} elements={elements.clone()}
+                outer_controller={(*controller_handle).clone()} inner_controller={(*inner_controller_handle).clone()} />
 
         </>
 
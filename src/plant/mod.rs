@@ -1,3 +1,5 @@
 pub mod named_element;
+#[cfg(feature = "web")]
 pub mod register_all;
+#[cfg(feature = "web")]
 pub mod registry;
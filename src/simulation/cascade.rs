@@ -0,0 +1,275 @@
+//! Cascade control: the outer controller sets the setpoint of a faster inner loop,
+//! e.g. flow heats up a tank or speed moves a carriage.
+use log::info;
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+
+use crate::control::pid_setup::PidSetup;
+use crate::control::timing::TimingSetup;
+use crate::control::LoopController;
+use crate::plant::named_element::NamedElement;
+use crate::time_signal::named_time_signal::NamedTimeSignal;
+use cb_simulation_util::plant::BoxedTransferTimeDomain;
+use cb_simulation_util::signal::TimeRange;
+
+/// Everything defining one simulated cascade
+#[derive(Debug, Clone, PartialEq)]
+pub struct CascadeSetup {
+    pub range: TimeRange,
+    pub setpoint: NamedTimeSignal<f64>,
+    /// Driven by the output of the inner element
+    pub outer_element: NamedElement<f64>,
+    pub inner_element: NamedElement<f64>,
+    pub outer_controller: PidSetup,
+    pub inner_controller: PidSetup,
+    /// The outer controller is executed at every `outer_ratio`-th sample of the inner loop
+    pub outer_ratio: usize,
+}
+
+impl CascadeSetup {
+    pub fn set_range(self, range: TimeRange) -> Self {
+        CascadeSetup { range, ..self }
+    }
+
+    pub fn set_setpoint(self, setpoint: NamedTimeSignal<f64>) -> Self {
+        CascadeSetup { setpoint, ..self }
+    }
+
+    pub fn set_outer_element(self, outer_element: NamedElement<f64>) -> Self {
+        CascadeSetup { outer_element, ..self }
+    }
+
+    pub fn set_inner_element(self, inner_element: NamedElement<f64>) -> Self {
+        CascadeSetup { inner_element, ..self }
+    }
+
+    pub fn set_outer_controller(self, outer_controller: PidSetup) -> Self {
+        CascadeSetup { outer_controller, ..self }
+    }
+
+    pub fn set_inner_controller(self, inner_controller: PidSetup) -> Self {
+        CascadeSetup { inner_controller, ..self }
+    }
+
+    pub fn set_outer_ratio(self, outer_ratio: usize) -> Self {
+        CascadeSetup { outer_ratio, ..self }
+    }
+
+    /// Runs the whole cascade at once
+    pub fn simulate(&self) -> CascadeResult {
+        let mut run = self.start();
+        run.advance(usize::MAX);
+        run.finish()
+    }
+
+    /// Fresh controllers and elements at rest, to be advanced step by step
+    pub fn start(&self) -> CascadeRun {
+        let time: Array1<f64> = self.range.collect();
+        let signal = self.setpoint.signal.clone();
+        let setpoint: Array1<f64> = time.iter().map(|v| signal.time_to_signal(*v)).collect();
+        let sampling_interval = self.range.sampling_interval;
+        let outer_ratio = self.outer_ratio.max(1);
+        let outer_pid = self
+            .outer_controller
+            .clone()
+            .set_sampling_interval(sampling_interval * outer_ratio as f64)
+            .set_model_steps(outer_ratio)
+            .controller();
+        let inner_pid = self.inner_controller.clone().set_sampling_interval(sampling_interval).controller();
+        info!("Simulation of cascade - outer: {:?} inner: {:?}", outer_pid, inner_pid);
+
+        let dim = time.len();
+        CascadeRun {
+            step: 0,
+            outer_samples: TimingSetup::default().sample_steps(outer_ratio, sampling_interval, dim),
+            outer_pid,
+            inner_pid,
+            outer_element: self.outer_element.element.clone(),
+            inner_element: self.inner_element.element.clone(),
+            time,
+            setpoint,
+            inner_setpoint: Array1::zeros(dim),
+            u_inner: Array1::zeros(dim),
+            y_inner: Array1::zeros(dim),
+            y_outer: Array1::zeros(dim),
+        }
+    }
+}
+
+impl Default for CascadeSetup {
+    fn default() -> Self {
+        CascadeSetup {
+            range: TimeRange::default(),
+            setpoint: NamedTimeSignal::default(),
+            outer_element: NamedElement::default(),
+            inner_element: NamedElement::default(),
+            outer_controller: PidSetup::default(),
+            inner_controller: PidSetup::default(),
+            outer_ratio: 1,
+        }
+    }
+}
+
+/// Cascade in progress, see [`CascadeSetup::start`]
+#[derive(Debug)]
+pub struct CascadeRun {
+    /// Next time step to compute
+    step: usize,
+    /// Marks the time steps at which the outer controller samples
+    outer_samples: Vec<bool>,
+    outer_pid: Box<dyn LoopController>,
+    inner_pid: Box<dyn LoopController>,
+    outer_element: BoxedTransferTimeDomain<f64>,
+    inner_element: BoxedTransferTimeDomain<f64>,
+    time: Array1<f64>,
+    setpoint: Array1<f64>,
+    inner_setpoint: Array1<f64>,
+    u_inner: Array1<f64>,
+    y_inner: Array1<f64>,
+    y_outer: Array1<f64>,
+}
+
+impl CascadeRun {
+    /// Computes up to `steps` further time steps, returns if the run is complete
+    pub fn advance(&mut self, steps: usize) -> bool {
+        let end = self.step.saturating_add(steps).min(self.time.len());
+        while self.step < end {
+            self.compute(self.step);
+            self.step += 1;
+        }
+        self.is_finished()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step >= self.time.len()
+    }
+
+    /// Fraction of the computed time steps, from 0 to 1
+    pub fn progress(&self) -> f64 {
+        if self.time.is_empty() {
+            1.0
+        } else {
+            self.step as f64 / self.time.len() as f64
+        }
+    }
+
+    /// The signals, time steps not yet computed are zero
+    pub fn finish(self) -> CascadeResult {
+        CascadeResult {
+            time: self.time,
+            setpoint: self.setpoint,
+            inner_setpoint: self.inner_setpoint,
+            u_inner: self.u_inner,
+            y_inner: self.y_inner,
+            y_outer: self.y_outer,
+        }
+    }
+
+    fn compute(&mut self, i: usize) {
+        self.y_inner[i] = self.inner_element.transfer_td(self.u_inner[i]);
+        self.y_outer[i] = self.outer_element.transfer_td(self.y_inner[i]);
+        if i + 1 == self.time.len() {
+            return;
+        }
+        // the outer controller output is held in between its samples
+        self.inner_setpoint[i + 1] = if self.outer_samples[i] {
+            self.outer_pid.update(self.setpoint[i], self.y_outer[i])
+        } else {
+            self.inner_setpoint[i]
+        };
+        self.u_inner[i + 1] = self.inner_pid.update(self.inner_setpoint[i + 1], self.y_inner[i]);
+    }
+}
+
+/// Signals of both loops of the cascade, all sampled at the time vector
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CascadeResult {
+    pub time: Array1<f64>,
+    /// Setpoint of the outer loop
+    pub setpoint: Array1<f64>,
+    /// Output of the outer controller, the setpoint of the inner loop
+    pub inner_setpoint: Array1<f64>,
+    /// Output of the inner controller
+    pub u_inner: Array1<f64>,
+    pub y_inner: Array1<f64>,
+    pub y_outer: Array1<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cb_controller::pid::PidCoreBuilder;
+    use cb_simulation_util::plant::pt1::PT1;
+    use cb_simulation_util::signal::step_fn::StepFunction;
+
+    const SAMPLING_INTERVAL: f64 = 1.0;
+
+    fn pt1(t1: f64) -> NamedElement<f64> {
+        let pt1 = PT1::<f64>::default()
+            .set_sample_time_or_default(SAMPLING_INTERVAL)
+            .set_t1_time_or_default(t1)
+            .set_kp(1.0);
+        NamedElement::default().set_element(Box::new(pt1))
+    }
+
+    fn pi(kp: f64, ki: f64) -> PidSetup {
+        PidSetup::default().set_core(PidCoreBuilder::default().kp(kp).ki(ki).kd(0.0))
+    }
+
+    /// Unit step onto a slow outer and a fast inner PT1
+    fn cascade(outer_ratio: usize) -> CascadeSetup {
+        let range = TimeRange::default()
+            .set_sampling_interval(SAMPLING_INTERVAL)
+            .set_start(0.0)
+            .set_end(1000.0);
+        CascadeSetup::default()
+            .set_range(range)
+            .set_setpoint(NamedTimeSignal::default().set_signal(Box::new(StepFunction::<f64>::default())))
+            .set_outer_element(pt1(50.0))
+            .set_inner_element(pt1(5.0))
+            .set_outer_controller(pi(1.0, 0.02))
+            .set_inner_controller(pi(2.0, 0.2))
+            .set_outer_ratio(outer_ratio)
+    }
+
+    #[test]
+    fn outer_controller_output_is_held_between_its_samples() {
+        let result = cascade(5).simulate();
+        for i in 0..result.time.len() - 1 {
+            if i % 5 != 0 {
+                assert_eq!(result.inner_setpoint[i + 1], result.inner_setpoint[i], "step {}", i);
+            }
+        }
+        // it does change at its samples
+        assert_ne!(result.inner_setpoint[1], 0.0);
+        let sampled = (5..result.time.len() - 1).step_by(5);
+        assert!(sampled.filter(|i| result.inner_setpoint[i + 1] != result.inner_setpoint[*i]).count() > 10);
+    }
+
+    #[test]
+    fn zero_outer_ratio_samples_every_step() {
+        assert_eq!(cascade(0).simulate(), cascade(1).simulate());
+    }
+
+    #[test]
+    fn cascade_settles_at_the_setpoint() {
+        for outer_ratio in [1, 5] {
+            let result = cascade(outer_ratio).simulate();
+            let last = result.time.len() - 1;
+            assert!((result.y_outer[last] - 1.0).abs() < 1e-3, "ratio {}: {}", outer_ratio, result.y_outer[last]);
+            // the inner loop follows its setpoint
+            assert!((result.y_inner[last] - result.inner_setpoint[last]).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn advancing_in_chunks_matches_the_whole_run() {
+        let setup = cascade(5);
+        let mut run = setup.start();
+        assert_eq!(run.progress(), 0.0);
+        while !run.advance(7) {
+            assert!(run.progress() < 1.0);
+        }
+        assert_eq!(run.finish(), setup.simulate());
+    }
+}
@@ -0,0 +1,611 @@
+use log::info;
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+
+use crate::control::dead_time::DeadTime;
+#[cfg(feature = "cli")]
+use crate::control::external::ExternalControllerSetup;
+use crate::control::feedforward::{FeedforwardSetup, LeadLagFilter};
+use crate::control::instrument::{Actuator, ActuatorSetup, Sensor, SensorSetup};
+use crate::control::mode_switch::{ManualAutoStation, ModeSwitchSetup};
+use crate::control::pid_setup::PidSetup;
+use crate::control::timing::{OutputDelay, TimingSetup};
+use crate::control::LoopController;
+use crate::plant::named_element::NamedElement;
+use crate::time_signal::named_time_signal::NamedTimeSignal;
+use cb_simulation_util::plant::BoxedTransferTimeDomain;
+use cb_simulation_util::signal::TimeRange;
+
+/// Everything defining one simulated control loop
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoopSetup {
+    pub range: TimeRange,
+    pub setpoint: NamedTimeSignal<f64>,
+    pub element: NamedElement<f64>,
+    pub controller: PidSetup,
+    /// Process computing the control variable instead of the controller, software in the loop
+    #[cfg(feature = "cli")]
+    pub external_controller: Option<ExternalControllerSetup>,
+    /// Without feedback the controller sees the setpoint as process variable
+    pub open_loop: bool,
+    /// Measurable disturbance added to the plant input
+    pub disturbance: Option<NamedTimeSignal<f64>>,
+    /// Load disturbance added to the plant output
+    pub output_disturbance: Option<NamedTimeSignal<f64>>,
+    /// Measurement noise added to the process variable in the feedback path
+    pub noise: Option<NamedTimeSignal<f64>>,
+    pub feedforward: FeedforwardSetup,
+    /// Dead time of the simulated plant [ms]
+    pub plant_dead_time: f64,
+    /// The controller is executed at every `controller_ratio`-th plant step,
+    /// the control variable is held in between.
+    pub controller_ratio: usize,
+    /// Sampling jitter and computation delay of the controller
+    pub timing: TimingSetup,
+    /// Ideal actuator without it
+    pub actuator: Option<ActuatorSetup>,
+    /// Ideal sensor without it
+    pub sensor: Option<SensorSetup>,
+    /// Output profile of the manual mode, the closed loop is automatic only without it
+    pub manual: Option<NamedTimeSignal<f64>>,
+    pub mode_switch: ModeSwitchSetup,
+    /// External scheduling variable of a gain-scheduled PID
+    pub scheduling: Option<NamedTimeSignal<f64>>,
+    /// Records the integrator state, zero for controllers not exposing it
+    pub record_integral: bool,
+}
+
+impl LoopSetup {
+    pub fn set_range(self, range: TimeRange) -> Self {
+        LoopSetup { range, ..self }
+    }
+
+    pub fn set_setpoint(self, setpoint: NamedTimeSignal<f64>) -> Self {
+        LoopSetup { setpoint, ..self }
+    }
+
+    pub fn set_element(self, element: NamedElement<f64>) -> Self {
+        LoopSetup { element, ..self }
+    }
+
+    pub fn set_controller(self, controller: PidSetup) -> Self {
+        LoopSetup { controller, ..self }
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn set_external_controller(self, external_controller: Option<ExternalControllerSetup>) -> Self {
+        LoopSetup { external_controller, ..self }
+    }
+
+    /// Whether a process replaces the controller, never without the `cli` feature
+    pub fn has_external_controller(&self) -> bool {
+        #[cfg(feature = "cli")]
+        return self.external_controller.is_some();
+        #[cfg(not(feature = "cli"))]
+        false
+    }
+
+    #[cfg(feature = "cli")]
+    fn spawn_external_controller(&self) -> Option<Box<dyn LoopController>> {
+        match self.external_controller.as_ref()?.spawn() {
+            Ok(external) => Some(Box::new(external)),
+            Err(error) => {
+                info!("External controller failed to start, using the PID: {}", error);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "cli"))]
+    fn spawn_external_controller(&self) -> Option<Box<dyn LoopController>> {
+        None
+    }
+
+    pub fn set_open_loop(self, open_loop: bool) -> Self {
+        LoopSetup { open_loop, ..self }
+    }
+
+    pub fn set_disturbance(self, disturbance: Option<NamedTimeSignal<f64>>) -> Self {
+        LoopSetup { disturbance, ..self }
+    }
+
+    pub fn set_output_disturbance(self, output_disturbance: Option<NamedTimeSignal<f64>>) -> Self {
+        LoopSetup { output_disturbance, ..self }
+    }
+
+    pub fn set_noise(self, noise: Option<NamedTimeSignal<f64>>) -> Self {
+        LoopSetup { noise, ..self }
+    }
+
+    pub fn set_feedforward(self, feedforward: FeedforwardSetup) -> Self {
+        LoopSetup { feedforward, ..self }
+    }
+
+    pub fn set_plant_dead_time(self, plant_dead_time: f64) -> Self {
+        LoopSetup { plant_dead_time, ..self }
+    }
+
+    pub fn set_controller_ratio(self, controller_ratio: usize) -> Self {
+        LoopSetup { controller_ratio, ..self }
+    }
+
+    pub fn set_timing(self, timing: TimingSetup) -> Self {
+        LoopSetup { timing, ..self }
+    }
+
+    pub fn set_actuator(self, actuator: Option<ActuatorSetup>) -> Self {
+        LoopSetup { actuator, ..self }
+    }
+
+    pub fn set_sensor(self, sensor: Option<SensorSetup>) -> Self {
+        LoopSetup { sensor, ..self }
+    }
+
+    pub fn set_manual(self, manual: Option<NamedTimeSignal<f64>>) -> Self {
+        LoopSetup { manual, ..self }
+    }
+
+    pub fn set_mode_switch(self, mode_switch: ModeSwitchSetup) -> Self {
+        LoopSetup { mode_switch, ..self }
+    }
+
+    pub fn set_scheduling(self, scheduling: Option<NamedTimeSignal<f64>>) -> Self {
+        LoopSetup { scheduling, ..self }
+    }
+
+    pub fn set_record_integral(self, record_integral: bool) -> Self {
+        LoopSetup { record_integral, ..self }
+    }
+
+    /// Runs the loop over the time range
+    pub fn simulate(&self) -> LoopResult {
+        let mut run = self.start();
+        run.advance(usize::MAX);
+        run.finish()
+    }
+
+    /// Prepares a run to be advanced in chunks of time steps, e.g. to report progress
+    pub fn start(&self) -> LoopRun {
+        let time: Array1<f64> = self.range.collect();
+        let sample = |signal: &NamedTimeSignal<f64>| -> Array1<f64> {
+            time.iter().map(|t| signal.signal.time_to_signal(*t)).collect()
+        };
+        let optional = |signal: &Option<NamedTimeSignal<f64>>| -> Array1<f64> {
+            signal.as_ref().map_or_else(|| Array1::zeros(time.len()), sample)
+        };
+        let setpoint = sample(&self.setpoint);
+        let disturbance = optional(&self.disturbance);
+        let output_disturbance = optional(&self.output_disturbance);
+        let noise = optional(&self.noise);
+        let manual = self.manual.as_ref().map(sample);
+        let scheduling = self.scheduling.as_ref().map(sample);
+
+        let dim = time.len();
+        let sampling_interval = self.range.sampling_interval;
+        let controller_ratio = self.controller_ratio.max(1);
+        let controller_interval = sampling_interval * controller_ratio as f64;
+        let controller = self
+            .controller
+            .clone()
+            .set_sampling_interval(controller_interval)
+            .set_model_steps(controller_ratio);
+        // the manual mode only exists with feedback
+        let manual = manual.filter(|_| !self.open_loop);
+
+        // a loop with manual mode runs the PID law able to track, with and without bumpless transfer alike
+        let pid = self.spawn_external_controller().unwrap_or_else(|| match manual {
+            Some(_) => controller.tracking_controller(),
+            None => controller.controller(),
+        });
+        info!("Simulation with controller: {:?}", pid);
+
+        LoopRun {
+            setup: self.clone(),
+            step: 0,
+            samples: self.timing.sample_steps(controller_ratio, sampling_interval, dim),
+            pid,
+            element: self.element.element.clone(),
+            plant_dead_time: DeadTime::new(self.plant_dead_time, sampling_interval),
+            actuator: self.actuator.as_ref().map(|a| a.actuator(sampling_interval)),
+            sensor: self.sensor.as_ref().map(|s| s.sensor(sampling_interval)),
+            output_delay: self.timing.output_delay(sampling_interval),
+            station: ManualAutoStation::new(&self.mode_switch),
+            setpoint_ff: self.feedforward.setpoint.as_ref().map(|f| f.filter(controller_interval)),
+            disturbance_ff: self.feedforward.disturbance.as_ref().map(|f| f.filter(controller_interval)),
+            manual,
+            scheduling,
+            u: Array1::zeros(dim),
+            applied: Array1::zeros(dim),
+            y: Array1::zeros(dim),
+            measured: Array1::zeros(dim),
+            integral: Array1::zeros(dim),
+            time,
+            setpoint,
+            disturbance,
+            output_disturbance,
+            noise,
+        }
+    }
+}
+
+/// Control loop simulation in progress, created by [`LoopSetup::start`]
+#[derive(Debug)]
+pub struct LoopRun {
+    setup: LoopSetup,
+    /// Next time step to compute
+    step: usize,
+    samples: Vec<bool>,
+    pid: Box<dyn LoopController>,
+    element: BoxedTransferTimeDomain<f64>,
+    plant_dead_time: DeadTime,
+    actuator: Option<Actuator>,
+    sensor: Option<Sensor>,
+    output_delay: OutputDelay,
+    station: ManualAutoStation,
+    setpoint_ff: Option<LeadLagFilter>,
+    disturbance_ff: Option<LeadLagFilter>,
+    manual: Option<Array1<f64>>,
+    scheduling: Option<Array1<f64>>,
+    time: Array1<f64>,
+    setpoint: Array1<f64>,
+    disturbance: Array1<f64>,
+    output_disturbance: Array1<f64>,
+    noise: Array1<f64>,
+    u: Array1<f64>,
+    applied: Array1<f64>,
+    y: Array1<f64>,
+    measured: Array1<f64>,
+    integral: Array1<f64>,
+}
+
+impl LoopRun {
+    /// Computes up to `steps` further time steps, returns if the run is complete
+    pub fn advance(&mut self, steps: usize) -> bool {
+        let end = self.step.saturating_add(steps).min(self.time.len());
+        while self.step < end {
+            self.compute(self.step);
+            self.step += 1;
+        }
+        self.is_finished()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step >= self.time.len()
+    }
+
+    /// Number of time steps still to compute
+    pub fn remaining(&self) -> usize {
+        self.time.len().saturating_sub(self.step)
+    }
+
+    /// Fraction of the computed time steps, from 0 to 1
+    pub fn progress(&self) -> f64 {
+        if self.time.is_empty() {
+            1.0
+        } else {
+            self.step as f64 / self.time.len() as f64
+        }
+    }
+
+    /// The signals, time steps not yet computed are zero
+    pub fn finish(self) -> LoopResult {
+        let error = &self.setpoint - &self.measured;
+        LoopResult {
+            time: self.time,
+            setpoint: self.setpoint,
+            error,
+            u: self.u,
+            applied: self.applied,
+            y: self.y,
+            measured: self.measured,
+            disturbance: self.disturbance,
+            output_disturbance: self.output_disturbance,
+            noise: self.noise,
+            integral: self.setup.record_integral.then_some(self.integral),
+        }
+    }
+
+    fn feedforward(&mut self, i: usize) -> f64 {
+        let setpoint = self.setpoint[i];
+        let disturbance = self.disturbance[i];
+        self.setpoint_ff.as_mut().map_or(0.0, |f| f.update(setpoint))
+            + self.disturbance_ff.as_mut().map_or(0.0, |f| f.update(disturbance))
+    }
+
+    fn compute(&mut self, i: usize) {
+        let dim = self.time.len();
+        let open_loop = self.setup.open_loop;
+        if open_loop {
+            if self.samples[i] {
+                self.pid.time(self.time[i]);
+                if let Some(scheduling) = &self.scheduling {
+                    self.pid.schedule(scheduling[i]);
+                }
+                // without feedback the setpoint is the only controller input
+                let output = self.pid.update(0.0, self.setpoint[i]) + self.feedforward(i);
+                self.output_delay.push(i, output);
+            }
+            // without feedback the plant sees the output of the same step
+            let held = if i > 0 { self.u[i - 1] } else { 0.0 };
+            self.u[i] = self.output_delay.release(i + 1, held);
+            self.integral[i] = self.pid.integral().unwrap_or_default();
+        }
+
+        let u = self.u[i];
+        self.applied[i] = self.actuator.as_mut().map_or(u, |a| a.update(u));
+        let plant_output = self.element.transfer_td(self.applied[i] + self.disturbance[i]);
+        self.y[i] = self.plant_dead_time.update(plant_output) + self.output_disturbance[i];
+        // the controller only sees the measurement including sensor errors and noise
+        let y = self.y[i];
+        self.measured[i] = self.sensor.as_mut().map_or(y, |s| s.update(y)) + self.noise[i];
+
+        if open_loop || i + 1 == dim {
+            return;
+        }
+        // zero-order hold in between the controller samples and until the computation is done
+        if !self.samples[i] {
+            self.u[i + 1] = self.output_delay.release(i + 1, self.u[i]);
+            self.integral[i + 1] = self.integral[i];
+            return;
+        }
+        let (time, setpoint, measured) = (self.time[i], self.setpoint[i], self.measured[i]);
+        self.pid.time(time);
+        if let Some(scheduling) = &self.scheduling {
+            self.pid.schedule(scheduling[i]);
+        }
+        // the feedforward filters keep running in manual mode
+        let feedforward_output = self.feedforward(i);
+        let output = match &self.manual {
+            Some(manual) => {
+                self.station.update(self.pid.as_mut(), time, manual[i], feedforward_output, setpoint, measured)
+            }
+            None => self.pid.update(setpoint, measured) + feedforward_output,
+        };
+        self.output_delay.push(i, output);
+        self.u[i + 1] = self.output_delay.release(i + 1, self.u[i]);
+        self.integral[i + 1] = self.pid.integral().unwrap_or_default();
+    }
+}
+
+impl Default for LoopSetup {
+    fn default() -> Self {
+        LoopSetup {
+            range: TimeRange::default(),
+            setpoint: NamedTimeSignal::default(),
+            element: NamedElement::default(),
+            controller: PidSetup::default(),
+            #[cfg(feature = "cli")]
+            external_controller: None,
+            open_loop: false,
+            disturbance: None,
+            output_disturbance: None,
+            noise: None,
+            feedforward: FeedforwardSetup::default(),
+            plant_dead_time: 0.0,
+            controller_ratio: 1,
+            timing: TimingSetup::default(),
+            actuator: None,
+            sensor: None,
+            manual: None,
+            mode_switch: ModeSwitchSetup::default(),
+            scheduling: None,
+            record_integral: false,
+        }
+    }
+}
+
+/// Signals of one simulated control loop, all sampled at the time vector
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoopResult {
+    pub time: Array1<f64>,
+    /// r: setpoint
+    pub setpoint: Array1<f64>,
+    /// e: control error `r - y_m` as seen by the controller
+    pub error: Array1<f64>,
+    /// u: control variable
+    pub u: Array1<f64>,
+    /// u_a: control variable applied by the actuator
+    pub applied: Array1<f64>,
+    /// y: process variable
+    pub y: Array1<f64>,
+    /// y_m: process variable as seen by the controller
+    pub measured: Array1<f64>,
+    /// d: disturbance at the plant input
+    pub disturbance: Array1<f64>,
+    /// d_o: disturbance at the plant output
+    pub output_disturbance: Array1<f64>,
+    /// n: measurement noise
+    pub noise: Array1<f64>,
+    /// I: integrator state of the PID, if recorded
+    pub integral: Option<Array1<f64>>,
+}
+
+impl LoopResult {
+    /// Named columns in the order of the CSV output, the integrator only if recorded
+    pub fn columns(&self) -> Vec<(&'static str, &Array1<f64>)> {
+        let mut columns = vec![
+            ("time", &self.time),
+            ("r", &self.setpoint),
+            ("e", &self.error),
+            ("u", &self.u),
+            ("u_a", &self.applied),
+            ("y", &self.y),
+            ("y_m", &self.measured),
+            ("d", &self.disturbance),
+            ("d_o", &self.output_disturbance),
+            ("n", &self.noise),
+        ];
+        if let Some(integral) = &self.integral {
+            columns.push(("I", integral));
+        }
+        columns
+    }
+
+    /// Comma separated values with a header line, one row per time step
+    pub fn to_csv(&self) -> String {
+        let columns = self.columns();
+        let mut csv = columns.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(",");
+        csv.push('\n');
+        for i in 0..self.time.len() {
+            let row = columns.iter().map(|(_, values)| values[i].to_string()).collect::<Vec<_>>();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::feedforward::LeadLag;
+    use crate::control::pid_law::{AntiWindup, PidOptions};
+    use crate::control::smith_predictor::SmithPredictorSetup;
+    use cb_controller::pid::{PidCoreBuilder, PidOutputLimit};
+    use cb_simulation_util::plant::pt1::PT1;
+    use cb_simulation_util::signal::step_fn::StepFunction;
+
+    const SAMPLING_INTERVAL: f64 = 10.0;
+
+    /// Unit step onto a PT1 with gain `ks`, long enough to settle
+    fn pt1_loop(ks: f64, controller: PidSetup) -> LoopSetup {
+        let range = TimeRange::default()
+            .set_sampling_interval(SAMPLING_INTERVAL)
+            .set_start(0.0)
+            .set_end(20000.0);
+        let pt1 = PT1::<f64>::default()
+            .set_sample_time_or_default(SAMPLING_INTERVAL)
+            .set_t1_time_or_default(500.0)
+            .set_kp(ks);
+        LoopSetup::default()
+            .set_range(range)
+            .set_element(NamedElement::default().set_element(Box::new(pt1)))
+            .set_controller(controller)
+    }
+
+    fn pi(kp: f64, ki: f64) -> PidSetup {
+        PidSetup::default().set_core(PidCoreBuilder::default().kp(kp).ki(ki).kd(0.0))
+    }
+
+    #[test]
+    fn recording_the_integral_keeps_the_control_law() {
+        for anti_windup in [AntiWindup::None, AntiWindup::Clamping, AntiWindup::BackCalculation { tracking_time: 200.0 }] {
+            let controller = pi(4.0, 0.01)
+                .set_output_limit(Some(PidOutputLimit::default().range(-2.0, 2.0)))
+                .set_options(PidOptions::default().set_anti_windup(anti_windup));
+            let setup = pt1_loop(1.0, controller);
+            let plain = setup.clone().simulate();
+            let recorded = setup.set_record_integral(true).simulate();
+            assert_eq!(plain.u, recorded.u, "{:?}", anti_windup);
+            assert!(plain.integral.is_none());
+            assert!(recorded.integral.is_some());
+        }
+    }
+
+    #[test]
+    fn open_loop_ignores_the_manual_mode() {
+        let setup = pt1_loop(1.0, pi(2.0, 0.0)).set_open_loop(true);
+        let automatic = setup.clone().simulate();
+        for bumpless in [false, true] {
+            let manual = setup
+                .clone()
+                .set_manual(Some(NamedTimeSignal::default()))
+                .set_mode_switch(ModeSwitchSetup::default().set_bumpless(bumpless))
+                .simulate();
+            assert_eq!(automatic.u, manual.u);
+        }
+    }
+
+    #[test]
+    fn proportional_control_leaves_a_steady_state_error() {
+        let (kp, ks) = (2.0, 1.5);
+        let result = pt1_loop(ks, pi(kp, 0.0)).simulate();
+        let error = result.error[result.error.len() - 1];
+        assert!((error - 1.0 / (1.0 + kp * ks)).abs() < 1e-6, "{}", error);
+    }
+
+    #[test]
+    fn switch_to_automatic_is_bumpless_with_feedforward() {
+        let setup = pt1_loop(1.0, pi(2.0, 0.001));
+        let switch_step = 500;
+        let feedforward = FeedforwardSetup::default().set_setpoint(Some(LeadLag::default().set_gain(0.5)));
+        let manual = NamedTimeSignal::default().set_signal(Box::new(StepFunction::<f64>::default().pre(1.0).post(1.0)));
+        let result = setup
+            .clone()
+            .set_feedforward(feedforward)
+            .set_manual(Some(manual))
+            .set_mode_switch(ModeSwitchSetup::default().set_switch_time(switch_step as f64 * SAMPLING_INTERVAL))
+            .simulate();
+        // the manual output is held until the switch, the first automatic output continues from it
+        assert_eq!(result.u[switch_step], 1.0);
+        let bump = result.u[switch_step + 1] - result.u[switch_step];
+        assert!(bump.abs() < 1e-3, "{}", bump);
+    }
+
+    #[test]
+    fn open_loop_feeds_the_setpoint_to_the_controller() {
+        let setup = pt1_loop(1.0, pi(2.0, 0.001)).set_open_loop(true);
+        let result = setup.simulate();
+        let mut pid = setup.controller.clone().set_sampling_interval(SAMPLING_INTERVAL).controller();
+        let expected: Array1<f64> = result.setpoint.iter().map(|r| pid.update(0.0, *r)).collect();
+        assert_eq!(result.u, expected);
+    }
+
+    #[test]
+    fn advancing_in_chunks_equals_one_run() {
+        let setup = pt1_loop(1.0, pi(2.0, 0.001)).set_controller_ratio(3).set_record_integral(true);
+        let mut run = setup.start();
+        while !run.advance(7) {
+            assert!(run.progress() < 1.0);
+        }
+        assert_eq!(run.finish(), setup.simulate());
+    }
+
+    #[test]
+    fn csv_columns_in_order() {
+        let result = pt1_loop(1.0, pi(2.0, 0.001)).set_record_integral(true).simulate();
+        let csv = result.to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("time,r,e,u,u_a,y,y_m,d,d_o,n,I"));
+        assert_eq!(lines.count(), result.time.len());
+        let row: Vec<f64> = csv.lines().nth(2).unwrap().split(',').map(|v| v.parse().unwrap()).collect();
+        let expected: Vec<f64> = result.columns().iter().map(|(_, values)| values[1]).collect();
+        assert_eq!(row, expected);
+    }
+
+    #[test]
+    fn smith_predictor_model_runs_at_the_plant_rate() {
+        let setup = pt1_loop(1.0, pi(2.0, 0.001)).set_controller_ratio(5);
+        let smith = SmithPredictorSetup::default().set_model(setup.element.clone()).set_dead_time(100.0);
+        let predicted = setup
+            .clone()
+            .set_plant_dead_time(100.0)
+            .set_controller(setup.controller.clone().set_smith_predictor(Some(smith)))
+            .simulate();
+        // with a perfect model the controller acts as without dead time
+        let undelayed = setup.simulate();
+        for (a, b) in predicted.u.iter().zip(undelayed.u.iter()) {
+            assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    fn actuator_sensor_and_noise_act_in_the_loop() {
+        let noise = StepFunction::<f64>::default().pre(0.0).post(0.05).step(5000.0);
+        let result = pt1_loop(1.0, pi(4.0, 0.01))
+            .set_actuator(Some(ActuatorSetup::default().set_minimum(0.0).set_maximum(1.5)))
+            .set_sensor(Some(SensorSetup::default().set_bits(8).set_minimum(0.0).set_maximum(2.0)))
+            .set_noise(Some(NamedTimeSignal::default().set_signal(Box::new(noise))))
+            .simulate();
+        let resolution = 2.0 / 255.0;
+        for i in 0..result.time.len() {
+            assert_eq!(result.applied[i], result.u[i].clamp(0.0, 1.5), "step {}", i);
+            // the ADC quantizes the process variable, the noise is added behind it
+            let reading = result.measured[i] - result.noise[i];
+            assert!((reading / resolution - (reading / resolution).round()).abs() < 1e-6, "step {}", i);
+            assert!((reading - result.y[i]).abs() <= resolution / 2.0 + 1e-12, "step {}", i);
+        }
+        assert!(result.u.iter().any(|u| *u > 1.5));
+        assert!(result.noise.iter().any(|n| *n == 0.05));
+    }
+}
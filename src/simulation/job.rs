@@ -0,0 +1,261 @@
+use ndarray::{s, Array1};
+use serde::{Deserialize, Serialize};
+
+use cb_simulation_util::plant::BoxedTransferTimeDomain;
+use cb_simulation_util::signal::{BoxedTimeSignal, TimeRange};
+
+use crate::plant::named_element::NamedElement;
+use crate::simulation::cascade::{CascadeResult, CascadeRun, CascadeSetup};
+use crate::simulation::engine::{LoopResult, LoopRun, LoopSetup};
+use crate::simulation::project::{
+    ControllerDescription, ElementDescription, NamedElementDescription, Project, ProjectError, RangeDescription,
+    SignalDescription,
+};
+use crate::time_signal::named_time_signal::NamedTimeSignal;
+
+/// Computation of one plot, described by parameters to be sent to a worker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SimulationJob {
+    /// Time signal sampled over the range
+    Signal { range: RangeDescription, signal: SignalDescription },
+    /// Response of an element to a time signal, discretized with the sampling interval of the range
+    Element {
+        range: RangeDescription,
+        signal: SignalDescription,
+        element: ElementDescription,
+    },
+    /// Control loops, the configured one and the compared variants
+    Loops(Vec<Project>),
+    Cascade(Box<CascadeDescription>),
+}
+
+/// Cascade of two loops by its parameters, see [`CascadeSetup`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CascadeDescription {
+    pub range: RangeDescription,
+    pub setpoint: SignalDescription,
+    pub outer_element: NamedElementDescription,
+    pub inner_element: NamedElementDescription,
+    pub outer_controller: ControllerDescription,
+    pub inner_controller: ControllerDescription,
+    pub outer_ratio: usize,
+}
+
+impl SimulationJob {
+    pub fn signal(range: &TimeRange, signal: &NamedTimeSignal<f64>) -> Result<Self, ProjectError> {
+        Ok(SimulationJob::Signal {
+            range: RangeDescription::from_time_range(range),
+            signal: SignalDescription::from_time_signal(&signal.signal)
+                .ok_or_else(|| ProjectError::UnsupportedSignal(signal.name.clone()))?,
+        })
+    }
+
+    pub fn element(
+        range: &TimeRange,
+        signal: &NamedTimeSignal<f64>,
+        element: &NamedElement<f64>,
+    ) -> Result<Self, ProjectError> {
+        Ok(SimulationJob::Element {
+            range: RangeDescription::from_time_range(range),
+            signal: SignalDescription::from_time_signal(&signal.signal)
+                .ok_or_else(|| ProjectError::UnsupportedSignal(signal.name.clone()))?,
+            element: ElementDescription::from_element(&element.element)
+                .ok_or_else(|| ProjectError::UnsupportedElement(element.name.clone()))?,
+        })
+    }
+
+    pub fn loops(setups: &[LoopSetup]) -> Result<Self, ProjectError> {
+        Ok(SimulationJob::Loops(
+            setups.iter().map(Project::from_loop_setup).collect::<Result<_, _>>()?,
+        ))
+    }
+
+    pub fn cascade(setup: &CascadeSetup) -> Result<Self, ProjectError> {
+        Ok(SimulationJob::Cascade(Box::new(CascadeDescription {
+            range: RangeDescription::from_time_range(&setup.range),
+            setpoint: SignalDescription::from_time_signal(&setup.setpoint.signal)
+                .ok_or_else(|| ProjectError::UnsupportedSignal(setup.setpoint.name.clone()))?,
+            outer_element: NamedElementDescription::from_element(&setup.outer_element)?,
+            inner_element: NamedElementDescription::from_element(&setup.inner_element)?,
+            outer_controller: ControllerDescription::from_pid_setup(&setup.outer_controller)?,
+            inner_controller: ControllerDescription::from_pid_setup(&setup.inner_controller)?,
+            outer_ratio: setup.outer_ratio,
+        })))
+    }
+
+    pub fn start(&self) -> Result<JobRun, ProjectError> {
+        Ok(match self {
+            SimulationJob::Signal { range, signal } => JobRun::samples(&range.time_range(), signal.time_signal(), None),
+            SimulationJob::Element { range, signal, element } => JobRun::samples(
+                &range.time_range(),
+                signal.time_signal(),
+                Some(element.element(range.sampling_interval)),
+            ),
+            SimulationJob::Loops(projects) => {
+                JobRun::loops(projects.iter().map(Project::loop_setup).collect::<Result<_, _>>()?)
+            }
+            SimulationJob::Cascade(cascade) => {
+                let sampling_interval = cascade.range.sampling_interval;
+                JobRun::cascade(
+                    CascadeSetup::default()
+                        .set_range(cascade.range.time_range())
+                        .set_setpoint(NamedTimeSignal::default().set_signal(cascade.setpoint.time_signal()))
+                        .set_outer_element(cascade.outer_element.named_element(sampling_interval))
+                        .set_inner_element(cascade.inner_element.named_element(sampling_interval))
+                        .set_outer_controller(cascade.outer_controller.pid_setup(sampling_interval))
+                        .set_inner_controller(cascade.inner_controller.pid_setup(sampling_interval))
+                        .set_outer_ratio(cascade.outer_ratio),
+                )
+            }
+        })
+    }
+}
+
+/// Result of a [`SimulationJob`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SimulationResult {
+    /// Input signal and, for an element, its response
+    Samples {
+        time: Array1<f64>,
+        input: Array1<f64>,
+        output: Option<Array1<f64>>,
+    },
+    /// In the order of the setups
+    Loops(Vec<LoopResult>),
+    Cascade(CascadeResult),
+}
+
+/// Job in progress, advanced in chunks of time steps
+#[derive(Debug)]
+pub enum JobRun {
+    Samples {
+        time: Array1<f64>,
+        signal: BoxedTimeSignal<f64>,
+        element: Option<BoxedTransferTimeDomain<f64>>,
+        input: Vec<f64>,
+        output: Vec<f64>,
+    },
+    Loops {
+        /// Setups not yet started, in reverse order
+        pending: Vec<LoopSetup>,
+        running: Option<Box<LoopRun>>,
+        results: Vec<LoopResult>,
+        count: usize,
+    },
+    Cascade(Box<CascadeRun>),
+}
+
+impl JobRun {
+    pub fn samples(
+        range: &TimeRange,
+        signal: BoxedTimeSignal<f64>,
+        element: Option<BoxedTransferTimeDomain<f64>>,
+    ) -> Self {
+        let time: Array1<f64> = range.collect();
+        JobRun::Samples {
+            input: Vec::with_capacity(time.len()),
+            output: Vec::with_capacity(if element.is_some() { time.len() } else { 0 }),
+            time,
+            signal,
+            element,
+        }
+    }
+
+    pub fn loops(mut setups: Vec<LoopSetup>) -> Self {
+        setups.reverse();
+        JobRun::Loops {
+            count: setups.len(),
+            pending: setups,
+            running: None,
+            results: Vec::new(),
+        }
+    }
+
+    pub fn cascade(setup: CascadeSetup) -> Self {
+        JobRun::Cascade(Box::new(setup.start()))
+    }
+
+    /// Computes up to `steps` further time steps, returns if the job is complete
+    pub fn advance(&mut self, steps: usize) -> bool {
+        match self {
+            JobRun::Samples { time, signal, element, input, output } => {
+                let end = input.len().saturating_add(steps).min(time.len());
+                for &t in time.slice(s![input.len()..end]) {
+                    let value = signal.time_to_signal(t);
+                    input.push(value);
+                    if let Some(element) = element {
+                        output.push(element.transfer_td(value));
+                    }
+                }
+            }
+            JobRun::Loops { pending, running, results, .. } => {
+                let mut remaining = steps;
+                while remaining > 0 {
+                    let run = match running {
+                        Some(run) => run,
+                        None => match pending.pop() {
+                            Some(setup) => running.insert(Box::new(setup.start())),
+                            None => break,
+                        },
+                    };
+                    let computed = remaining.min(run.remaining());
+                    remaining -= computed;
+                    if run.advance(computed) {
+                        if let Some(run) = running.take() {
+                            results.push(run.finish());
+                        }
+                    }
+                }
+            }
+            JobRun::Cascade(run) => {
+                run.advance(steps);
+            }
+        }
+        self.is_finished()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match self {
+            JobRun::Samples { time, input, .. } => input.len() >= time.len(),
+            JobRun::Loops { pending, running, .. } => pending.is_empty() && running.is_none(),
+            JobRun::Cascade(run) => run.is_finished(),
+        }
+    }
+
+    /// Fraction of the computed time steps, from 0 to 1
+    pub fn progress(&self) -> f64 {
+        match self {
+            JobRun::Samples { time, input, .. } if !time.is_empty() => input.len() as f64 / time.len() as f64,
+            JobRun::Loops { running, results, count, .. } if *count > 0 => {
+                (results.len() as f64 + running.as_ref().map_or(0.0, |r| r.progress())) / *count as f64
+            }
+            JobRun::Cascade(run) => run.progress(),
+            _ => 1.0,
+        }
+    }
+
+    /// The results, missing time steps are zero
+    pub fn finish(self) -> SimulationResult {
+        match self {
+            JobRun::Samples { time, mut input, mut output, element, .. } => {
+                input.resize(time.len(), 0.0);
+                let output = element.map(|_| {
+                    output.resize(time.len(), 0.0);
+                    Array1::from(output)
+                });
+                SimulationResult::Samples { time, input: Array1::from(input), output }
+            }
+            JobRun::Loops { running, mut results, .. } => {
+                results.extend(running.map(|run| run.finish()));
+                SimulationResult::Loops(results)
+            }
+            JobRun::Cascade(run) => SimulationResult::Cascade(run.finish()),
+        }
+    }
+
+    /// Computes the whole job at once
+    pub fn complete(mut self) -> SimulationResult {
+        self.advance(usize::MAX);
+        self.finish()
+    }
+}
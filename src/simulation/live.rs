@@ -0,0 +1,207 @@
+//! Control loop simulated without end time, e.g. for a strip chart advancing in real time.
+//!
+//! Setpoint and gains may change while the loop runs, the controller keeps its states.
+use std::collections::VecDeque;
+use std::fmt;
+
+use cb_controller::pid::PidCoreBuilder;
+
+use crate::control::dead_time::DeadTime;
+use crate::control::instrument::{Actuator, Sensor};
+use crate::control::pid_law::PidLaw;
+use crate::control::LoopController;
+use crate::simulation::engine::LoopSetup;
+use cb_simulation_util::plant::BoxedTransferTimeDomain;
+
+/// Signals of one time step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiveSample {
+    pub time: f64,
+    /// r: setpoint
+    pub setpoint: f64,
+    /// u: control variable
+    pub u: f64,
+    /// y: process variable
+    pub y: f64,
+}
+
+/// Steps computed between two checks of the time left to [`LiveLoop::advance`]
+const STEPS_PER_CHECK: usize = 256;
+
+/// Part of the controller setup the live loop cannot run
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsupportedSetup(pub Vec<&'static str>);
+
+impl fmt::Display for UnsupportedSetup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the live loop runs the plain PID only, not: {}", self.0.join(", "))
+    }
+}
+
+impl std::error::Error for UnsupportedSetup {}
+
+/// Loop of the plant with dead time, actuator and sensor and the simulator's PID law.
+///
+/// Disturbances, noise, feedforward, manual mode and the timing of the setup are left out,
+/// the controller is executed at every step. Controllers replacing or adapting the PID law
+/// are rejected, see [`LiveLoop::unsupported`].
+#[derive(Debug)]
+pub struct LiveLoop {
+    sampling_interval: f64,
+    /// Next time step to compute
+    step: u64,
+    /// Simulated time to catch up with [ms]
+    target: f64,
+    setpoint: f64,
+    pid: PidLaw,
+    element: BoxedTransferTimeDomain<f64>,
+    plant_dead_time: DeadTime,
+    actuator: Option<Actuator>,
+    sensor: Option<Sensor>,
+    /// Control variable of the next step
+    u: f64,
+    /// The most recent samples, at most `capacity`
+    history: VecDeque<LiveSample>,
+    capacity: usize,
+}
+
+impl LiveLoop {
+    /// Parts of the setup changing the control law, which the live loop would silently drop
+    pub fn unsupported(setup: &LoopSetup) -> Vec<&'static str> {
+        let controller = &setup.controller;
+        [
+            (controller.smith_predictor.is_some(), "Smith predictor"),
+            (controller.gain_schedule.is_some(), "gain schedule"),
+            (controller.state_feedback.is_some(), "state feedback"),
+            (controller.mpc.is_some(), "MPC"),
+            (setup.has_external_controller(), "external controller"),
+        ]
+        .into_iter()
+        .filter_map(|(used, name)| used.then_some(name))
+        .collect()
+    }
+
+    /// Starts at time zero with setpoint zero, the history spans the length of the time range.
+    ///
+    /// Fails for setups with [`LiveLoop::unsupported`] parts.
+    pub fn new(setup: &LoopSetup) -> Result<Self, UnsupportedSetup> {
+        let unsupported = LiveLoop::unsupported(setup);
+        if !unsupported.is_empty() {
+            return Err(UnsupportedSetup(unsupported));
+        }
+        let sampling_interval = setup.range.sampling_interval;
+        let window = setup.range.end - setup.range.start;
+        let capacity = if sampling_interval > 0.0 && window > 0.0 {
+            (window / sampling_interval).ceil() as usize + 1
+        } else {
+            1
+        };
+        Ok(LiveLoop {
+            sampling_interval,
+            step: 0,
+            target: 0.0,
+            setpoint: 0.0,
+            pid: PidLaw::new(&setup.controller.clone().set_sampling_interval(sampling_interval)),
+            element: setup.element.element.clone(),
+            plant_dead_time: DeadTime::new(setup.plant_dead_time, sampling_interval),
+            actuator: setup.actuator.as_ref().map(|a| a.actuator(sampling_interval)),
+            sensor: setup.sensor.as_ref().map(|s| s.sensor(sampling_interval)),
+            u: 0.0,
+            history: VecDeque::with_capacity(capacity),
+            capacity,
+        })
+    }
+
+    pub fn set_setpoint(&mut self, setpoint: f64) {
+        self.setpoint = setpoint;
+    }
+
+    /// Changes the gains, the integral and the derivative filter of the PID are kept
+    pub fn set_gains(&mut self, core: &PidCoreBuilder<f64>) {
+        self.pid.set_gains(core.kp, core.get_ki(), core.get_kd());
+    }
+
+    /// Time of the next step [ms]
+    pub fn time(&self) -> f64 {
+        self.step as f64 * self.sampling_interval
+    }
+
+    /// Length of the history [ms]
+    pub fn window(&self) -> f64 {
+        self.capacity.saturating_sub(1) as f64 * self.sampling_interval
+    }
+
+    /// The most recent samples, the oldest first
+    pub fn history(&self) -> &VecDeque<LiveSample> {
+        &self.history
+    }
+
+    /// Computes the steps within further `duration` [ms] of simulated time.
+    ///
+    /// The computation stops once `in_time` tells the caller's time budget is spent,
+    /// a loop lagging behind then gives up catching up instead of blocking the caller.
+    pub fn advance(&mut self, duration: f64, mut in_time: impl FnMut() -> bool) {
+        if self.sampling_interval <= 0.0 {
+            return;
+        }
+        self.target += duration.max(0.0);
+        let mut steps = 0;
+        while self.time() < self.target {
+            if steps % STEPS_PER_CHECK == STEPS_PER_CHECK - 1 && !in_time() {
+                self.target = self.time();
+                break;
+            }
+            self.compute();
+            steps += 1;
+        }
+    }
+
+    fn compute(&mut self) {
+        let u = self.u;
+        let applied = self.actuator.as_mut().map_or(u, |a| a.update(u));
+        let y = self.plant_dead_time.update(self.element.transfer_td(applied));
+        let measured = self.sensor.as_mut().map_or(y, |s| s.update(y));
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(LiveSample { time: self.time(), setpoint: self.setpoint, u, y });
+        // the new control variable is applied in the next step
+        self.u = self.pid.update(self.setpoint, measured);
+        self.step += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::smith_predictor::SmithPredictorSetup;
+
+    #[test]
+    fn rejects_controllers_beyond_the_pid() {
+        let setup = LoopSetup::default();
+        assert!(LiveLoop::new(&setup).is_ok());
+        let controller = setup.controller.clone().set_smith_predictor(Some(SmithPredictorSetup::default()));
+        let error = LiveLoop::new(&setup.set_controller(controller)).unwrap_err();
+        assert_eq!(error, UnsupportedSetup(vec!["Smith predictor"]));
+    }
+
+    #[test]
+    fn catches_up_within_the_time_budget() {
+        let mut live = LiveLoop::new(&LoopSetup::default()).unwrap();
+        let dt = live.sampling_interval;
+        // far more than one history behind, within the budget
+        live.advance(10_000.0 * dt, || true);
+        assert_eq!(live.time(), 10_000.0 * dt);
+        // the budget runs out at the second check
+        let mut checks = 0;
+        live.advance(10_000.0 * dt, || {
+            checks += 1;
+            checks < 2
+        });
+        let computed = (2 * STEPS_PER_CHECK - 1) as f64 * dt;
+        assert_eq!(live.time(), 10_000.0 * dt + computed);
+        // the dropped lag is not caught up later
+        live.advance(0.0, || true);
+        assert_eq!(live.time(), 10_000.0 * dt + computed);
+    }
+}
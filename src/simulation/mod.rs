@@ -0,0 +1,16 @@
+//! Simulation of control loops, independent of the user interface
+pub mod cascade;
+pub mod engine;
+pub mod job;
+pub mod live;
+pub mod project;
+pub mod regression;
+#[cfg(feature = "web")]
+pub mod worker;
+
+pub use cascade::{CascadeResult, CascadeRun, CascadeSetup};
+pub use engine::{LoopResult, LoopRun, LoopSetup};
+pub use job::{JobRun, SimulationJob, SimulationResult};
+pub use live::{LiveLoop, LiveSample, UnsupportedSetup};
+pub use project::{Project, ProjectError};
+pub use regression::{Golden, Report, Tolerance};
@@ -0,0 +1,595 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use cb_controller::pid::{PidCoreBuilder, PidOutputLimit, PidSetpointRange};
+use cb_simulation_util::plant::{pt0::PT0, pt1::PT1, pt2::PT2, BoxedTransferTimeDomain};
+use cb_simulation_util::signal::{impulse_fn::ImpulseFunction, step_fn::StepFunction, BoxedTimeSignal, TimeRange};
+
+#[cfg(feature = "cli")]
+use crate::control::external::ExternalControllerSetup;
+use crate::control::feedforward::FeedforwardSetup;
+use crate::control::gain_schedule::GainScheduleSetup;
+use crate::control::instrument::{ActuatorSetup, SensorSetup};
+use crate::control::mode_switch::ModeSwitchSetup;
+use crate::control::mpc::MpcSetup;
+use crate::control::pid_law::PidOptions;
+use crate::control::pid_setup::PidSetup;
+use crate::control::smith_predictor::SmithPredictorSetup;
+use crate::control::state_feedback::{GainDesign, ObserverDesign, StateFeedbackSetup};
+use crate::control::timing::TimingSetup;
+use crate::plant::named_element::NamedElement;
+use crate::simulation::engine::LoopSetup;
+use crate::time_signal::named_time_signal::NamedTimeSignal;
+
+/// Time range of the simulation [ms]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RangeDescription {
+    pub start: f64,
+    pub end: f64,
+    pub sampling_interval: f64,
+}
+
+impl RangeDescription {
+    pub fn from_time_range(range: &TimeRange) -> Self {
+        RangeDescription {
+            start: range.start,
+            end: range.end,
+            sampling_interval: range.sampling_interval,
+        }
+    }
+
+    pub fn time_range(&self) -> TimeRange {
+        TimeRange::default()
+            .set_sampling_interval(self.sampling_interval)
+            .set_start(self.start)
+            .set_end(self.end)
+    }
+}
+
+/// Time signal by its parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SignalDescription {
+    Step { pre_value: f64, post_value: f64, step_time: f64 },
+    Impulse { out_value: f64, in_value: f64, start_time: f64, duration: f64 },
+}
+
+impl SignalDescription {
+    /// The parameters of the signal, [`None`] for signal types without description
+    pub fn from_time_signal(signal: &BoxedTimeSignal<f64>) -> Option<Self> {
+        let any = signal.as_any();
+        if let Some(step) = any.downcast_ref::<StepFunction<f64>>() {
+            Some(SignalDescription::Step {
+                pre_value: step.pre_value,
+                post_value: step.post_value,
+                step_time: step.step_time,
+            })
+        } else {
+            any.downcast_ref::<ImpulseFunction<f64>>().map(|impulse| SignalDescription::Impulse {
+                out_value: impulse.out_value,
+                in_value: impulse.in_value,
+                start_time: impulse.start_time,
+                duration: impulse.duration,
+            })
+        }
+    }
+
+    pub fn time_signal(&self) -> BoxedTimeSignal<f64> {
+        match *self {
+            SignalDescription::Step { pre_value, post_value, step_time } => {
+                Box::new(StepFunction::<f64> { pre_value, post_value, step_time })
+            }
+            SignalDescription::Impulse { out_value, in_value, start_time, duration } => {
+                Box::new(ImpulseFunction::<f64> { out_value, in_value, start_time, duration })
+            }
+        }
+    }
+}
+
+/// Plant element by its parameters, time constants in ms
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ElementDescription {
+    PT0 { kp: f64, t0_time: f64 },
+    PT1 { kp: f64, t1_time: f64 },
+    PT2 { kp: f64, t1_time: f64, damping: f64 },
+}
+
+impl ElementDescription {
+    /// The parameters of the element, [`None`] for element types without description
+    pub fn from_element(element: &BoxedTransferTimeDomain<f64>) -> Option<Self> {
+        let any = element.as_any();
+        if let Some(pt0) = any.downcast_ref::<PT0<f64>>() {
+            Some(ElementDescription::PT0 { kp: pt0.kp, t0_time: pt0.t0_time })
+        } else if let Some(pt1) = any.downcast_ref::<PT1<f64>>() {
+            Some(ElementDescription::PT1 { kp: pt1.kp, t1_time: pt1.t1_time })
+        } else {
+            any.downcast_ref::<PT2<f64>>().map(|pt2| ElementDescription::PT2 {
+                kp: pt2.kp,
+                t1_time: 1.0 / pt2.omega,
+                damping: pt2.damping,
+            })
+        }
+    }
+
+    /// The element discretized with the sampling interval of the simulation
+    pub fn element(&self, sample_time: f64) -> BoxedTransferTimeDomain<f64> {
+        match *self {
+            ElementDescription::PT0 { kp, t0_time } => Box::new(
+                PT0::<f64>::default()
+                    .set_sample_time_or_default(sample_time)
+                    .set_t0_time_or_default(t0_time)
+                    .set_kp(kp),
+            ),
+            ElementDescription::PT1 { kp, t1_time } => Box::new(
+                PT1::<f64>::default()
+                    .set_sample_time_or_default(sample_time)
+                    .set_t1_time_or_default(t1_time)
+                    .set_kp(kp),
+            ),
+            ElementDescription::PT2 { kp, t1_time, damping } => Box::new(
+                PT2::<f64>::default()
+                    .set_sample_time_or_default(sample_time)
+                    .set_t1_time_or_default(t1_time)
+                    .set_damping_or_default(damping)
+                    .set_kp(kp),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedSignalDescription {
+    pub name: String,
+    #[serde(flatten)]
+    pub signal: SignalDescription,
+}
+
+impl NamedSignalDescription {
+    pub fn from_signal(signal: &NamedTimeSignal<f64>) -> Result<Self, ProjectError> {
+        let description = SignalDescription::from_time_signal(&signal.signal)
+            .ok_or_else(|| ProjectError::UnsupportedSignal(signal.name.clone()))?;
+        Ok(NamedSignalDescription { name: signal.name.clone(), signal: description })
+    }
+
+    pub fn named_signal(&self) -> NamedTimeSignal<f64> {
+        NamedTimeSignal::default().set_name(self.name.clone()).set_signal(self.signal.time_signal())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedElementDescription {
+    pub name: String,
+    #[serde(flatten)]
+    pub element: ElementDescription,
+}
+
+impl NamedElementDescription {
+    pub fn from_element(element: &NamedElement<f64>) -> Result<Self, ProjectError> {
+        let description = ElementDescription::from_element(&element.element)
+            .ok_or_else(|| ProjectError::UnsupportedElement(element.name.clone()))?;
+        Ok(NamedElementDescription { name: element.name.clone(), element: description })
+    }
+
+    pub fn named_element(&self, sample_time: f64) -> NamedElement<f64> {
+        NamedElement::default().set_name(self.name.clone()).set_element(self.element.element(sample_time))
+    }
+}
+
+/// Setpoint range of the PID, outside of it the PID returns the off-band output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetpointRangeDescription {
+    pub minimum: f64,
+    pub maximum: f64,
+    pub off_band_output: f64,
+}
+
+/// Smith predictor with its internal model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmithPredictorDescription {
+    pub model: NamedElementDescription,
+    /// Dead time of the model [ms]
+    pub dead_time: f64,
+}
+
+/// State feedback with observer for the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateFeedbackDescription {
+    pub model: NamedElementDescription,
+    pub gain_design: GainDesign,
+    pub observer: ObserverDesign,
+}
+
+/// Model predictive controller, see [`MpcSetup`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MpcDescription {
+    pub model: NamedElementDescription,
+    pub prediction_horizon: usize,
+    pub control_horizon: usize,
+    pub output_weight: f64,
+    pub rate_weight: f64,
+    #[serde(default)]
+    pub input_limit: Option<(f64, f64)>,
+    #[serde(default)]
+    pub rate_limit: Option<f64>,
+}
+
+/// PID controller in parallel form
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControllerDescription {
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+    /// minimum and maximum
+    pub output_limit: Option<(f64, f64)>,
+    pub setpoint_range: Option<SetpointRangeDescription>,
+    pub dead_band: Option<f64>,
+    pub options: PidOptions,
+    pub smith_predictor: Option<SmithPredictorDescription>,
+    pub gain_schedule: Option<GainScheduleSetup>,
+    pub state_feedback: Option<StateFeedbackDescription>,
+    pub mpc: Option<MpcDescription>,
+}
+
+impl ControllerDescription {
+    pub fn from_pid_setup(setup: &PidSetup) -> Result<Self, ProjectError> {
+        Ok(ControllerDescription {
+            kp: setup.core.kp,
+            ki: setup.core.get_ki(),
+            kd: setup.core.get_kd(),
+            output_limit: setup.output_limit.as_ref().map(|l| (l.minimum(), l.maximum())),
+            setpoint_range: setup.setpoint_range.as_ref().map(|r| SetpointRangeDescription {
+                minimum: r.minimum(),
+                maximum: r.maximum(),
+                off_band_output: r.off_band_output,
+            }),
+            dead_band: setup.dead_band,
+            options: setup.options.clone(),
+            smith_predictor: setup
+                .smith_predictor
+                .as_ref()
+                .map(|smith| -> Result<_, ProjectError> {
+                    Ok(SmithPredictorDescription {
+                        model: NamedElementDescription::from_element(&smith.model)?,
+                        dead_time: smith.dead_time,
+                    })
+                })
+                .transpose()?,
+            gain_schedule: setup.gain_schedule.clone(),
+            state_feedback: setup
+                .state_feedback
+                .as_ref()
+                .map(|feedback| -> Result<_, ProjectError> {
+                    Ok(StateFeedbackDescription {
+                        model: NamedElementDescription::from_element(&feedback.model)?,
+                        gain_design: feedback.gain_design,
+                        observer: feedback.observer,
+                    })
+                })
+                .transpose()?,
+            mpc: setup
+                .mpc
+                .as_ref()
+                .map(|mpc| -> Result<_, ProjectError> {
+                    Ok(MpcDescription {
+                        model: NamedElementDescription::from_element(&mpc.model)?,
+                        prediction_horizon: mpc.prediction_horizon,
+                        control_horizon: mpc.control_horizon,
+                        output_weight: mpc.output_weight,
+                        rate_weight: mpc.rate_weight,
+                        input_limit: mpc.input_limit,
+                        rate_limit: mpc.rate_limit,
+                    })
+                })
+                .transpose()?,
+        })
+    }
+
+    /// The controller, internal models are discretized with the sampling interval of the simulation
+    pub fn pid_setup(&self, sample_time: f64) -> PidSetup {
+        PidSetup::default()
+            .set_core(PidCoreBuilder::<f64>::default().kp(self.kp).ki(self.ki).kd(self.kd))
+            .set_output_limit(
+                self.output_limit
+                    .map(|(minimum, maximum)| PidOutputLimit::default().range(minimum, maximum)),
+            )
+            .set_setpoint_range(self.setpoint_range.as_ref().map(|r| {
+                PidSetpointRange::default()
+                    .range(r.minimum, r.maximum)
+                    .out_of_band_output(r.off_band_output)
+            }))
+            .set_dead_band(self.dead_band)
+            .set_options(self.options.clone())
+            .set_smith_predictor(self.smith_predictor.as_ref().map(|smith| SmithPredictorSetup {
+                model: smith.model.named_element(sample_time),
+                dead_time: smith.dead_time,
+            }))
+            .set_gain_schedule(self.gain_schedule.clone())
+            .set_state_feedback(self.state_feedback.as_ref().map(|feedback| StateFeedbackSetup {
+                model: feedback.model.named_element(sample_time),
+                gain_design: feedback.gain_design,
+                observer: feedback.observer,
+            }))
+            .set_mpc(self.mpc.as_ref().map(|mpc| MpcSetup {
+                model: mpc.model.named_element(sample_time),
+                prediction_horizon: mpc.prediction_horizon,
+                control_horizon: mpc.control_horizon,
+                output_weight: mpc.output_weight,
+                rate_weight: mpc.rate_weight,
+                input_limit: mpc.input_limit,
+                rate_limit: mpc.rate_limit,
+            }))
+    }
+}
+
+impl Default for ControllerDescription {
+    fn default() -> Self {
+        ControllerDescription {
+            kp: 1.0,
+            ki: 0.0,
+            kd: 0.0,
+            output_limit: None,
+            setpoint_range: None,
+            dead_band: None,
+            options: PidOptions::default(),
+            smith_predictor: None,
+            gain_schedule: None,
+            state_feedback: None,
+            mpc: None,
+        }
+    }
+}
+
+fn default_controller_ratio() -> usize {
+    1
+}
+
+/// Control loop as stored in a project file.
+///
+/// Signals and elements are defined once and referenced by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub range: RangeDescription,
+    pub signals: Vec<NamedSignalDescription>,
+    pub elements: Vec<NamedElementDescription>,
+    /// Name of the setpoint signal
+    pub setpoint: String,
+    /// Name of the plant element
+    pub element: String,
+    #[serde(default)]
+    pub controller: ControllerDescription,
+    /// Process replacing the controller, see `crate::control::external`; native builds with `cli` only
+    #[cfg(feature = "cli")]
+    #[serde(default)]
+    pub external_controller: Option<ExternalControllerSetup>,
+    #[serde(default)]
+    pub open_loop: bool,
+    /// Names of the optional signals
+    #[serde(default)]
+    pub disturbance: Option<String>,
+    #[serde(default)]
+    pub output_disturbance: Option<String>,
+    #[serde(default)]
+    pub noise: Option<String>,
+    #[serde(default)]
+    pub manual: Option<String>,
+    #[serde(default)]
+    pub scheduling: Option<String>,
+    #[serde(default)]
+    pub feedforward: FeedforwardSetup,
+    /// Dead time of the plant [ms]
+    #[serde(default)]
+    pub plant_dead_time: f64,
+    #[serde(default = "default_controller_ratio")]
+    pub controller_ratio: usize,
+    #[serde(default)]
+    pub timing: TimingSetup,
+    #[serde(default)]
+    pub actuator: Option<ActuatorSetup>,
+    #[serde(default)]
+    pub sensor: Option<SensorSetup>,
+    #[serde(default)]
+    pub mode_switch: ModeSwitchSetup,
+    /// Records the integrator state of the PID
+    #[serde(default)]
+    pub record_integral: bool,
+}
+
+/// Errors resolving the names of a [`Project`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProjectError {
+    UnknownSignal(String),
+    UnknownElement(String),
+    /// Signal type without description
+    UnsupportedSignal(String),
+    /// Element type without description
+    UnsupportedElement(String),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectError::UnknownSignal(name) => write!(f, "unknown signal '{}'", name),
+            ProjectError::UnknownElement(name) => write!(f, "unknown element '{}'", name),
+            ProjectError::UnsupportedSignal(name) => write!(f, "signal '{}' has no description", name),
+            ProjectError::UnsupportedElement(name) => write!(f, "element '{}' has no description", name),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+impl Project {
+    /// Describes a loop configured in the web UI, fails for signals and elements without description
+    pub fn from_loop_setup(setup: &LoopSetup) -> Result<Self, ProjectError> {
+        let mut project = Project {
+            range: RangeDescription::from_time_range(&setup.range),
+            signals: Vec::new(),
+            elements: vec![NamedElementDescription::from_element(&setup.element)?],
+            setpoint: String::new(),
+            element: setup.element.name.clone(),
+            controller: ControllerDescription::from_pid_setup(&setup.controller)?,
+            #[cfg(feature = "cli")]
+            external_controller: setup.external_controller.clone(),
+            open_loop: setup.open_loop,
+            disturbance: None,
+            output_disturbance: None,
+            noise: None,
+            manual: None,
+            scheduling: None,
+            feedforward: setup.feedforward.clone(),
+            plant_dead_time: setup.plant_dead_time,
+            controller_ratio: setup.controller_ratio,
+            timing: setup.timing.clone(),
+            actuator: setup.actuator.clone(),
+            sensor: setup.sensor.clone(),
+            mode_switch: setup.mode_switch.clone(),
+            record_integral: setup.record_integral,
+        };
+        project.setpoint = project.add_signal(&setup.setpoint)?;
+        project.disturbance = project.add_optional_signal(&setup.disturbance)?;
+        project.output_disturbance = project.add_optional_signal(&setup.output_disturbance)?;
+        project.noise = project.add_optional_signal(&setup.noise)?;
+        project.manual = project.add_optional_signal(&setup.manual)?;
+        project.scheduling = project.add_optional_signal(&setup.scheduling)?;
+        Ok(project)
+    }
+
+    /// Adds the signal unless already there, returns the name to reference it,
+    /// which is made unique if different signals share a name
+    fn add_signal(&mut self, signal: &NamedTimeSignal<f64>) -> Result<String, ProjectError> {
+        let mut description = NamedSignalDescription::from_signal(signal)?;
+        let mut index = 1;
+        while let Some(existing) = self.signals.iter().find(|s| s.name == description.name) {
+            if existing.signal == description.signal {
+                return Ok(description.name);
+            }
+            index += 1;
+            description.name = format!("{} ({})", signal.name, index);
+        }
+        let name = description.name.clone();
+        self.signals.push(description);
+        Ok(name)
+    }
+
+    fn add_optional_signal(&mut self, signal: &Option<NamedTimeSignal<f64>>) -> Result<Option<String>, ProjectError> {
+        signal.as_ref().map(|s| self.add_signal(s)).transpose()
+    }
+
+    fn signal(&self, name: &str) -> Result<NamedTimeSignal<f64>, ProjectError> {
+        self.signals
+            .iter()
+            .find(|s| s.name == name)
+            .map(NamedSignalDescription::named_signal)
+            .ok_or_else(|| ProjectError::UnknownSignal(name.to_owned()))
+    }
+
+    fn optional_signal(&self, name: &Option<String>) -> Result<Option<NamedTimeSignal<f64>>, ProjectError> {
+        name.as_deref().map(|n| self.signal(n)).transpose()
+    }
+
+    /// The loop to simulate, the same as configured in the web UI
+    pub fn loop_setup(&self) -> Result<LoopSetup, ProjectError> {
+        let element = self
+            .elements
+            .iter()
+            .find(|e| e.name == self.element)
+            .map(|e| e.named_element(self.range.sampling_interval))
+            .ok_or_else(|| ProjectError::UnknownElement(self.element.clone()))?;
+        let setup = LoopSetup::default()
+            .set_range(self.range.time_range())
+            .set_setpoint(self.signal(&self.setpoint)?)
+            .set_element(element)
+            .set_controller(self.controller.pid_setup(self.range.sampling_interval))
+            .set_open_loop(self.open_loop)
+            .set_disturbance(self.optional_signal(&self.disturbance)?)
+            .set_output_disturbance(self.optional_signal(&self.output_disturbance)?)
+            .set_noise(self.optional_signal(&self.noise)?)
+            .set_manual(self.optional_signal(&self.manual)?)
+            .set_scheduling(self.optional_signal(&self.scheduling)?)
+            .set_feedforward(self.feedforward.clone())
+            .set_plant_dead_time(self.plant_dead_time)
+            .set_controller_ratio(self.controller_ratio)
+            .set_timing(self.timing.clone())
+            .set_actuator(self.actuator.clone())
+            .set_sensor(self.sensor.clone())
+            .set_mode_switch(self.mode_switch.clone())
+            .set_record_integral(self.record_integral);
+        #[cfg(feature = "cli")]
+        let setup = setup.set_external_controller(self.external_controller.clone());
+        Ok(setup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PT2_PID: &str = include_str!("../../projects/pt2_pid.json");
+
+    fn pt2_pid() -> Project {
+        serde_json::from_str(PT2_PID).unwrap()
+    }
+
+    #[test]
+    fn example_project_parses() {
+        let project = pt2_pid();
+        assert_eq!(project.range, RangeDescription { start: 0.0, end: 200.0, sampling_interval: 1.0 });
+        assert_eq!(project.signals.len(), 2);
+        assert_eq!(project.elements[0].element, ElementDescription::PT2 { kp: 2.0, t1_time: 10.0, damping: 0.7 });
+        assert_eq!((project.setpoint.as_str(), project.element.as_str()), ("Setpoint", "Plant"));
+        assert_eq!(project.disturbance.as_deref(), Some("Load"));
+        assert_eq!((project.controller.kp, project.controller.ki, project.controller.kd), (0.8, 0.05, 2.0));
+        assert_eq!(project.controller.output_limit, Some((-5.0, 5.0)));
+        assert_eq!(project.controller.options.derivative_filter, Some(10.0));
+        // omitted fields take their defaults
+        assert_eq!(project.controller_ratio, 1);
+        assert!(!project.open_loop && project.manual.is_none() && project.actuator.is_none());
+
+        let setup = project.loop_setup().unwrap();
+        assert_eq!(setup.setpoint.name, "Setpoint");
+        assert_eq!(setup.element.name, "Plant");
+        assert!(setup.disturbance.is_some() && setup.noise.is_none());
+    }
+
+    #[test]
+    fn unknown_names_are_reported() {
+        let mut project = pt2_pid();
+        project.disturbance = Some("Missing".to_owned());
+        assert_eq!(project.loop_setup().err(), Some(ProjectError::UnknownSignal("Missing".to_owned())));
+        project.element = "Missing".to_owned();
+        assert_eq!(project.loop_setup().err(), Some(ProjectError::UnknownElement("Missing".to_owned())));
+    }
+
+    #[test]
+    fn project_round_trips_through_the_loop_setup() {
+        let mut project = pt2_pid();
+        project.signals.push(NamedSignalDescription {
+            name: "Noise".to_owned(),
+            signal: SignalDescription::Impulse { out_value: 0.0, in_value: 0.1, start_time: 50.0, duration: 5.0 },
+        });
+        project.noise = Some("Noise".to_owned());
+        project.controller_ratio = 2;
+        project.plant_dead_time = 3.0;
+        project.record_integral = true;
+
+        let round_trip = Project::from_loop_setup(&project.loop_setup().unwrap()).unwrap();
+        assert_eq!(round_trip, project);
+    }
+
+    #[test]
+    fn signals_sharing_a_name_are_kept_apart() {
+        let mut project = pt2_pid();
+        project.signals[1].name = "Setpoint 2".to_owned();
+        project.disturbance = Some("Setpoint 2".to_owned());
+        let mut setup = project.loop_setup().unwrap();
+        setup.disturbance = setup.disturbance.map(|d| d.set_name("Setpoint".to_owned()));
+
+        let round_trip = Project::from_loop_setup(&setup).unwrap();
+        assert_eq!(round_trip.setpoint, "Setpoint");
+        assert_eq!(round_trip.disturbance.as_deref(), Some("Setpoint (2)"));
+        assert_eq!(round_trip.signals[1].signal, project.signals[1].signal);
+    }
+}
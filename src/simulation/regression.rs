@@ -0,0 +1,377 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::simulation::engine::LoopResult;
+
+/// Deviations listed per signal in a [`Report`], the rest is only counted
+const LISTED_DEVIATIONS: usize = 5;
+
+/// Accepted deviation `|actual - reference| <= absolute + relative * |reference|`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Tolerance {
+    pub fn set_absolute(self, absolute: f64) -> Self {
+        Tolerance { absolute, ..self }
+    }
+
+    pub fn set_relative(self, relative: f64) -> Self {
+        Tolerance { relative, ..self }
+    }
+
+    pub fn accepts(&self, reference: f64, actual: f64) -> bool {
+        if reference.is_nan() || actual.is_nan() {
+            return reference.is_nan() && actual.is_nan();
+        }
+        (actual - reference).abs() <= self.absolute + self.relative * reference.abs()
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            absolute: 1e-9,
+            relative: 1e-6,
+        }
+    }
+}
+
+/// Sampled signals by name, as stored in a golden file
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Golden {
+    pub columns: Vec<(String, Vec<f64>)>,
+}
+
+/// Errors reading a golden file
+#[derive(Debug, Clone, PartialEq)]
+pub enum GoldenError {
+    Empty,
+    /// line number, starting at 1
+    RowLength(usize),
+    /// line number and the unparsable value
+    Value(usize, String),
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Empty => write!(f, "no header line"),
+            GoldenError::RowLength(line) => write!(f, "line {}: number of values differs from the header", line),
+            GoldenError::Value(line, value) => write!(f, "line {}: '{}' is not a number", line, value),
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+impl Golden {
+    pub fn from_result(result: &LoopResult) -> Self {
+        Golden {
+            columns: result
+                .columns()
+                .into_iter()
+                .map(|(name, values)| (name.to_owned(), values.to_vec()))
+                .collect(),
+        }
+    }
+
+    /// Reads the CSV written by [`LoopResult::to_csv`]
+    pub fn from_csv(text: &str) -> Result<Self, GoldenError> {
+        let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        let (_, header) = lines.next().ok_or(GoldenError::Empty)?;
+        let mut columns: Vec<(String, Vec<f64>)> =
+            header.split(',').map(|name| (name.trim().to_owned(), Vec::new())).collect();
+        for (index, line) in lines {
+            let values: Vec<&str> = line.split(',').collect();
+            if values.len() != columns.len() {
+                return Err(GoldenError::RowLength(index + 1));
+            }
+            for ((_, column), value) in columns.iter_mut().zip(values) {
+                let value = value.trim();
+                column.push(value.parse::<f64>().map_err(|_| GoldenError::Value(index + 1, value.to_owned()))?);
+            }
+        }
+        Ok(Golden { columns })
+    }
+
+    pub fn column(&self, name: &str) -> Option<&[f64]> {
+        self.columns.iter().find(|(n, _)| n == name).map(|(_, values)| values.as_slice())
+    }
+
+    /// Characteristic values of the response, [`None`] if a needed signal is missing
+    pub fn metrics(&self) -> Option<Metrics> {
+        let time = self.column("time")?;
+        let error = self.column("e")?;
+        let y = self.column("y")?;
+        let u = self.column("u")?;
+        let dt = if time.len() > 1 { time[1] - time[0] } else { 0.0 };
+        Some(Metrics {
+            iae: error.iter().map(|e| e.abs() * dt).sum(),
+            ise: error.iter().map(|e| e * e * dt).sum(),
+            peak_y: y.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            final_y: y.last().copied().unwrap_or_default(),
+            peak_u: u.iter().map(|u| u.abs()).fold(0.0, f64::max),
+            total_variation_u: u.windows(2).map(|w| (w[1] - w[0]).abs()).sum(),
+        })
+    }
+}
+
+/// Integral and peak values summarizing a closed-loop response
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// Integral of the absolute error
+    pub iae: f64,
+    /// Integral of the squared error
+    pub ise: f64,
+    pub peak_y: f64,
+    pub final_y: f64,
+    /// Largest magnitude of the control variable
+    pub peak_u: f64,
+    /// Sum of the control variable changes, a measure of actuator wear
+    pub total_variation_u: f64,
+}
+
+impl Metrics {
+    pub fn named(&self) -> [(&'static str, f64); 6] {
+        [
+            ("IAE", self.iae),
+            ("ISE", self.ise),
+            ("peak y", self.peak_y),
+            ("final y", self.final_y),
+            ("peak |u|", self.peak_u),
+            ("total variation u", self.total_variation_u),
+        ]
+    }
+}
+
+/// One sample out of tolerance
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deviation {
+    pub index: usize,
+    pub time: f64,
+    pub reference: f64,
+    pub actual: f64,
+}
+
+/// Samples out of tolerance of one signal
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalDeviation {
+    pub name: String,
+    pub deviations: Vec<Deviation>,
+    /// Largest absolute deviation
+    pub maximum: f64,
+}
+
+/// Result of comparing a run with its golden result
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub scenario: String,
+    pub tolerance: Tolerance,
+    /// Signals in only one of both results
+    pub missing: Vec<String>,
+    pub added: Vec<String>,
+    /// Number of samples of reference and actual run, if different
+    pub length: Option<(usize, usize)>,
+    pub signals: Vec<SignalDeviation>,
+    /// name, reference and actual value of the metrics out of tolerance
+    pub metrics: Vec<(&'static str, f64, f64)>,
+}
+
+impl Report {
+    /// Compares the signals sample by sample and the metrics derived from them
+    pub fn compare(scenario: &str, reference: &Golden, actual: &Golden, tolerance: Tolerance) -> Self {
+        let missing = reference
+            .columns
+            .iter()
+            .filter(|(name, _)| actual.column(name).is_none())
+            .map(|(name, _)| name.clone())
+            .collect();
+        let added = actual
+            .columns
+            .iter()
+            .filter(|(name, _)| reference.column(name).is_none())
+            .map(|(name, _)| name.clone())
+            .collect();
+        let reference_length = reference.columns.first().map_or(0, |(_, v)| v.len());
+        let actual_length = actual.columns.first().map_or(0, |(_, v)| v.len());
+        let length = (reference_length != actual_length).then_some((reference_length, actual_length));
+
+        let time = reference.column("time").unwrap_or_default();
+        let signals = reference
+            .columns
+            .iter()
+            .filter_map(|(name, expected)| {
+                let values = actual.column(name)?;
+                let deviations: Vec<Deviation> = expected
+                    .iter()
+                    .zip(values)
+                    .enumerate()
+                    .filter(|(_, (r, a))| !tolerance.accepts(**r, **a))
+                    .map(|(index, (r, a))| Deviation {
+                        index,
+                        time: time.get(index).copied().unwrap_or(index as f64),
+                        reference: *r,
+                        actual: *a,
+                    })
+                    .collect();
+                let maximum = deviations.iter().map(|d| (d.actual - d.reference).abs()).fold(0.0, f64::max);
+                (!deviations.is_empty()).then(|| SignalDeviation { name: name.clone(), deviations, maximum })
+            })
+            .collect();
+
+        let metrics = match (reference.metrics(), actual.metrics()) {
+            (Some(r), Some(a)) => r
+                .named()
+                .into_iter()
+                .zip(a.named())
+                .filter(|((_, r), (_, a))| !tolerance.accepts(*r, *a))
+                .map(|((name, r), (_, a))| (name, r, a))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Report {
+            scenario: scenario.to_owned(),
+            tolerance,
+            missing,
+            added,
+            length,
+            signals,
+            metrics,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.missing.is_empty()
+            && self.added.is_empty()
+            && self.length.is_none()
+            && self.signals.is_empty()
+            && self.metrics.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.passed() {
+            return writeln!(f, "{}: ok", self.scenario);
+        }
+        writeln!(
+            f,
+            "{}: FAILED (tolerance absolute {}, relative {})",
+            self.scenario, self.tolerance.absolute, self.tolerance.relative
+        )?;
+        if !self.missing.is_empty() {
+            writeln!(f, "  missing signals: {}", self.missing.join(", "))?;
+        }
+        if !self.added.is_empty() {
+            writeln!(f, "  new signals: {}", self.added.join(", "))?;
+        }
+        if let Some((reference, actual)) = self.length {
+            writeln!(f, "  samples: {} expected, {} actual", reference, actual)?;
+        }
+        for (name, reference, actual) in &self.metrics {
+            writeln!(f, "  metric {}: {} expected, {} actual", name, reference, actual)?;
+        }
+        for signal in &self.signals {
+            writeln!(
+                f,
+                "  signal {}: {} samples deviate, at most by {}",
+                signal.name,
+                signal.deviations.len(),
+                signal.maximum
+            )?;
+            for d in signal.deviations.iter().take(LISTED_DEVIATIONS) {
+                writeln!(
+                    f,
+                    "    [{}] t = {}: {} expected, {} actual",
+                    d.index, d.time, d.reference, d.actual
+                )?;
+            }
+            if signal.deviations.len() > LISTED_DEVIATIONS {
+                writeln!(f, "    ...")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn golden(columns: &[(&str, &[f64])]) -> Golden {
+        Golden {
+            columns: columns.iter().map(|(name, values)| (name.to_string(), values.to_vec())).collect(),
+        }
+    }
+
+    #[test]
+    fn tolerance_is_absolute_plus_relative() {
+        let tolerance = Tolerance::default().set_absolute(0.1).set_relative(0.01);
+        assert!(tolerance.accepts(1.0, 1.1));
+        assert!(tolerance.accepts(100.0, 98.95));
+        assert!(!tolerance.accepts(1.0, 1.2));
+        assert!(!tolerance.accepts(100.0, 101.2));
+    }
+
+    #[test]
+    fn tolerance_accepts_nan_only_against_nan() {
+        let tolerance = Tolerance::default().set_absolute(f64::INFINITY);
+        assert!(tolerance.accepts(f64::NAN, f64::NAN));
+        assert!(!tolerance.accepts(f64::NAN, 0.0));
+        assert!(!tolerance.accepts(0.0, f64::NAN));
+    }
+
+    #[test]
+    fn golden_from_csv() {
+        let text = "time,y\n0,1.5\n\n1,NaN\n";
+        let parsed = Golden::from_csv(text).unwrap();
+        assert_eq!(parsed.column("time"), Some(&[0.0, 1.0][..]));
+        assert!(parsed.column("y").unwrap()[1].is_nan());
+        assert_eq!(parsed.column("u"), None);
+    }
+
+    #[test]
+    fn golden_from_invalid_csv() {
+        assert_eq!(Golden::from_csv(""), Err(GoldenError::Empty));
+        assert_eq!(Golden::from_csv("time,y\n0,1\n1\n"), Err(GoldenError::RowLength(3)));
+        assert_eq!(Golden::from_csv("time,y\n0,x\n"), Err(GoldenError::Value(2, "x".to_owned())));
+    }
+
+    #[test]
+    fn report_of_equal_results_passes() {
+        let reference = golden(&[("time", &[0.0, 1.0]), ("y", &[0.5, f64::NAN])]);
+        let report = Report::compare("equal", &reference, &reference.clone(), Tolerance::default());
+        assert!(report.passed(), "{}", report);
+    }
+
+    #[test]
+    fn report_lists_deviating_samples() {
+        let reference = golden(&[("time", &[0.0, 1.0, 2.0]), ("y", &[0.0, 1.0, 2.0])]);
+        let actual = golden(&[("time", &[0.0, 1.0, 2.0]), ("y", &[0.0, f64::NAN, 2.5])]);
+        let report = Report::compare("deviating", &reference, &actual, Tolerance::default());
+        assert!(!report.passed());
+        assert_eq!(report.signals.len(), 1);
+        let indices: Vec<usize> = report.signals[0].deviations.iter().map(|d| d.index).collect();
+        assert_eq!(indices, vec![1, 2]);
+        assert_eq!(report.signals[0].deviations[1].time, 2.0);
+    }
+
+    #[test]
+    fn report_of_different_shapes() {
+        let reference = golden(&[("time", &[0.0, 1.0, 2.0]), ("y", &[0.0, 1.0, 2.0]), ("I", &[0.0; 3])]);
+        let actual = golden(&[("time", &[0.0, 1.0]), ("y", &[0.0, 1.0]), ("u", &[0.0; 2])]);
+        let report = Report::compare("shapes", &reference, &actual, Tolerance::default());
+        assert!(!report.passed());
+        assert_eq!(report.missing, vec!["I".to_owned()]);
+        assert_eq!(report.added, vec!["u".to_owned()]);
+        assert_eq!(report.length, Some((3, 2)));
+        // the common samples agree
+        assert!(report.signals.is_empty());
+    }
+}
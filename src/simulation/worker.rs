@@ -0,0 +1,141 @@
+//! Web Worker computing the simulations off the UI thread.
+//!
+//! One worker serves all plots of the app, each plot connects with a bridge of its own.
+//! A plot sends [`WorkerInput::Run`] and receives [`WorkerOutput::Progress`] until
+//! [`WorkerOutput::Done`]. The job is computed in chunks, in between new messages are
+//! handled: a new run of the same plot replaces the running one, [`WorkerInput::Cancel`]
+//! drops it.
+use std::collections::HashMap;
+
+use gloo::timers::future::TimeoutFuture;
+use gloo::worker::{HandlerId, Worker, WorkerScope};
+use serde::{Deserialize, Serialize};
+
+use crate::simulation::job::{JobRun, SimulationJob, SimulationResult};
+
+/// Loader of the worker script built by trunk, see `index.html`
+pub const WORKER_PATH: &str = "/cb-simulator-yew/simulation_worker_loader.js";
+
+/// Time steps computed before the worker handles its messages again
+const CHUNK_STEPS: usize = 5000;
+
+/// The binary encoding between page and worker does not support the tagged enums of
+/// the descriptions, therefore the job is embedded as JSON
+mod json {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::simulation::job::SimulationJob;
+
+    pub fn serialize<S: Serializer>(job: &SimulationJob, serializer: S) -> Result<S::Ok, S::Error> {
+        let text = serde_json::to_string(job).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&text)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SimulationJob, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        serde_json::from_str(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WorkerInput {
+    /// The generation is returned with the outputs to tell them from those of replaced runs
+    Run {
+        generation: u64,
+        #[serde(with = "json")]
+        job: SimulationJob,
+    },
+    Cancel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WorkerOutput {
+    /// Fraction of the computed time steps
+    Progress { generation: u64, fraction: f64 },
+    /// The result or why the job could not be started
+    Done {
+        generation: u64,
+        result: Result<SimulationResult, String>,
+    },
+}
+
+pub enum WorkerMessage {
+    /// Computes the next chunk of all running jobs
+    Continue,
+}
+
+/// Running jobs, at most one per connected plot
+pub struct SimulationWorker {
+    runs: HashMap<HandlerId, (u64, JobRun)>,
+    /// A [`WorkerMessage::Continue`] is pending
+    scheduled: bool,
+}
+
+impl SimulationWorker {
+    fn schedule(&mut self, scope: &WorkerScope<Self>) {
+        if !self.scheduled && !self.runs.is_empty() {
+            self.scheduled = true;
+            // yields to the message queue, so runs can be replaced or cancelled
+            scope.send_future(async {
+                TimeoutFuture::new(0).await;
+                WorkerMessage::Continue
+            });
+        }
+    }
+}
+
+impl Worker for SimulationWorker {
+    type Message = WorkerMessage;
+    type Input = WorkerInput;
+    type Output = WorkerOutput;
+
+    fn create(_scope: &WorkerScope<Self>) -> Self {
+        SimulationWorker {
+            runs: HashMap::new(),
+            scheduled: false,
+        }
+    }
+
+    fn update(&mut self, scope: &WorkerScope<Self>, msg: Self::Message) {
+        match msg {
+            WorkerMessage::Continue => {
+                self.scheduled = false;
+                let ids: Vec<HandlerId> = self.runs.keys().copied().collect();
+                for id in ids {
+                    let Some((generation, run)) = self.runs.get_mut(&id) else { continue };
+                    let generation = *generation;
+                    if run.advance(CHUNK_STEPS) {
+                        if let Some((_, run)) = self.runs.remove(&id) {
+                            scope.respond(id, WorkerOutput::Done { generation, result: Ok(run.finish()) });
+                        }
+                    } else {
+                        scope.respond(id, WorkerOutput::Progress { generation, fraction: run.progress() });
+                    }
+                }
+                self.schedule(scope);
+            }
+        }
+    }
+
+    fn received(&mut self, scope: &WorkerScope<Self>, msg: Self::Input, id: HandlerId) {
+        match msg {
+            WorkerInput::Run { generation, job } => match job.start() {
+                Ok(run) => {
+                    self.runs.insert(id, (generation, run));
+                    self.schedule(scope);
+                }
+                Err(error) => {
+                    self.runs.remove(&id);
+                    scope.respond(id, WorkerOutput::Done { generation, result: Err(error.to_string()) });
+                }
+            },
+            WorkerInput::Cancel => {
+                self.runs.remove(&id);
+            }
+        }
+    }
+
+    fn disconnected(&mut self, _scope: &WorkerScope<Self>, id: HandlerId) {
+        self.runs.remove(&id);
+    }
+}
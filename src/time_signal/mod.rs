@@ -1,5 +1,8 @@
 pub mod named_time_signal;
+#[cfg(feature = "web")]
 pub mod register_all;
+#[cfg(feature = "web")]
 pub mod registry;
 
+#[cfg(feature = "web")]
 pub use register_all::register_build_in_time_signals;
@@ -0,0 +1,31 @@
+//! Software in the loop with the stand-in controller `cb-sil-pi`.
+use cb_simulator_yew::control::external::ExternalControllerSetup;
+use cb_simulator_yew::simulation::LoopSetup;
+
+fn external(program: &str, arguments: &[&str]) -> Option<ExternalControllerSetup> {
+    Some(
+        ExternalControllerSetup::default()
+            .set_program(program.to_owned())
+            .set_arguments(arguments.iter().map(|a| a.to_string()).collect()),
+    )
+}
+
+#[test]
+fn echo_controller_returns_the_control_error() {
+    let setup = LoopSetup::default().set_external_controller(external(env!("CARGO_BIN_EXE_cb-sil-pi"), &["1", "0"]));
+    let result = setup.simulate();
+    assert!(result.time.len() > 1);
+    // the output of a sample is applied at the next plant step
+    for i in 0..result.time.len() - 1 {
+        assert_eq!(result.u[i + 1], result.setpoint[i] - result.measured[i], "step {}", i);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn silent_controller_times_out_and_holds_the_output() {
+    // `sleep` neither answers nor ends when its stdin is closed
+    let setup = LoopSetup::default().set_external_controller(external("sleep", &["60"]));
+    let result = setup.simulate();
+    assert!(result.u.iter().all(|u| *u == 0.0));
+}